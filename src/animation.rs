@@ -0,0 +1,210 @@
+use std::io::Write;
+
+use anyhow::Result;
+use derive_new::new;
+use rand::prelude::*;
+
+use crate::{AntiAliasing, Camera, Canvas, Light, Output, World};
+
+/// A `Frame` holds the camera and lights used to render a single frame of an
+/// `Animation`, and optionally the time to pose any keyframed objects at.
+#[derive(Clone, Debug, new)]
+pub struct Frame {
+    camera: Camera,
+    lights: Vec<Light>,
+    #[new(default)]
+    time: Option<f64>,
+}
+
+impl Frame {
+    #[must_use]
+    pub const fn with_time(mut self, time: f64) -> Self {
+        self.time = Some(time);
+
+        self
+    }
+}
+
+/// An `Animation` renders a sequence of frames of a `World` where the camera
+/// and lights move and objects only move along their keyframes. The objects
+/// are divided into bounding volume hierarchies once when the animation is
+/// created and shared between all the frames.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    world: World,
+}
+
+impl Animation {
+    #[must_use]
+    pub fn new(mut world: World, threshold: u32) -> Self {
+        world.divide(threshold);
+
+        Self { world }
+    }
+
+    #[must_use]
+    pub const fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Render a single frame, the lights of the frame replace any lights that
+    /// are not in a group.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are problems writing status messages.
+    pub fn render_frame<O: Write, R: Rng>(
+        &mut self,
+        frame: &Frame,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Canvas> {
+        self.world.lights.clone_from(&frame.lights);
+
+        if let Some(time) = frame.time {
+            self.world.set_time(time);
+        }
+
+        frame.camera.render(
+            &self.world,
+            depth,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_3;
+
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{
+        math::{float::*, Angle, Point, Transformation, Vector},
+        Colour, Object,
+    };
+
+    fn rng() -> impl Rng {
+        Xoshiro256PlusPlus::seed_from_u64(0)
+    }
+
+    fn world() -> World {
+        let mut w = World::new();
+
+        let mut spheres = Vec::new();
+
+        for x in -2..=2 {
+            for z in -2..=2 {
+                spheres.push(
+                    Object::sphere_builder()
+                        .transformation(
+                            Transformation::new()
+                                .scale(0.4, 0.4, 0.4)
+                                .translate(f64::from(x), 0.0, f64::from(z)),
+                        )
+                        .build(),
+                );
+            }
+        }
+
+        w.add_object(Object::group_builder().set_objects(spheres).build());
+
+        w
+    }
+
+    fn camera(from: Point) -> Camera {
+        Camera::new(
+            20,
+            20,
+            Angle(FRAC_PI_3),
+            Transformation::view_transformation(
+                from,
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        )
+    }
+
+    #[test]
+    fn rendering_frames_with_a_shared_world() {
+        let mut a = Animation::new(world(), 4);
+
+        // The world is divided into a bounding volume hierarchy up front.
+        let groups = a.world().summary().groups();
+
+        assert!(groups > 1);
+
+        let divided = a.world().objects.clone();
+
+        // Rendering a frame must not divide the world again.
+        let assert_undivided = |a: &Animation| {
+            assert_eq!(a.world().objects.len(), divided.len());
+
+            for (lhs, rhs) in a.world().objects.iter().zip(&divided) {
+                assert_approx_eq!(lhs, rhs);
+            }
+        };
+
+        let f1 = Frame::new(
+            camera(Point::new(0.0, 5.0, -5.0)),
+            vec![Light::new_point(
+                Point::new(-10.0, 10.0, -10.0),
+                Colour::white(),
+            )],
+        );
+        let f2 = Frame::new(
+            camera(Point::new(5.0, 5.0, 0.0)),
+            vec![Light::new_point(
+                Point::new(10.0, 10.0, 10.0),
+                Colour::white(),
+            )],
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+
+        let c1 = a
+            .render_frame(&f1, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        assert_undivided(&a);
+
+        let c2 = a
+            .render_frame(&f2, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        assert_undivided(&a);
+        assert_eq!(a.world().summary().groups(), groups);
+
+        // Each frame should match rendering an undivided world from scratch.
+        for (frame, canvas) in [(&f1, &c1), (&f2, &c2)] {
+            let mut w = world();
+            w.lights.clone_from(&frame.lights);
+
+            let expected = frame
+                .camera
+                .render(&w, 5, true, AntiAliasing::None, &mut o, &mut rng())
+                .unwrap();
+
+            assert_eq!(w.summary().groups(), 1);
+
+            for y in 0..20 {
+                for x in 0..20 {
+                    assert_approx_eq!(
+                        canvas.get_pixel(x, y),
+                        expected.get_pixel(x, y)
+                    );
+                }
+            }
+        }
+
+        assert!((0..20).any(|y| (0..20)
+            .any(|x| approx_ne!(c1.get_pixel(x, y), c2.get_pixel(x, y)))));
+    }
+}
@@ -2,6 +2,7 @@ use clap::{
     builder::{styling::AnsiColor, Styles},
     Parser,
 };
+use raytracer::{ClampMode, RenderMode};
 
 const fn styles() -> Styles {
     Styles::styled()
@@ -11,7 +12,26 @@ const fn styles() -> Styles {
         .placeholder(AnsiColor::Cyan.on_default())
 }
 
+/// An `Exposure` is either a number of stops or `auto` to pick one from the
+/// lights in the scene, see `Scene::auto_exposure`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Exposure {
+    Auto,
+    Stops(f64),
+}
+
+fn parse_exposure(value: &str) -> Result<Exposure, String> {
+    if value.eq_ignore_ascii_case("auto") {
+        return Ok(Exposure::Auto);
+    }
+
+    value.parse().map(Exposure::Stops).map_err(|_| {
+        format!("expected a number of stops or 'auto', found '{value}'")
+    })
+}
+
 #[derive(Clone, Debug, Parser)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(author, version, about, styles = styles())]
 pub struct Arguments {
     /// Output file to write to
@@ -26,22 +46,80 @@ pub struct Arguments {
     #[arg(long, default_value = "false")]
     pub sphere_scene: bool,
 
+    /// Image, such as a PNG or PPM, seen behind the render wherever rays miss
+    /// everything, stretched to the output resolution
+    #[arg(long)]
+    pub backplate: Option<String>,
+
     /// Scale the width and height of the image by this value
     #[arg(long, default_value = "1.0")]
     pub scale: f64,
 
-    /// The number of reflection rays to produce
-    #[arg(long, default_value = "5")]
-    pub depth: u32,
+    /// The number of reflection rays to produce, overrides the scene setting
+    #[arg(long)]
+    pub depth: Option<u32>,
+
+    /// The number of samples along each axis of a pixel, overrides the scene
+    /// setting
+    #[arg(long)]
+    pub samples: Option<u32>,
+
+    /// How to bring colours into the displayable range (per-channel or
+    /// hue-preserving), overrides the scene setting
+    #[arg(long)]
+    pub tonemap: Option<ClampMode>,
+
+    /// The exposure in stops or 'auto' to pick one from the power of the
+    /// lights, overrides the scene setting
+    #[arg(long, allow_negative_numbers = true, value_parser = parse_exposure)]
+    pub exposure: Option<Exposure>,
+
+    /// What to render for each pixel (shaded, uv, matte, normals or
+    /// wireframe), overrides the scene setting
+    #[arg(long)]
+    pub mode: Option<RenderMode>,
 
     /// The seed to use when using random numbers
     #[arg[long]]
     pub seed: Option<u64>,
 
+    /// The seed to use when sampling within pixels, independent of --seed,
+    /// overrides the scene setting
+    #[arg(long)]
+    pub sampling_seed: Option<u64>,
+
     /// Run the rendering process with a single thread
     #[arg(long)]
     pub single_threaded: bool,
 
+    /// Supersample only those pixels that lie along detected edges
+    #[arg(long)]
+    pub edge_aa: bool,
+
+    /// Trace red, green and blue wavelengths separately through dispersive
+    /// materials
+    #[arg(long)]
+    pub spectral: bool,
+
+    /// Sample this many lights at each hit, chosen by how much they are likely
+    /// to contribute, rather than every light
+    #[arg(long)]
+    pub light_samples: Option<usize>,
+
+    /// Randomly continue reflections and refractions past the depth limit
+    /// rather than cutting them off
+    #[arg(long)]
+    pub russian_roulette: bool,
+
+    /// Warn about overlapping coplanar faces of different objects, which is
+    /// slow for large scenes
+    #[arg(long)]
+    pub check_coplanar: bool,
+
+    /// Limit the number of intersections kept for any single ray
+    #[arg(long)]
+    pub max_intersections: Option<usize>,
+
     /// Suppress program output
     #[arg(short, long)]
     pub quiet: bool,
@@ -1,6 +1,15 @@
-use std::{io::Write, iter::from_fn, time::Instant};
+use std::{
+    collections::HashMap,
+    io::Write,
+    iter::from_fn,
+    mem::discriminant,
+    ptr,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use indicatif::{
     HumanCount, HumanDuration, ParallelProgressIterator, ProgressBar,
     ProgressDrawTarget, ProgressFinish, ProgressIterator, ProgressStyle,
@@ -8,26 +17,215 @@ use indicatif::{
 use rand::prelude::*;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use rayon::prelude::*;
-use serde::{Deserialize, Deserializer};
+use serde::{de::Error, Deserialize, Deserializer};
+use typed_builder::{Optional, TypedBuilder};
 
 use crate::{
     math::{
-        float::impl_approx_eq, Angle, Point, Ray, Transformable,
-        Transformation, Vector,
+        float::{approx_eq, approx_ne, impl_approx_eq},
+        Angle, Point, Ray, Transformable, Transformation, Vector,
     },
-    Canvas, Colour, Output, World,
+    BoundingBox, Canvas, Colour, Output, World,
 };
 
-/// `Camera` holds all the data representing our view into the scene.
+/// The maximum difference allowed in any channel between neighbouring pixels
+/// before we consider the pixels to be on an edge.
+const EDGE_THRESHOLD: f64 = 0.1;
+
+/// The number of samples along each axis of a pixel when anti-aliasing edges.
+const EDGE_SAMPLES: u32 = 4;
+
+/// The width of the lines drawn by `RenderMode::Wireframe` when parsed, as a
+/// barycentric distance from each edge.
+const WIREFRAME_THICKNESS: f64 = 0.02;
+
+/// `AntiAliasing` selects whether a render makes a second pass over the image
+/// supersampling only those pixels that differ noticeably from their
+/// neighbours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AntiAliasing {
+    #[default]
+    None,
+    /// Supersample the pixels along edges with at least `EDGE_SAMPLES` along
+    /// each axis.
+    Edges,
+}
+
+/// `RenderMode` selects what is written to each pixel, the debug modes show
+/// information about the hit rather than the shaded colour.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum RenderMode {
+    #[default]
+    Shaded,
+    /// Show the u and v values of the hit in the red and green channels.
+    Uv,
+    /// Show the alpha of each pixel for compositing the shaded render, shadow
+    /// catchers are only as opaque as the shadows on them.
+    Matte,
+    /// Show the world space normal of the hit with each axis mapped from
+    /// -1.0..1.0 to 0.0..1.0 in the red, green and blue channels.
+    Normals,
+    /// Draw the edges of triangles in `colour` over the shaded render, hits
+    /// within `thickness` of an edge, measured as a barycentric coordinate,
+    /// are part of the edge.
+    Wireframe { colour: Colour, thickness: f64 },
+}
+
+impl PartialEq for RenderMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Wireframe { colour, thickness },
+                Self::Wireframe {
+                    colour: other_colour,
+                    thickness: other_thickness,
+                },
+            ) => {
+                approx_eq!(*colour, *other_colour)
+                    && approx_eq!(*thickness, *other_thickness)
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
+
+impl FromStr for RenderMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shaded" => Ok(Self::Shaded),
+            "uv" => Ok(Self::Uv),
+            "matte" => Ok(Self::Matte),
+            "normals" => Ok(Self::Normals),
+            "wireframe" => Ok(Self::Wireframe {
+                colour: Colour::black(),
+                thickness: WIREFRAME_THICKNESS,
+            }),
+            _ => bail!("Unknown render mode '{s}'"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RenderMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(Error::custom)
+    }
+}
+
+/// `RenderStats` records how long a render took and how much work it did,
+/// `rays` only counts the rays generated by the camera and not any secondary
+/// rays.
+#[derive(Clone, Copy, Debug)]
+pub struct RenderStats {
+    pixels: u64,
+    rays: u64,
+    duration: Duration,
+}
+
+impl RenderStats {
+    #[must_use]
+    pub const fn pixels(&self) -> u64 {
+        self.pixels
+    }
+
+    #[must_use]
+    pub const fn rays(&self) -> u64 {
+        self.rays
+    }
+
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    #[must_use]
+    pub fn pixels_per_second(&self) -> f64 {
+        self.per_second(self.pixels)
+    }
+
+    #[must_use]
+    pub fn rays_per_second(&self) -> f64 {
+        self.per_second(self.rays)
+    }
+
+    #[must_use]
+    fn per_second(&self, count: u64) -> f64 {
+        // Counts large enough to lose precision are not a concern.
+        #[allow(clippy::cast_precision_loss)]
+        let count = count as f64;
+
+        count / self.duration.as_secs_f64()
+    }
+}
+
+/// `Aovs` are the buffers written by `Camera::render_aovs` for compositing,
+/// each holding one value per pixel row by row. Every buffer is filled from the
+/// same primary ray so they line up exactly.
+#[derive(Clone, Debug)]
+pub struct Aovs {
+    /// The shaded image, rays that miss everything see the background.
+    pub beauty: Canvas,
+    /// The t value of the hit along the primary ray, infinite for misses.
+    pub depth: Vec<f64>,
+    /// The world space normal of the hit encoded as `RenderMode::Normals`
+    /// does, black for misses.
+    pub normal: Canvas,
+    /// The index of the top level object in the world that was hit.
+    pub object_id: Vec<Option<usize>>,
+    /// The light reaching the hit directly from the lights, black for misses.
+    pub direct: Canvas,
+    /// The light reaching the hit by reflection and refraction, black for
+    /// misses.
+    pub indirect: Canvas,
+}
+
+/// The values written to each buffer of `Aovs` for a single pixel.
 #[derive(Clone, Copy, Debug)]
+struct AovPixel {
+    beauty: Colour,
+    depth: f64,
+    normal: Colour,
+    object_id: Option<usize>,
+    direct: Colour,
+    indirect: Colour,
+}
+
+/// `Camera` holds all the data representing our view into the scene.
+#[derive(Clone, Copy, Debug, TypedBuilder)]
+#[builder(builder_type(name = _CameraBuilder))]
+#[builder(build_method(vis = "", name = _build))]
 pub struct Camera {
     horizontal_size: u32,
     vertical_size: u32,
     field_of_view: Angle,
+    #[builder(default = Transformation::new())]
+    transformation: Transformation,
+    #[builder(default = Transformation::new(), setter(skip))]
     inverse_transformation: Transformation,
+    #[builder(default = 0.0, setter(skip))]
     half_width: f64,
+    #[builder(default = 0.0, setter(skip))]
     half_height: f64,
+    #[builder(default = 0.0, setter(skip))]
     pixel_size: f64,
+    #[builder(default = 0.0)]
+    near: f64,
+    #[builder(default = f64::INFINITY)]
+    far: f64,
+    #[builder(default = 1)]
+    samples: u32,
+    #[builder(default)]
+    render_mode: RenderMode,
+    #[builder(default = None)]
+    sampling_seed: Option<u64>,
+    /// The colour seen by rays that miss everything when rendering through
+    /// this camera, taking precedence over the world's background.
+    #[builder(default = None)]
+    background: Option<Colour>,
 }
 
 impl Camera {
@@ -38,18 +236,59 @@ impl Camera {
         field_of_view: Angle,
         transformation: Transformation,
     ) -> Self {
-        let (half_width, half_height, pixel_size) =
-            Self::calculate(horizontal_size, vertical_size, field_of_view);
-
-        Self {
-            horizontal_size,
-            vertical_size,
-            field_of_view,
-            inverse_transformation: transformation.invert(),
-            half_width,
-            half_height,
-            pixel_size,
-        }
+        Self::builder()
+            .horizontal_size(horizontal_size)
+            .vertical_size(vertical_size)
+            .field_of_view(field_of_view)
+            .transformation(transformation)
+            .build()
+    }
+
+    /// Set the near and far clipping planes, primary rays only hit objects
+    /// between these distances from the camera measured along the direction
+    /// the camera is looking.
+    #[must_use]
+    pub const fn with_clip(mut self, near: f64, far: f64) -> Self {
+        self.near = near;
+        self.far = far;
+
+        self
+    }
+
+    /// Set the number of samples taken along each axis of every pixel, a value
+    /// of 1 gives a single ray through the centre of each pixel.
+    #[must_use]
+    pub const fn with_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn with_render_mode(mut self, render_mode: RenderMode) -> Self {
+        self.render_mode = render_mode;
+
+        self
+    }
+
+    /// Fix the seed used for sampling within pixels, see `sampling_rng`.
+    /// Without a seed it is drawn from the random number generator passed to
+    /// `render`.
+    #[must_use]
+    pub const fn with_sampling_seed(
+        mut self,
+        sampling_seed: Option<u64>,
+    ) -> Self {
+        self.sampling_seed = sampling_seed;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn with_background(mut self, background: Option<Colour>) -> Self {
+        self.background = background;
+
+        self
     }
 
     fn calculate(
@@ -96,8 +335,119 @@ impl Camera {
         self.vertical_size
     }
 
+    #[must_use]
+    pub const fn clip(&self) -> (f64, f64) {
+        (self.near, self.far)
+    }
+
+    #[must_use]
+    pub const fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    #[must_use]
+    pub const fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    #[must_use]
+    pub const fn sampling_seed(&self) -> Option<u64> {
+        self.sampling_seed
+    }
+
+    #[must_use]
+    pub const fn background(&self) -> Option<Colour> {
+        self.background
+    }
+
+    /// Check if any part of the bounding box could be seen by the camera, this
+    /// is conservative so boxes near the corners of the view may be reported
+    /// as visible when they are not. Boxes that are not finite are always
+    /// treated as visible.
+    #[must_use]
+    pub fn can_see(&self, bounding_box: &BoundingBox) -> bool {
+        if !bounding_box.is_finite() {
+            return true;
+        }
+
+        // In camera space the camera looks down -z, so depth is -z.
+        let corners: Vec<_> = bounding_box
+            .corners()
+            .iter()
+            .map(|corner| corner.apply(&self.transformation))
+            .collect();
+
+        let outside = |test: &dyn Fn(&Point) -> bool| corners.iter().all(test);
+
+        !(outside(&|corner| -corner.z < self.near)
+            || outside(&|corner| -corner.z > self.far)
+            || outside(&|corner| corner.x > -corner.z * self.half_width)
+            || outside(&|corner| corner.x < corner.z * self.half_width)
+            || outside(&|corner| corner.y > -corner.z * self.half_height)
+            || outside(&|corner| corner.y < corner.z * self.half_height))
+    }
+
+    /// The range of t values along a primary ray that lie between the
+    /// clipping planes.
+    #[must_use]
+    fn clipping_range(&self, ray: &Ray) -> (f64, f64) {
+        if approx_eq!(self.near, 0.0) && self.far.is_infinite() {
+            return (0.0, f64::INFINITY);
+        }
+
+        // The clipping planes are perpendicular to the view direction so
+        // convert distances along that direction to t values along the ray.
+        let forward = -Vector::z_axis().apply(&self.inverse_transformation);
+        let cos = ray.direction.dot(&forward.normalise());
+
+        (self.near / cos, self.far / cos)
+    }
+
+    /// Find the colour for a primary ray, only considering hits that lie
+    /// between the clipping planes.
+    #[must_use]
+    fn colour_for_ray<R: Rng>(
+        &self,
+        world: &World,
+        ray: &Ray,
+        background: Option<Colour>,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        let (minimum, maximum) = self.clipping_range(ray);
+
+        match self.render_mode {
+            RenderMode::Shaded => world.colour_at_with_background(
+                ray, minimum, maximum, depth, background, rng,
+            ),
+            RenderMode::Uv => world.u_v_at_within(ray, minimum, maximum),
+            RenderMode::Matte => {
+                world.matte_at_within(ray, minimum, maximum, rng)
+            }
+            RenderMode::Normals => {
+                world.normal_at_within(ray, minimum, maximum)
+            }
+            RenderMode::Wireframe { colour, thickness } => {
+                if world
+                    .edge_distance_within(ray, minimum, maximum)
+                    .is_some_and(|distance| distance < thickness)
+                {
+                    colour
+                } else {
+                    world.colour_at_with_background(
+                        ray, minimum, maximum, depth, background, rng,
+                    )
+                }
+            }
+        }
+    }
+
     /// Renders the given `World` using the given camera.
     ///
+    /// With `AntiAliasing::Edges` a second pass is made over the image which
+    /// supersamples only those pixels that differ noticeably from their
+    /// neighbours.
+    ///
     /// # Errors
     ///
     /// This function will return an error if it can't convert values or there
@@ -107,63 +457,302 @@ impl Camera {
         world: &World,
         depth: u32,
         single_threaded: bool,
+        anti_aliasing: AntiAliasing,
         output: &mut Output<O>,
         rng: &mut R,
     ) -> Result<Canvas> {
-        writeln!(
+        self.render_with_stats(
+            world,
+            depth,
+            single_threaded,
+            anti_aliasing,
             output,
-            "Size {} by {}, field of view {:.1} degrees",
-            HumanCount(self.horizontal_size.into()),
-            HumanCount(self.vertical_size.into()),
-            self.field_of_view.to_degrees()
-        )?;
+            rng,
+        )
+        .map(|(canvas, _)| canvas)
+    }
 
-        writeln!(output, "Rendering scene...")?;
+    /// Renders the given `World` lit by only the light at `light`, in the
+    /// order given by `World::lights`, with rays that miss everything seeing
+    /// black. With `light` set to `None` no lights are used so only what rays
+    /// that miss everything see is rendered. Summing the passes for every
+    /// light along with the `None` pass gives the full render, provided the
+    /// lights are not sampled at random. Edges are not anti-aliased as the
+    /// edges found would differ between passes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it can't convert values or there
+    /// is an error writing output.
+    pub fn render_light_pass<O: Write, R: Rng>(
+        &self,
+        world: &World,
+        light: Option<usize>,
+        depth: u32,
+        single_threaded: bool,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Canvas> {
+        let mut world = world.clone();
 
-        let bar = ProgressBar::new(self.vertical_size.into())
-            .with_style(
-                ProgressStyle::with_template(
-                    "\
-{prefix} {bar:40.cyan/blue} {human_pos:>7}/{human_len:7} ({percent}%)
-Elapsed: {elapsed}, remaining: {eta}, rows/sec: {per_sec}",
-                )?
-                .progress_chars("#>-"),
-            )
-            .with_prefix("Rows")
-            .with_finish(ProgressFinish::AndClear);
+        world.isolate_light(light);
 
-        bar.set_draw_target(if output.is_sink() {
-            ProgressDrawTarget::hidden()
+        let camera = if light.is_some() {
+            self.with_background(Some(Colour::black()))
         } else {
-            ProgressDrawTarget::stdout()
-        });
+            *self
+        };
 
-        let started = Instant::now();
+        camera.render(
+            &world,
+            depth,
+            single_threaded,
+            AntiAliasing::None,
+            output,
+            rng,
+        )
+    }
 
-        let iterator_fn = |(y, seed)| {
-            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    /// Renders the given `World` as `render` does, also returning how long
+    /// the render took and how many pixels and rays were rendered.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it can't convert values or there
+    /// is an error writing output.
+    pub fn render_with_stats<O: Write, R: Rng>(
+        &self,
+        world: &World,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<(Canvas, RenderStats)> {
+        self.render_to_canvas(
+            world,
+            None,
+            depth,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+        )
+    }
 
-            let mut colours = Vec::with_capacity(self.vertical_size as usize);
+    /// Renders the given `World` as `render` does but with `backplate` seen
+    /// wherever rays miss everything in place of the background. The
+    /// backplate is stretched to cover the whole image whatever its size.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it can't convert values or there
+    /// is an error writing output.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_with_backplate<O: Write, R: Rng>(
+        &self,
+        world: &World,
+        backplate: &Canvas,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Canvas> {
+        self.render_to_canvas(
+            world,
+            Some(backplate),
+            depth,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+        )
+        .map(|(canvas, _)| canvas)
+    }
 
-            for x in 0..self.horizontal_size {
-                let ray = self.ray_for_pixel(x, y);
+    #[allow(clippy::too_many_arguments)]
+    fn render_to_canvas<O: Write, R: Rng>(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<(Canvas, RenderStats)> {
+        let width = self.horizontal_size;
 
-                let colour = world.colour_at(&ray, depth, &mut rng);
+        let mut pixels =
+            vec![Colour::black(); (width * self.vertical_size) as usize];
 
-                colours.push(colour);
-            }
+        let stats = self.render_pixels(
+            world,
+            backplate,
+            depth,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+            |x, y, colour| pixels[(y * width + x) as usize] = colour,
+        )?;
 
-            colours
-        };
+        Ok((
+            Canvas::with_vec(self.horizontal_size, self.vertical_size, pixels),
+            stats,
+        ))
+    }
+
+    /// Renders the given `World`, passing each pixel to `callback` as soon as
+    /// its colour is known rather than collecting them into a `Canvas`. Pixels
+    /// are passed row by row but when rendering multi threaded the rows arrive
+    /// in no particular order. With `AntiAliasing::Edges`, pixels along edges
+    /// are passed a second time once they have been supersampled. Returns the
+    /// statistics for the render.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it can't convert values or there
+    /// is an error writing output.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_with_callback<O, R, F>(
+        &self,
+        world: &World,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+        callback: F,
+    ) -> Result<RenderStats>
+    where
+        O: Write,
+        R: Rng,
+        F: FnMut(u32, u32, Colour) + Send,
+    {
+        self.render_pixels(
+            world,
+            None,
+            depth,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+            callback,
+        )
+    }
+
+    /// Renders the given `World` as `render_with_backplate` does, or as
+    /// `render` does when there is no `backplate`, but single threaded and
+    /// without edge anti-aliasing. Nothing is written to any output and the
+    /// render is neither timed nor shows a progress bar, so it can run where
+    /// there are no threads or clock such as WebAssembly in a browser.
+    #[must_use]
+    pub fn render_quietly<R: Rng>(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        depth: u32,
+        rng: &mut R,
+    ) -> Canvas {
+        world.reset_truncated_intersections();
+
+        let mut sampling_rng = self.sampling_rng(rng);
 
-        let seeds: Vec<u64> = from_fn(|| Some(rng.gen()))
-            .take(self.vertical_size as usize)
+        let pixels = (0..self.vertical_size)
+            .zip(self.seeds(&mut sampling_rng))
+            .flat_map(|(y, seed)| {
+                self.render_row(world, backplate, depth, y, seed)
+            })
             .collect();
 
-        // Either does not appear to play nicely with rayon / std iterators so
-        // there appears no nice way to simplify this check despite it looking
-        // like it should be trivial to do so.
-        let pixels: Vec<Colour> = if single_threaded {
+        Canvas::with_vec(self.horizontal_size, self.vertical_size, pixels)
+    }
+
+    /// Renders the given `World` into a set of `Aovs` in a single pass, every
+    /// buffer is filled from one primary ray through the centre of each pixel.
+    /// The render mode is ignored and pixels are neither supersampled nor
+    /// anti-aliased, so the beauty buffer matches `render` with one sample and
+    /// no edge anti-aliasing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is an error writing output.
+    pub fn render_aovs<O: Write, R: Rng>(
+        &self,
+        world: &World,
+        depth: u32,
+        single_threaded: bool,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Aovs> {
+        writeln!(output, "Rendering AOVs...")?;
+
+        let bar = self.progress_bar("Rows", output)?;
+
+        // Hits only refer to the shape that was hit so find the top level
+        // object holding each shape up front, keyed by the shape's address.
+        let object_ids = world
+            .objects
+            .iter()
+            .enumerate()
+            .flat_map(|(id, object)| {
+                object
+                    .shapes()
+                    .into_iter()
+                    .map(move |shape| (ptr::from_ref(shape).addr(), id))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let iterator_fn = |(y, seed)| {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+
+            (0..self.horizontal_size)
+                .map(|x| {
+                    let ray = self.ray_for_pixel(x, y);
+                    let (minimum, maximum) = self.clipping_range(&ray);
+
+                    world
+                        .split_colour_at_within(
+                            &ray,
+                            minimum,
+                            maximum,
+                            depth,
+                            self.background,
+                            &mut rng,
+                        )
+                        .map_or_else(
+                            || AovPixel {
+                                beauty: world
+                                    .miss_colour(&ray, self.background),
+                                depth: f64::INFINITY,
+                                normal: Colour::black(),
+                                object_id: None,
+                                direct: Colour::black(),
+                                indirect: Colour::black(),
+                            },
+                            |(hit, direct, indirect)| AovPixel {
+                                beauty: direct + indirect,
+                                depth: hit.t,
+                                normal: Colour::from_normal(
+                                    &hit.object
+                                        .normal_at(&ray.position(hit.t), &hit),
+                                ),
+                                object_id: object_ids
+                                    .get(&ptr::from_ref(hit.object).addr())
+                                    .copied(),
+                                direct,
+                                indirect,
+                            },
+                        )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let seeds = self.seeds(&mut self.sampling_rng(rng));
+
+        let pixels: Vec<AovPixel> = if single_threaded {
             (0..self.vertical_size)
                 .zip(seeds)
                 .progress_with(bar)
@@ -180,22 +769,416 @@ Elapsed: {elapsed}, remaining: {eta}, rows/sec: {per_sec}",
 
         output.clear_last_line()?;
 
-        writeln!(
-            output,
-            "Rendering scene...done\nRendered {} rows in {}",
+        writeln!(output, "Rendering AOVs...done")?;
+
+        let canvas = |buffer: fn(&AovPixel) -> Colour| {
+            Canvas::with_vec(
+                self.horizontal_size,
+                self.vertical_size,
+                pixels.iter().map(buffer).collect(),
+            )
+        };
+
+        Ok(Aovs {
+            beauty: canvas(|pixel| pixel.beauty),
+            depth: pixels.iter().map(|pixel| pixel.depth).collect(),
+            normal: canvas(|pixel| pixel.normal),
+            object_id: pixels.iter().map(|pixel| pixel.object_id).collect(),
+            direct: canvas(|pixel| pixel.direct),
+            indirect: canvas(|pixel| pixel.indirect),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_pixels<O, R, F>(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        depth: u32,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+        callback: F,
+    ) -> Result<RenderStats>
+    where
+        O: Write,
+        R: Rng,
+        F: FnMut(u32, u32, Colour) + Send,
+    {
+        writeln!(
+            output,
+            "Size {} by {}, field of view {:.1} degrees",
+            HumanCount(self.horizontal_size.into()),
+            HumanCount(self.vertical_size.into()),
+            self.field_of_view.to_degrees()
+        )?;
+
+        writeln!(output, "Rendering scene...")?;
+
+        world.reset_truncated_intersections();
+
+        let bar = self.progress_bar("Rows", output)?;
+
+        let started = Instant::now();
+
+        let callback = Mutex::new(callback);
+
+        let mut sampling_rng = self.sampling_rng(rng);
+
+        let iterator_fn = |(y, seed)| {
+            let colours = self.render_row(world, backplate, depth, y, seed);
+
+            let mut callback =
+                callback.lock().unwrap_or_else(PoisonError::into_inner);
+
+            for (x, colour) in (0..).zip(&colours) {
+                callback(x, y, *colour);
+            }
+
+            colours
+        };
+
+        let seeds = self.seeds(&mut sampling_rng);
+
+        // Either does not appear to play nicely with rayon / std iterators so
+        // there appears no nice way to simplify this check despite it looking
+        // like it should be trivial to do so.
+        let pixels: Vec<Colour> = if single_threaded {
+            (0..self.vertical_size)
+                .zip(seeds)
+                .progress_with(bar)
+                .flat_map(iterator_fn)
+                .collect()
+        } else {
+            (0..self.vertical_size)
+                .into_par_iter()
+                .zip(seeds)
+                .progress_with(bar)
+                .flat_map(iterator_fn)
+                .collect()
+        };
+
+        output.clear_last_line()?;
+
+        let pixel_count = pixels.len() as u64;
+
+        let mut rays = if self.samples > 1 {
+            pixel_count * u64::from(self.samples * self.samples)
+        } else {
+            pixel_count
+        };
+
+        if anti_aliasing == AntiAliasing::Edges {
+            writeln!(output, "Anti-aliasing edges...")?;
+
+            rays += self.antialias_edges(
+                world,
+                backplate,
+                depth,
+                single_threaded,
+                &pixels,
+                &callback,
+                output,
+                &mut sampling_rng,
+            )?;
+
+            output.clear_last_line()?;
+        }
+
+        Self::report_truncated_intersections(world, output)?;
+
+        let duration = started.elapsed();
+
+        writeln!(
+            output,
+            "Rendering scene...done\nRendered {} rows in {}",
             HumanCount(self.horizontal_size.into()),
-            HumanDuration(started.elapsed())
+            HumanDuration(duration)
         )?;
 
-        Ok(Canvas::with_vec(self.horizontal_size, self.vertical_size, pixels))
+        Ok(RenderStats { pixels: pixel_count, rays, duration })
+    }
+
+    /// Render the row of pixels at `y`, each row has its own generator seeded
+    /// with `seed` so rows give the same colours in any order.
+    #[must_use]
+    fn render_row(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        depth: u32,
+        y: u32,
+        seed: u64,
+    ) -> Vec<Colour> {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+
+        (0..self.horizontal_size)
+            .map(|x| {
+                if self.samples > 1 {
+                    self.supersample(
+                        world,
+                        backplate,
+                        x,
+                        y,
+                        self.samples,
+                        depth,
+                        &mut rng,
+                    )
+                } else {
+                    let ray = self.ray_for_pixel(x, y);
+
+                    self.colour_for_ray(
+                        world,
+                        &ray,
+                        self.background_at(backplate, x, y, 0.5, 0.5),
+                        depth,
+                        &mut rng,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Report how many intersection lists were truncated during the render,
+    /// see `World::set_max_intersections`.
+    fn report_truncated_intersections<O: Write>(
+        world: &World,
+        output: &mut Output<O>,
+    ) -> Result<()> {
+        if let Some(maximum) = world.max_intersections() {
+            let truncated = world.truncated_intersections();
+
+            if truncated > 0 {
+                writeln!(
+                    output,
+                    "Truncated {} intersection lists to {} intersections",
+                    HumanCount(truncated as u64),
+                    HumanCount(maximum as u64)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn progress_bar<O: Write>(
+        &self,
+        prefix: &'static str,
+        output: &Output<O>,
+    ) -> Result<ProgressBar> {
+        let bar = ProgressBar::new(self.vertical_size.into())
+            .with_style(
+                ProgressStyle::with_template(
+                    "\
+{prefix} {bar:40.cyan/blue} {human_pos:>7}/{human_len:7} ({percent}%)
+Elapsed: {elapsed}, remaining: {eta}, rows/sec: {per_sec}",
+                )?
+                .progress_chars("#>-"),
+            )
+            .with_prefix(prefix)
+            .with_finish(ProgressFinish::AndClear);
+
+        bar.set_draw_target(if output.is_sink() {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stdout()
+        });
+
+        Ok(bar)
+    }
+
+    /// Create the generator that seeds the generator used for each row of a
+    /// render, in both the main pass and when anti-aliasing edges. It is
+    /// seeded with `sampling_seed` if set and otherwise with a value drawn from
+    /// `rng`, so a render with a sampling seed gives the same image whatever
+    /// other random choices were made while building the scene.
+    fn sampling_rng<R: Rng>(&self, rng: &mut R) -> Xoshiro256PlusPlus {
+        Xoshiro256PlusPlus::seed_from_u64(
+            self.sampling_seed.unwrap_or_else(|| rng.gen()),
+        )
+    }
+
+    fn seeds<R: Rng>(&self, rng: &mut R) -> Vec<u64> {
+        from_fn(|| Some(rng.gen())).take(self.vertical_size as usize).collect()
+    }
+
+    /// Find the pixels that differ from any of their immediate neighbours by
+    /// more than `EDGE_THRESHOLD` in any channel.
+    #[must_use]
+    pub(crate) fn find_edges(&self, pixels: &[Colour]) -> Vec<bool> {
+        let width = self.horizontal_size as usize;
+        let height = self.vertical_size as usize;
+
+        let mut edges = vec![false; pixels.len()];
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+
+                let mut check = |neighbour: usize| {
+                    if approx_ne!(
+                        pixels[index],
+                        pixels[neighbour],
+                        epsilon = EDGE_THRESHOLD
+                    ) {
+                        edges[index] = true;
+                        edges[neighbour] = true;
+                    }
+                };
+
+                if x + 1 < width {
+                    check(index + 1);
+                }
+
+                if y + 1 < height {
+                    check(index + width);
+                }
+            }
+        }
+
+        edges
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn antialias_edges<O, R, F>(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        depth: u32,
+        single_threaded: bool,
+        pixels: &[Colour],
+        callback: &Mutex<F>,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<u64>
+    where
+        O: Write,
+        R: Rng,
+        F: FnMut(u32, u32, Colour) + Send,
+    {
+        let edges = self.find_edges(pixels);
+
+        let samples = EDGE_SAMPLES.max(self.samples);
+
+        let bar = self.progress_bar("Edges", output)?;
+
+        let iterator_fn = |(y, seed)| {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+
+            let mut colours = Vec::new();
+
+            for x in 0..self.horizontal_size {
+                if edges[(y * self.horizontal_size + x) as usize] {
+                    colours.push((
+                        x,
+                        self.supersample(
+                            world, backplate, x, y, samples, depth, &mut rng,
+                        ),
+                    ));
+                }
+            }
+
+            let mut callback =
+                callback.lock().unwrap_or_else(PoisonError::into_inner);
+
+            for (x, colour) in colours {
+                callback(x, y, colour);
+            }
+        };
+
+        let seeds = self.seeds(rng);
+
+        if single_threaded {
+            (0..self.vertical_size)
+                .zip(seeds)
+                .progress_with(bar)
+                .for_each(iterator_fn);
+        } else {
+            (0..self.vertical_size)
+                .into_par_iter()
+                .zip(seeds)
+                .progress_with(bar)
+                .for_each(iterator_fn);
+        }
+
+        let edge_count = edges.iter().filter(|edge| **edge).count() as u64;
+
+        Ok(edge_count * u64::from(samples * samples))
+    }
+
+    /// Sample the pixel on a regular `samples` x `samples` grid and return the
+    /// average colour.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    fn supersample<R: Rng>(
+        &self,
+        world: &World,
+        backplate: Option<&Canvas>,
+        x: u32,
+        y: u32,
+        samples: u32,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        let mut colour = Colour::black();
+
+        for v in 0..samples {
+            for u in 0..samples {
+                let offset_x = (f64::from(u) + 0.5) / f64::from(samples);
+                let offset_y = (f64::from(v) + 0.5) / f64::from(samples);
+
+                let ray = self.ray_for_pixel_offset(x, y, offset_x, offset_y);
+
+                colour += self.colour_for_ray(
+                    world,
+                    &ray,
+                    self.background_at(backplate, x, y, offset_x, offset_y),
+                    depth,
+                    rng,
+                );
+            }
+        }
+
+        colour / f64::from(samples * samples)
+    }
+
+    /// The background seen through a position within the pixel at (x, y),
+    /// taken from the backplate if there is one.
+    #[must_use]
+    fn background_at(
+        &self,
+        backplate: Option<&Canvas>,
+        x: u32,
+        y: u32,
+        offset_x: f64,
+        offset_y: f64,
+    ) -> Option<Colour> {
+        backplate.map_or(self.background, |backplate| {
+            Some(backplate.sample(
+                (f64::from(x) + offset_x) / f64::from(self.horizontal_size),
+                (f64::from(y) + offset_y) / f64::from(self.vertical_size),
+            ))
+        })
     }
 
     #[must_use]
     pub fn ray_for_pixel(&self, x: u32, y: u32) -> Ray {
-        #[allow(clippy::cast_precision_loss)]
-        let x_offset = (f64::from(x) + 0.5) * self.pixel_size;
-        #[allow(clippy::cast_precision_loss)]
-        let y_offset = (f64::from(y) + 0.5) * self.pixel_size;
+        self.ray_for_pixel_offset(x, y, 0.5, 0.5)
+    }
+
+    /// Generate a ray through the pixel at (x, y) where `offset_x` and
+    /// `offset_y` give the position within the pixel in the range 0.0..1.0.
+    #[must_use]
+    pub fn ray_for_pixel_offset(
+        &self,
+        x: u32,
+        y: u32,
+        offset_x: f64,
+        offset_y: f64,
+    ) -> Ray {
+        let x_offset = (f64::from(x) + offset_x) * self.pixel_size;
+        let y_offset = (f64::from(y) + offset_y) * self.pixel_size;
 
         let world_x = self.half_width - x_offset;
         let world_y = self.half_height - y_offset;
@@ -209,13 +1192,45 @@ Elapsed: {elapsed}, remaining: {eta}, rows/sec: {per_sec}",
     }
 }
 
+impl<T, N, F, S, M, D, B>
+    _CameraBuilder<((u32,), (u32,), (Angle,), T, N, F, S, M, D, B)>
+where
+    T: Optional<Transformation>,
+    N: Optional<f64>,
+    F: Optional<f64>,
+    S: Optional<u32>,
+    M: Optional<RenderMode>,
+    D: Optional<Option<u64>>,
+    B: Optional<Option<Colour>>,
+{
+    #[must_use]
+    pub fn build(self) -> Camera {
+        let mut camera = self._build();
+
+        camera.inverse_transformation = camera.transformation.invert();
+
+        (camera.half_width, camera.half_height, camera.pixel_size) =
+            Camera::calculate(
+                camera.horizontal_size,
+                camera.vertical_size,
+                camera.field_of_view,
+            );
+
+        camera
+    }
+}
+
 impl_approx_eq!(Camera {
     eq horizontal_size,
     eq vertical_size,
     field_of_view,
     half_width,
     half_height,
-    pixel_size
+    pixel_size,
+    near,
+    far,
+    eq samples,
+    eq render_mode
 });
 
 impl<'de> Deserialize<'de> for Camera {
@@ -231,34 +1246,78 @@ impl<'de> Deserialize<'de> for Camera {
             pub width: u32,
             pub height: u32,
             pub field_of_view: Angle,
-            pub from: Point,
-            pub to: Point,
-            pub up: Vector,
+            pub from: Option<Point>,
+            pub to: Option<Point>,
+            pub up: Option<Vector>,
+            pub matrix: Option<[[f64; 4]; 4]>,
+            pub transform: Option<Transformation>,
+            pub clip: Option<(f64, f64)>,
+            pub background: Option<Colour>,
         }
 
         let camera = Camera::deserialize(deserializer)?;
 
-        Ok(Self::new(
+        // The view transformation can be given by from, to and up, or by an
+        // explicit matrix or list of transformations, but only one of them.
+        let transformation = match (
+            camera.from,
+            camera.to,
+            camera.up,
+            camera.matrix,
+            camera.transform,
+        ) {
+            (Some(from), Some(to), Some(up), None, None) => {
+                Transformation::view_transformation(from, to, up)
+            }
+            (None, None, None, Some(matrix), None) => matrix.into(),
+            (None, None, None, None, Some(transformation)) => transformation,
+            _ => {
+                return Err(Error::custom(
+                    "\
+Camera requires exactly one of from, to and up, matrix or transform",
+                ))
+            }
+        };
+
+        let new_camera = Self::new(
             camera.width,
             camera.height,
             camera.field_of_view,
-            Transformation::view_transformation(
-                camera.from,
-                camera.to,
-                camera.up,
-            ),
-        ))
+            transformation,
+        )
+        .with_background(camera.background);
+
+        let Some((near, far)) = camera.clip else {
+            return Ok(new_camera);
+        };
+
+        if near < 0.0 || far <= near {
+            return Err(Error::custom(
+                "Camera clip must be [near, far] with 0 <= near < far",
+            ));
+        }
+
+        Ok(new_camera.with_clip(near, far))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, PI, SQRT_2};
+    use std::f64::consts::{
+        FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, PI, SQRT_2,
+    };
 
     use serde_yaml::from_str;
 
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
     use super::*;
-    use crate::math::{float::*, Vector};
+    use crate::{
+        math::{float::*, Vector},
+        object::Updatable,
+        world::test_world,
+        Bounded, Light, Material, Object, Pattern,
+    };
 
     #[test]
     #[allow(clippy::many_single_char_names)]
@@ -276,6 +1335,8 @@ mod tests {
         assert_approx_eq!(c.half_width, 1.0);
         assert_approx_eq!(c.half_height, 0.75);
         assert_approx_eq!(c.pixel_size, 0.012_5);
+        assert_eq!(c.samples(), 1);
+        assert_eq!(c.with_samples(3).samples(), 3);
 
         let c = Camera::new(200, 125, f, t);
         assert_approx_eq!(c.half_width, 1.0);
@@ -288,6 +1349,45 @@ mod tests {
         assert_approx_eq!(c.pixel_size, 0.01);
     }
 
+    #[test]
+    fn creating_a_camera_with_a_builder() {
+        let f = Angle(FRAC_PI_3);
+        let t = Transformation::view_transformation(
+            Point::new(1.0, 2.0, -5.0),
+            Point::origin(),
+            Vector::y_axis(),
+        );
+
+        let c = Camera::builder()
+            .horizontal_size(200)
+            .vertical_size(125)
+            .field_of_view(f)
+            .transformation(t)
+            .build();
+
+        assert_approx_eq!(c, Camera::new(200, 125, f, t));
+        assert_approx_eq!(c.inverse_transformation, t.invert());
+        assert_approx_eq!(c.clip().0, 0.0);
+        assert!(c.clip().1.is_infinite());
+        assert_eq!(c.samples(), 1);
+
+        let c = Camera::builder()
+            .horizontal_size(200)
+            .vertical_size(125)
+            .field_of_view(f)
+            .near(0.5)
+            .far(10.0)
+            .samples(4)
+            .build();
+
+        assert_approx_eq!(
+            c,
+            Camera::new(200, 125, f, Transformation::new())
+                .with_clip(0.5, 10.0)
+                .with_samples(4)
+        );
+    }
+
     #[test]
     fn scaling_a_camera() {
         let mut c = Camera::new(
@@ -404,30 +1504,948 @@ mod tests {
     }
 
     #[test]
-    fn deserialize_camera() {
-        let c: Camera = from_str(
-            "\
-width: 200
-height: 150
-field-of-view: \"PI / 3\"
-from: [1, 2, 3]
-to: [0, 1.5, 0.0]
-up: [0, 1, 0]",
-        )
-        .unwrap();
+    #[allow(clippy::many_single_char_names)]
+    fn finding_edges_in_rendered_pixels() {
+        let c = Camera::new(4, 3, Angle(FRAC_PI_2), Transformation::new());
 
-        assert_approx_eq!(
-            c,
-            Camera::new(
-                200,
-                150,
-                Angle(FRAC_PI_3),
-                Transformation::view_transformation(
-                    Point::new(1.0, 2.0, 3.0),
-                    Point::new(0.0, 1.5, 0.0),
-                    Vector::y_axis()
-                )
-            )
+        let b = Colour::black();
+        let w = Colour::white();
+        let n = Colour::new(0.05, 0.0, 0.0);
+
+        let e = c.find_edges(&[b, b, b, w, b, n, b, w, b, b, b, w]);
+
+        assert_eq!(
+            e,
+            [
+                false, false, true, true, false, false, true, true, false,
+                false, true, true
+            ]
+        );
+
+        let e = c.find_edges(&[b; 12]);
+
+        assert!(e.iter().all(|e| !e));
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn rendering_with_stats() {
+        let w = test_world();
+
+        let c = Camera::new(
+            11,
+            9,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
         );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let (i, s) = c
+            .render_with_stats(&w, 5, true, AntiAliasing::None, &mut o, &mut r)
+            .unwrap();
+
+        assert_eq!(s.pixels(), 11 * 9);
+        assert_eq!(s.rays(), 11 * 9);
+        assert!(s.duration() > Duration::ZERO);
+        assert!(s.pixels_per_second() > 0.0);
+        assert_approx_eq!(s.rays_per_second(), s.pixels_per_second());
+
+        assert_approx_eq!(
+            i.get_pixel(5, 4),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+
+        let (_, s) = c
+            .with_samples(2)
+            .render_with_stats(&w, 5, true, AntiAliasing::None, &mut o, &mut r)
+            .unwrap();
+
+        assert_eq!(s.pixels(), 11 * 9);
+        assert_eq!(s.rays(), 11 * 9 * 4);
+
+        let (_, s) = c
+            .render_with_stats(&w, 5, true, AntiAliasing::Edges, &mut o, &mut r)
+            .unwrap();
+
+        assert!(s.rays() > 11 * 9);
+        assert_eq!((s.rays() - 11 * 9) % 16, 0);
+    }
+
+    #[test]
+    fn rendering_with_a_sampling_seed() {
+        let mut w = test_world();
+        w.objects_mut()[0].replace_material(
+            &Material::builder().reflective(0.8).roughness(0.5).build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(
+                    Material::builder()
+                        .pattern(
+                            Pattern::checker_builder(
+                                Colour::white().into(),
+                                Colour::red().into(),
+                            )
+                            .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let render = |seed, rng_seed| {
+            c.with_sampling_seed(seed)
+                .render(
+                    &w,
+                    5,
+                    false,
+                    AntiAliasing::Edges,
+                    &mut Output::<Vec<_>>::new_sink(),
+                    &mut Xoshiro256PlusPlus::seed_from_u64(rng_seed),
+                )
+                .unwrap()
+                .to_raw_rgb_f32()
+                .iter()
+                .map(|value| value.to_bits())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(c.sampling_seed(), None);
+        assert_eq!(c.with_sampling_seed(Some(3)).sampling_seed(), Some(3));
+
+        assert_eq!(render(Some(3), 0), render(Some(3), 1));
+        assert_ne!(render(Some(3), 0), render(Some(4), 0));
+        assert_ne!(render(None, 0), render(None, 1));
+    }
+
+    #[test]
+    fn accumulating_renders() {
+        let mut w = test_world();
+        w.objects_mut()[0].replace_material(
+            &Material::builder().reflective(0.8).roughness(0.5).build(),
+        );
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let render = |seed| {
+            c.with_sampling_seed(Some(seed))
+                .render(
+                    &w,
+                    5,
+                    false,
+                    AntiAliasing::Edges,
+                    &mut Output::<Vec<_>>::new_sink(),
+                    &mut Xoshiro256PlusPlus::seed_from_u64(0),
+                )
+                .unwrap()
+        };
+
+        let single = render(3);
+
+        let mut accumulated = Canvas::new(11, 11);
+        accumulated.accumulate(&render(3), 0.5);
+        accumulated.accumulate(&render(3), 0.5);
+        accumulated.normalise();
+
+        for (lhs, rhs) in accumulated.pixels().zip(single.pixels()) {
+            assert_approx_eq!(*lhs, *rhs, epsilon = 0.000_01);
+        }
+
+        // Renders with different seeds differ but their mean is the mean of
+        // the combined render.
+        let other = render(4);
+
+        let mut accumulated = Canvas::new(11, 11);
+        accumulated.accumulate(&single, 0.5);
+        accumulated.accumulate(&other, 0.5);
+        accumulated.normalise();
+
+        assert_approx_eq!(
+            accumulated.stats().mean,
+            (single.stats().mean + other.stats().mean) / 2.0,
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_aovs() {
+        let mut w = test_world();
+        w.objects_mut()[0].replace_material(
+            &Material::builder().reflective(0.8).roughness(0.5).build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .build(),
+        );
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        )
+        .with_sampling_seed(Some(3));
+
+        let render = |c: Camera| {
+            c.render(
+                &w,
+                5,
+                false,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0),
+            )
+            .unwrap()
+        };
+
+        let a = c
+            .render_aovs(
+                &w,
+                5,
+                false,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(1),
+            )
+            .unwrap();
+
+        assert_eq!(a.beauty.to_raw_rgb_f32(), render(c).to_raw_rgb_f32());
+        assert_eq!(
+            a.normal.to_raw_rgb_f32(),
+            render(c.with_render_mode(RenderMode::Normals)).to_raw_rgb_f32()
+        );
+
+        for y in 0..11 {
+            for x in 0..11 {
+                let index = y * 11 + x;
+                let ray = c.ray_for_pixel(x, y);
+
+                let hit = w
+                    .intersections_with_ids(&ray)
+                    .into_iter()
+                    .map(|(_, t, _)| t)
+                    .filter(|t| *t > 0.0)
+                    .reduce(f64::min);
+
+                assert_approx_eq!(
+                    a.depth[index as usize],
+                    hit.unwrap_or(f64::INFINITY)
+                );
+
+                if hit.is_some() {
+                    assert_approx_eq!(
+                        a.direct.get_pixel(x as usize, y as usize)
+                            + a.indirect.get_pixel(x as usize, y as usize),
+                        a.beauty.get_pixel(x as usize, y as usize)
+                    );
+                }
+            }
+        }
+
+        // The centre hits the reflective outer sphere, the bottom the plane
+        // and the top corner misses everything.
+        assert_approx_eq!(a.depth[60], 4.0);
+        assert_eq!(a.object_id[60], Some(0));
+        assert!(a.direct.get_pixel(5, 5).luminance() > 0.0);
+        assert!(a.indirect.get_pixel(5, 5).luminance() > 0.0);
+
+        assert_eq!(a.object_id[115], Some(2));
+        assert_approx_eq!(a.indirect.get_pixel(5, 10), Colour::black());
+
+        assert_eq!(a.object_id[0], None);
+        assert_approx_eq!(a.beauty.get_pixel(0, 0), Colour::black());
+        assert_approx_eq!(a.normal.get_pixel(0, 0), Colour::black());
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn rendering_u_and_v() {
+        let mut w = World::new();
+
+        w.add_object(Object::sphere_builder().build());
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(0.5),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        )
+        .with_render_mode(RenderMode::Uv);
+
+        assert_eq!(c.render_mode(), RenderMode::Uv);
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let i =
+            c.render(&w, 5, true, AntiAliasing::None, &mut o, &mut r).unwrap();
+
+        assert_approx_eq!(i.get_pixel(5, 5), Colour::new(0.0, 0.5, 0.0));
+        assert!(i.get_pixel(5, 2).green > 0.5);
+        assert!(i.get_pixel(5, 8).green < 0.5);
+        assert_approx_eq!(i.get_pixel(0, 0), Colour::black());
+    }
+
+    #[test]
+    fn rendering_world_space_normals() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(
+                    Transformation::new().rotate_x(Angle(-FRAC_PI_2)),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(1.0, 2.0, 1.0)
+                        .translate(5.0, 0.0, -1.0),
+                )
+                .build(),
+        );
+
+        let c = Camera::new(11, 11, Angle(FRAC_PI_2), Transformation::new())
+            .with_render_mode(RenderMode::Normals);
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        // The plane is rotated to face -z so its normal is no longer the y
+        // axis.
+        let plane = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+        assert_approx_eq!(
+            c.colour_for_ray(&w, &plane, None, 5, &mut r),
+            Colour::new(0.5, 0.5, 0.0)
+        );
+
+        // Scaling the sphere tilts its normal away from the direction to the
+        // point on its surface.
+        let sphere = Ray::new(Point::new(5.0, SQRT_2, -5.0), Vector::z_axis());
+        let normal = Vector::new(0.0, SQRT_2 / 4.0, -FRAC_1_SQRT_2).normalise();
+        assert_approx_eq!(
+            c.colour_for_ray(&w, &sphere, None, 5, &mut r),
+            Colour::from_normal(&normal)
+        );
+
+        let miss = Ray::new(Point::new(0.0, 0.0, -5.0), -Vector::z_axis());
+        assert_approx_eq!(
+            c.colour_for_ray(&w, &miss, None, 5, &mut r),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    fn rendering_a_wireframe() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::triangle_builder(
+                Point::new(-1.0, -1.0, 0.0),
+                Point::new(1.0, -1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+                -Vector::z_axis(),
+                -Vector::z_axis(),
+                -Vector::z_axis(),
+            )
+            .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+
+        let shaded =
+            Camera::new(11, 11, Angle(FRAC_PI_2), Transformation::new());
+        let wireframe = shaded.with_render_mode(RenderMode::Wireframe {
+            colour: Colour::green(),
+            thickness: 0.05,
+        });
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let edge = Ray::new(Point::new(0.0, -0.98, -5.0), Vector::z_axis());
+        assert_approx_eq!(
+            wireframe.colour_for_ray(&w, &edge, None, 5, &mut r),
+            Colour::green()
+        );
+
+        let interior = Ray::new(Point::new(0.0, -0.2, -5.0), Vector::z_axis());
+        let colour = wireframe.colour_for_ray(&w, &interior, None, 5, &mut r);
+        assert_approx_ne!(colour, Colour::green());
+        assert_approx_eq!(
+            colour,
+            shaded.colour_for_ray(&w, &interior, None, 5, &mut r)
+        );
+
+        let miss = Ray::new(Point::new(3.0, 0.0, -5.0), Vector::z_axis());
+        assert_approx_eq!(
+            wireframe.colour_for_ray(&w, &miss, None, 5, &mut r),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    fn rendering_over_a_backplate() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(Transformation::new().scale(0.5, 0.5, 0.5))
+                .material(
+                    Material::builder().pattern(Colour::red().into()).build(),
+                )
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+        w.set_background(Colour::blue());
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let backplate =
+            Canvas::with_vec(1, 1, vec![Colour::new(0.2, 0.6, 0.4)]);
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let image = c
+            .render_with_backplate(
+                &w,
+                &backplate,
+                5,
+                true,
+                AntiAliasing::Edges,
+                &mut o,
+                &mut r,
+            )
+            .unwrap();
+        let plain =
+            c.render(&w, 5, true, AntiAliasing::Edges, &mut o, &mut r).unwrap();
+
+        for (x, y) in [(0, 0), (10, 0), (0, 10), (10, 10), (5, 0)] {
+            assert_approx_eq!(
+                image.get_pixel(x, y),
+                Colour::new(0.2, 0.6, 0.4)
+            );
+            assert_approx_eq!(plain.get_pixel(x, y), Colour::blue());
+        }
+
+        assert_approx_eq!(image.get_pixel(5, 5), plain.get_pixel(5, 5));
+        assert_approx_ne!(image.get_pixel(5, 5), Colour::new(0.2, 0.6, 0.4));
+
+        // The backplate is stretched over the whole image.
+        let backplate =
+            Canvas::with_vec(2, 1, vec![Colour::green(), Colour::white()]);
+
+        let image = c
+            .with_samples(2)
+            .render_with_backplate(
+                &w,
+                &backplate,
+                5,
+                true,
+                AntiAliasing::None,
+                &mut o,
+                &mut r,
+            )
+            .unwrap();
+
+        assert_approx_eq!(image.get_pixel(0, 5), Colour::green());
+        assert_approx_eq!(image.get_pixel(10, 5), Colour::white());
+    }
+
+    #[test]
+    fn rendering_a_shadow_catcher() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(Transformation::new().translate(0.0, 1.0, 0.0))
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .material(Material::builder().shadow_catcher(true).build())
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, 0.0),
+            Colour::white(),
+        ));
+
+        // Looking down on the plane with x pointing to the right of the image.
+        let c = Camera::new(
+            21,
+            21,
+            Angle(FRAC_PI_3),
+            Transformation::view_transformation(
+                Point::new(0.0, 10.0, 0.0),
+                Point::origin(),
+                Vector::z_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let image =
+            c.render(&w, 5, true, AntiAliasing::None, &mut o, &mut r).unwrap();
+
+        assert_approx_ne!(image.get_pixel(10, 10), Colour::black());
+        assert_approx_eq!(image.get_pixel(13, 10), Colour::black());
+        assert_approx_eq!(image.get_pixel(7, 10), Colour::black());
+
+        let c = c.with_render_mode(RenderMode::Matte);
+
+        let image =
+            c.render(&w, 5, true, AntiAliasing::None, &mut o, &mut r).unwrap();
+
+        // The sphere is opaque and its shadow falls away from the light.
+        assert_approx_eq!(image.get_pixel(10, 10), Colour::white());
+        assert_approx_eq!(image.get_pixel(13, 10), Colour::white());
+        assert_approx_eq!(image.get_pixel(7, 10), Colour::black());
+        assert_approx_eq!(image.get_pixel(10, 3), Colour::black());
+        assert_approx_eq!(image.get_pixel(0, 0), Colour::black());
+    }
+
+    #[test]
+    fn summing_light_passes_gives_the_full_render() {
+        let mut w = test_world();
+
+        w.set_background(Colour::new(0.2, 0.3, 0.4));
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+
+        w.add_object(
+            Object::group_builder()
+                .transformation(Transformation::new().translate(10.0, 0.0, 0.0))
+                .add_light(Light::new_point(
+                    Point::new(0.0, 5.0, -10.0),
+                    Colour::red(),
+                ))
+                .build(),
+        );
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 1.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let expected =
+            c.render(&w, 5, true, AntiAliasing::None, &mut o, &mut r).unwrap();
+
+        let mut pass = |light| {
+            c.render_light_pass(&w, light, 5, true, &mut o, &mut r).unwrap()
+        };
+
+        let passes = [pass(Some(0)), pass(Some(1)), pass(None)];
+
+        // A light that does not exist lights nothing.
+        let none = pass(Some(2));
+
+        for y in 0..11 {
+            for x in 0..11 {
+                let colour = passes
+                    .iter()
+                    .map(|pass| pass.get_pixel(x, y))
+                    .fold(Colour::black(), |sum, colour| sum + colour);
+
+                assert_approx_eq!(
+                    colour,
+                    expected.get_pixel(x, y),
+                    epsilon = 0.000_01
+                );
+                assert_approx_eq!(none.get_pixel(x, y), Colour::black());
+            }
+        }
+
+        assert_approx_ne!(passes[0].get_pixel(5, 5), Colour::black());
+        assert_approx_ne!(passes[1].get_pixel(5, 5), Colour::black());
+        assert_approx_eq!(passes[2].get_pixel(5, 5), Colour::black());
+        assert_approx_eq!(passes[0].get_pixel(0, 0), Colour::black());
+        assert_approx_eq!(passes[2].get_pixel(0, 0), w.background());
+    }
+
+    #[test]
+    fn parsing_render_modes() {
+        assert_eq!("shaded".parse::<RenderMode>().unwrap(), RenderMode::Shaded);
+        assert_eq!(from_str::<RenderMode>("uv").unwrap(), RenderMode::Uv);
+        assert_eq!(from_str::<RenderMode>("matte").unwrap(), RenderMode::Matte);
+        assert_eq!(
+            from_str::<RenderMode>("normals").unwrap(),
+            RenderMode::Normals
+        );
+        assert_ne!(
+            RenderMode::Wireframe {
+                colour: Colour::black(),
+                thickness: WIREFRAME_THICKNESS
+            },
+            RenderMode::Shaded
+        );
+        assert_eq!(
+            from_str::<RenderMode>("wireframe").unwrap(),
+            RenderMode::Wireframe {
+                colour: Colour::black(),
+                thickness: WIREFRAME_THICKNESS
+            }
+        );
+        assert_eq!(RenderMode::default(), RenderMode::Shaded);
+
+        assert_eq!(
+            from_str::<RenderMode>("depth").unwrap_err().to_string(),
+            "Unknown render mode 'depth'"
+        );
+    }
+
+    #[test]
+    fn cameras_with_different_backgrounds() {
+        let mut w = World::new();
+
+        w.add_object(Object::sphere_builder().build());
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+        w.set_background(Colour::green());
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        assert!(c.background().is_none());
+
+        let render = |camera: Camera| {
+            camera
+                .render(
+                    &w,
+                    5,
+                    true,
+                    AntiAliasing::None,
+                    &mut Output::<Vec<_>>::new_sink(),
+                    &mut Xoshiro256PlusPlus::seed_from_u64(0),
+                )
+                .unwrap()
+        };
+
+        let studio = render(c.with_background(Some(Colour::white())));
+        let outdoor = render(c.with_background(Some(Colour::blue())));
+        let default = render(c);
+
+        assert_approx_eq!(studio.get_pixel(0, 0), Colour::white());
+        assert_approx_eq!(outdoor.get_pixel(0, 0), Colour::blue());
+        assert_approx_eq!(default.get_pixel(0, 0), Colour::green());
+
+        assert_approx_eq!(studio.get_pixel(5, 5), outdoor.get_pixel(5, 5));
+        assert_approx_eq!(studio.get_pixel(5, 5), default.get_pixel(5, 5));
+    }
+
+    #[test]
+    fn checking_if_a_camera_can_see_a_bounding_box() {
+        let c = Camera::new(
+            100,
+            50,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let b = |x: f64, y: f64, z: f64| {
+            BoundingBox::new(
+                Point::new(x - 0.5, y - 0.5, z - 0.5),
+                Point::new(x + 0.5, y + 0.5, z + 0.5),
+            )
+        };
+
+        assert!(c.can_see(&b(0.0, 0.0, 0.0)));
+        assert!(c.can_see(&b(5.0, 0.0, 0.0)));
+        assert!(c.can_see(&b(0.0, 2.5, 0.0)));
+
+        assert!(!c.can_see(&b(0.0, 0.0, -10.0)));
+        assert!(!c.can_see(&b(6.5, 0.0, 0.0)));
+        assert!(!c.can_see(&b(-6.5, 0.0, 0.0)));
+        assert!(!c.can_see(&b(0.0, 3.5, 0.0)));
+        assert!(!c.can_see(&b(0.0, -3.5, 0.0)));
+
+        let c = c.with_clip(1.0, 10.0);
+
+        assert!(c.can_see(&b(0.0, 0.0, 0.0)));
+        assert!(!c.can_see(&b(0.0, 0.0, 6.0)));
+
+        assert!(c.can_see(&Object::plane_builder().build().bounding_box()));
+    }
+
+    #[test]
+    fn deserialize_camera() {
+        let c: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+from: [1, 2, 3]
+to: [0, 1.5, 0.0]
+up: [0, 1, 0]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            c,
+            Camera::new(
+                200,
+                150,
+                Angle(FRAC_PI_3),
+                Transformation::view_transformation(
+                    Point::new(1.0, 2.0, 3.0),
+                    Point::new(0.0, 1.5, 0.0),
+                    Vector::y_axis()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn deserialize_camera_with_a_matrix() {
+        let c: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+from: [8, 0, 0]
+to: [0, 0, 0]
+up: [0, 1, 0]",
+        )
+        .unwrap();
+
+        let m: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+matrix:
+  - [0, 0, -1, 0]
+  - [0, 1, 0, 0]
+  - [1, 0, 0, -8]
+  - [0, 0, 0, 1]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(m, c);
+
+        let c: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+transform:
+  - [rotate-y, \"PI / 2\"]
+  - [translate, 0, 0, 5]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            c,
+            Camera::new(
+                200,
+                150,
+                Angle(FRAC_PI_3),
+                Transformation::new()
+                    .rotate_y(Angle(FRAC_PI_2))
+                    .translate(0.0, 0.0, 5.0)
+            )
+        );
+
+        for yaml in [
+            "from: [1, 2, 3]\nto: [0, 0, 0]",
+            "from: [1, 2, 3]\nto: [0, 0, 0]\nup: [0, 1, 0]\ntransform: []",
+            "",
+        ] {
+            assert_eq!(
+                from_str::<Camera>(&format!(
+                    "width: 200\nheight: 150\nfield-of-view: 1\n{yaml}"
+                ))
+                .unwrap_err()
+                .to_string(),
+                "\
+Camera requires exactly one of from, to and up, matrix or transform"
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_camera_with_background() {
+        let c: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+from: [1, 2, 3]
+to: [0, 1.5, 0.0]
+up: [0, 1, 0]
+background: [0.2, 0.4, 0.6]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(c.background().unwrap(), Colour::new(0.2, 0.4, 0.6));
+    }
+
+    #[test]
+    fn deserialize_camera_with_clipping() {
+        let c: Camera = from_str(
+            "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+from: [1, 2, 3]
+to: [0, 1.5, 0.0]
+up: [0, 1, 0]
+clip: [1.5, 20]",
+        )
+        .unwrap();
+
+        let (n, f) = c.clip();
+        assert_approx_eq!(n, 1.5);
+        assert_approx_eq!(f, 20.0);
+
+        assert_eq!(
+            from_str::<Camera>(
+                "\
+width: 200
+height: 150
+field-of-view: \"PI / 3\"
+from: [1, 2, 3]
+to: [0, 1.5, 0.0]
+up: [0, 1, 0]
+clip: [5, 2]",
+            )
+            .unwrap_err()
+            .to_string(),
+            "Camera clip must be [near, far] with 0 <= near < far"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn clipping_a_sphere_straddling_the_near_plane() {
+        let mut w = World::new();
+
+        w.add_object(Object::sphere_builder().build());
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(0.5),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        assert_approx_eq!(c.clip().0, 0.0);
+        assert!(c.clip().1.is_infinite());
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let i =
+            c.render(&w, 5, true, AntiAliasing::None, &mut o, &mut r).unwrap();
+
+        let clipped = c.with_clip(4.2, 100.0);
+        let l = clipped
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut r)
+            .unwrap();
+
+        // The centre of the sphere is closer than the near plane so we see the
+        // inside of the sphere, the edges and background are unchanged.
+        assert_approx_ne!(i.get_pixel(5, 5), l.get_pixel(5, 5));
+        assert_approx_eq!(i.get_pixel(5, 1), l.get_pixel(5, 1));
+        assert_approx_ne!(i.get_pixel(5, 1), Colour::black());
+        assert_approx_eq!(i.get_pixel(0, 0), l.get_pixel(0, 0));
+
+        let clipped = c.with_clip(0.0, 3.9);
+        let l = clipped
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut r)
+            .unwrap();
+
+        assert_approx_eq!(l.get_pixel(5, 5), Colour::black());
     }
 }
@@ -1,4 +1,35 @@
-use super::Colour;
+use std::{
+    path::Path,
+    slice::{Iter, IterMut},
+};
+
+use anyhow::Result;
+use rayon::prelude::*;
+
+use super::{ClampMode, Colour};
+use crate::pattern::Image;
+
+/// A `Histogram` counts how many pixels of a canvas fall into each of a number
+/// of equally sized bins between 0.0 and 1.0 for each channel and for the
+/// luminance. Values outside that range are counted in the first or last bin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    pub red: Vec<usize>,
+    pub green: Vec<usize>,
+    pub blue: Vec<usize>,
+    pub luminance: Vec<usize>,
+}
+
+/// `CanvasStats` summarise the range of a canvas before tone mapping, the
+/// minimum, maximum and mean are per channel and `clipped` is the number of
+/// pixels with any channel brighter than 1.0.
+#[derive(Clone, Copy, Debug)]
+pub struct CanvasStats {
+    pub minimum: Colour,
+    pub maximum: Colour,
+    pub mean: Colour,
+    pub clipped: usize,
+}
 
 /// The Canvas represents the area we are going to be drawing images onto. This
 /// will be a basic implementation and will probably need to be refactored later
@@ -8,6 +39,9 @@ pub struct Canvas {
     width: u32,
     height: u32,
     pixels: Vec<Colour>,
+    /// The total weight of the canvases added to each pixel by `accumulate`,
+    /// `None` when the canvas is not accumulating.
+    weights: Option<Vec<f64>>,
 }
 
 impl Canvas {
@@ -17,6 +51,7 @@ impl Canvas {
             width,
             height,
             pixels: vec![Colour::black(); (width * height) as usize],
+            weights: None,
         }
     }
 
@@ -34,7 +69,66 @@ impl Canvas {
             "Pixels must contain width * height values."
         );
 
-        Self { width, height, pixels }
+        Self { width, height, pixels, weights: None }
+    }
+
+    /// Load a `Canvas` from an image file such as a PNG or PPM, the sRGB
+    /// values in the file are converted to linear colours.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if the file can not be read or decoded or if the
+    /// image is empty.
+    pub fn load<P: AsRef<Path>>(filename: P) -> Result<Self> {
+        Ok(Image::load(filename, false)?.into_canvas())
+    }
+
+    /// Create a `Canvas` from a buffer of floats holding the red, green and
+    /// blue values of each pixel in turn, row by row.
+    ///
+    /// # Panics
+    ///
+    /// Function will panic if the buffer does not contain exactly three values
+    /// for each pixel or if that number of values does not fit in a `usize`.
+    #[must_use]
+    pub fn from_raw_rgb_f32(width: u32, height: u32, data: &[f32]) -> Self {
+        let length = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .expect("Canvas is too large to hold as raw floats.");
+
+        assert_eq!(
+            data.len(),
+            length,
+            "Data must contain width * height * 3 values."
+        );
+
+        let pixels = data
+            .chunks_exact(3)
+            .map(|rgb| {
+                Colour::new(
+                    f64::from(rgb[0]),
+                    f64::from(rgb[1]),
+                    f64::from(rgb[2]),
+                )
+            })
+            .collect();
+
+        Self::with_vec(width, height, pixels)
+    }
+
+    /// Copy the canvas into a buffer of floats holding the red, green and blue
+    /// values of each pixel in turn, row by row. Values are not clamped.
+    #[must_use]
+    pub fn to_raw_rgb_f32(&self) -> Vec<f32> {
+        // Losing precision is expected when converting to f32.
+        #[allow(clippy::cast_possible_truncation)]
+        self.pixels
+            .iter()
+            .flat_map(|pixel| {
+                [pixel.red as f32, pixel.green as f32, pixel.blue as f32]
+            })
+            .collect()
     }
 
     pub fn write_pixel(&mut self, x: usize, y: usize, colour: &Colour) {
@@ -46,12 +140,301 @@ impl Canvas {
         self.pixels[y * self.width as usize + x]
     }
 
+    /// The pixel nearest to `u` and `v`, both in the range 0.0..1.0 running
+    /// from the left and top of the canvas, this scales the canvas to any
+    /// resolution.
+    #[must_use]
+    pub fn sample(&self, u: f64, v: f64) -> Colour {
+        // Both u and v are clamped to 0..1 so the conversion is always valid.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let index = |value: f64, size: u32| {
+            ((value.clamp(0.0, 1.0) * f64::from(size)) as usize)
+                .min(size as usize - 1)
+        };
+
+        self.get_pixel(index(u, self.width), index(v, self.height))
+    }
+
+    /// Iterate over the pixels row by row starting at the top left.
+    pub fn pixels(&self) -> Iter<'_, Colour> {
+        self.pixels.iter()
+    }
+
+    /// Iterate mutably over the pixels in the same order as `pixels`.
+    pub fn pixels_mut(&mut self) -> IterMut<'_, Colour> {
+        self.pixels.iter_mut()
+    }
+
+    /// Create a new canvas by applying `function` to every pixel, the pixels
+    /// are processed in parallel.
+    #[must_use]
+    pub fn par_map<F>(&self, function: F) -> Self
+    where
+        F: Fn(&Colour) -> Colour + Sync + Send,
+    {
+        Self::with_vec(
+            self.width,
+            self.height,
+            self.pixels.par_iter().map(function).collect(),
+        )
+    }
+
+    /// Reduce noise with a bilateral filter, each pixel becomes a weighted
+    /// average of its neighbours where the weights fall off with both distance
+    /// (`sigma_spatial`, in pixels) and difference in colour (`sigma_colour`)
+    /// so that edges are preserved. Impulse noise, such as salt and pepper,
+    /// differs from its neighbours just as an edge does so is only removed
+    /// when `sigma_colour` is large enough to span it, at the cost of softer
+    /// edges. If either sigma is not positive no pixel has any neighbours to
+    /// average with and the canvas is left unchanged.
+    #[must_use]
+    pub fn denoise_bilateral(
+        &self,
+        sigma_spatial: f64,
+        sigma_colour: f64,
+    ) -> Self {
+        self.bilateral(sigma_spatial, sigma_colour, self)
+    }
+
+    /// As `denoise_bilateral` but colour differences are measured in `guide`
+    /// rather than in the canvas itself. Using a noise free pass such as depth
+    /// or normals as the guide keeps edges that are hidden by noise.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `guide` is not the same size as the canvas.
+    #[must_use]
+    pub fn denoise_bilateral_with_guide(
+        &self,
+        sigma_spatial: f64,
+        sigma_colour: f64,
+        guide: &Self,
+    ) -> Self {
+        assert!(
+            self.width == guide.width && self.height == guide.height,
+            "Guide must be the same size as the canvas."
+        );
+
+        self.bilateral(sigma_spatial, sigma_colour, guide)
+    }
+
+    fn bilateral(
+        &self,
+        sigma_spatial: f64,
+        sigma_colour: f64,
+        guide: &Self,
+    ) -> Self {
+        if sigma_spatial <= 0.0 || sigma_colour <= 0.0 {
+            return self.clone();
+        }
+
+        // The radius is small and positive so the conversion is safe.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let radius = (2.0 * sigma_spatial).ceil() as usize;
+
+        let spatial = -0.5 / sigma_spatial.powi(2);
+        let colour = -0.5 / sigma_colour.powi(2);
+
+        let width = self.width as usize;
+        let height = self.height as usize;
+
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+
+        for y in 0..height {
+            for x in 0..width {
+                let centre = guide.get_pixel(x, y);
+
+                let mut total = Colour::black();
+                let mut total_weight = 0.0;
+
+                for ny in
+                    y.saturating_sub(radius)..=(y + radius).min(height - 1)
+                {
+                    for nx in
+                        x.saturating_sub(radius)..=(x + radius).min(width - 1)
+                    {
+                        // Distances are within the radius so are exact.
+                        #[allow(clippy::cast_precision_loss)]
+                        let distance = (x.abs_diff(nx).pow(2)
+                            + y.abs_diff(ny).pow(2))
+                            as f64;
+
+                        let difference = guide.get_pixel(nx, ny) - centre;
+                        let difference = difference.red.powi(2)
+                            + difference.green.powi(2)
+                            + difference.blue.powi(2);
+
+                        let weight =
+                            (distance * spatial + difference * colour).exp();
+
+                        total += self.get_pixel(nx, ny) * weight;
+                        total_weight += weight;
+                    }
+                }
+
+                pixels.push(total / total_weight);
+            }
+        }
+
+        Self::with_vec(self.width, self.height, pixels)
+    }
+
+    /// Count the pixels in each of `bins` bins for each channel and the
+    /// luminance, see `Histogram`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `bins` is 0.
+    #[must_use]
+    pub fn histogram(&self, bins: usize) -> Histogram {
+        assert!(bins > 0, "A histogram needs at least one bin");
+
+        // The value is clamped to 0..1 so the conversion is always valid.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_precision_loss)]
+        #[allow(clippy::cast_sign_loss)]
+        let bin = |value: f64| {
+            ((value.clamp(0.0, 1.0) * bins as f64) as usize).min(bins - 1)
+        };
+
+        let mut histogram = Histogram {
+            red: vec![0; bins],
+            green: vec![0; bins],
+            blue: vec![0; bins],
+            luminance: vec![0; bins],
+        };
+
+        for pixel in &self.pixels {
+            histogram.red[bin(pixel.red)] += 1;
+            histogram.green[bin(pixel.green)] += 1;
+            histogram.blue[bin(pixel.blue)] += 1;
+            histogram.luminance[bin(pixel.luminance())] += 1;
+        }
+
+        histogram
+    }
+
+    /// Find the minimum, maximum and mean of each channel and the number of
+    /// clipped pixels, see `CanvasStats`.
+    #[must_use]
+    pub fn stats(&self) -> CanvasStats {
+        let mut minimum =
+            Colour::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut maximum = Colour::new(
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        );
+        let mut total = Colour::black();
+        let mut clipped = 0;
+
+        for pixel in &self.pixels {
+            minimum = Colour::new(
+                minimum.red.min(pixel.red),
+                minimum.green.min(pixel.green),
+                minimum.blue.min(pixel.blue),
+            );
+            maximum = Colour::new(
+                maximum.red.max(pixel.red),
+                maximum.green.max(pixel.green),
+                maximum.blue.max(pixel.blue),
+            );
+            total += *pixel;
+
+            if pixel.red > 1.0 || pixel.green > 1.0 || pixel.blue > 1.0 {
+                clipped += 1;
+            }
+        }
+
+        CanvasStats {
+            minimum,
+            maximum,
+            mean: total / f64::from(self.width * self.height),
+            clipped,
+        }
+    }
+
+    /// Add `other` scaled by `weight` to the canvas, keeping track of the
+    /// total weight added to each pixel so that partial renders, such as those
+    /// with different sampling seeds, can be combined by calling `normalise`
+    /// once they have all been added. Accumulating into a canvas that is not
+    /// already accumulating starts from black, discarding its pixels.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the canvases are not the same size.
+    pub fn accumulate(&mut self, other: &Self, weight: f64) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Only canvases of the same size can be accumulated."
+        );
+
+        let weights = self.weights.get_or_insert_with(|| {
+            self.pixels.fill(Colour::black());
+
+            vec![0.0; self.pixels.len()]
+        });
+
+        for ((pixel, total), colour) in
+            self.pixels.iter_mut().zip(weights).zip(&other.pixels)
+        {
+            *pixel += *colour * weight;
+            *total += weight;
+        }
+    }
+
+    /// Divide each pixel by the total weight accumulated into it, see
+    /// `accumulate`. Pixels with no weight are left black and the canvas
+    /// stops accumulating.
+    pub fn normalise(&mut self) {
+        let Some(weights) = self.weights.take() else {
+            return;
+        };
+
+        for (pixel, total) in self.pixels.iter_mut().zip(weights) {
+            if total > 0.0 {
+                *pixel /= total;
+            }
+        }
+    }
+
+    /// Scale every pixel by 2 to the power of `exposure`, so each step of
+    /// exposure doubles or halves the brightness of the image.
+    pub fn apply_exposure(&mut self, exposure: f64) {
+        let scale = exposure.exp2();
+
+        for pixel in &mut self.pixels {
+            *pixel *= scale;
+        }
+    }
+
     #[must_use]
     pub fn to_ppm(&self) -> String {
+        self.to_ppm_with_mode(ClampMode::default())
+    }
+
+    #[must_use]
+    pub fn to_ppm_with_mode(&self, mode: ClampMode) -> String {
+        self.ppm(|pixel| pixel.to_u8_with_mode(mode))
+    }
+
+    /// Generate PPM data ready for display, each pixel is scaled by 2 to the
+    /// power of `exposure` and then gamma corrected before being clamped.
+    #[must_use]
+    pub fn to_ppm_with(&self, gamma: f64, exposure: f64) -> String {
+        let scale = exposure.exp2();
+
+        self.ppm(|pixel| (*pixel * scale).gamma(gamma).to_u8())
+    }
+
+    #[must_use]
+    fn ppm<F: Fn(&Colour) -> [u8; 3]>(&self, convert: F) -> String {
         let mut data = format!("P3\n{} {}\n255\n", self.width, self.height);
 
         for pixel in &self.pixels {
-            let [red, green, blue] = pixel.to_u8();
+            let [red, green, blue] = convert(pixel);
 
             data.push_str(&format!("{red} {green} {blue}\n"));
         }
@@ -62,9 +445,78 @@ impl Canvas {
 
 #[cfg(test)]
 mod tests {
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
     use super::*;
     use crate::math::float::*;
 
+    #[test]
+    fn accumulating_weighted_canvases() {
+        let mut c = Canvas::with_vec(2, 1, vec![Colour::red(); 2]);
+
+        c.accumulate(
+            &Canvas::with_vec(2, 1, vec![Colour::white(), Colour::blue()]),
+            1.0,
+        );
+        c.accumulate(
+            &Canvas::with_vec(2, 1, vec![Colour::black(), Colour::green()]),
+            3.0,
+        );
+
+        assert_approx_eq!(c.get_pixel(0, 0), Colour::white());
+        assert_approx_eq!(c.get_pixel(1, 0), Colour::new(0.0, 3.0, 1.0));
+
+        c.normalise();
+
+        assert_approx_eq!(c.get_pixel(0, 0), Colour::new(0.25, 0.25, 0.25));
+        assert_approx_eq!(c.get_pixel(1, 0), Colour::new(0.0, 0.75, 0.25));
+
+        // Normalising again does nothing once the canvas stops accumulating.
+        c.normalise();
+
+        assert_approx_eq!(c.get_pixel(1, 0), Colour::new(0.0, 0.75, 0.25));
+
+        let mut c = Canvas::new(1, 1);
+        c.accumulate(&Canvas::with_vec(1, 1, vec![Colour::white()]), 0.0);
+        c.normalise();
+
+        assert_approx_eq!(c.get_pixel(0, 0), Colour::black());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Only canvases of the same size can be accumulated."
+    )]
+    fn accumulating_canvases_of_different_sizes() {
+        Canvas::new(2, 1).accumulate(&Canvas::new(1, 2), 1.0);
+    }
+
+    /// A canvas that is black on the left and white on the right.
+    fn edge_canvas() -> Canvas {
+        let mut c = Canvas::new(20, 10);
+
+        for y in 0..10 {
+            for x in 10..20 {
+                c.write_pixel(x, y, &Colour::white());
+            }
+        }
+
+        c
+    }
+
+    fn error(lhs: &Canvas, rhs: &Canvas) -> f64 {
+        lhs.pixels
+            .iter()
+            .zip(&rhs.pixels)
+            .map(|(lhs, rhs)| {
+                let d = *lhs - *rhs;
+
+                d.red.powi(2) + d.green.powi(2) + d.blue.powi(2)
+            })
+            .sum()
+    }
+
     #[test]
     fn creating_a_canvas() {
         let c = Canvas::new(10, 20);
@@ -108,6 +560,73 @@ assertion `left == right` failed: Pixels must contain width * height values.
         let _ = Canvas::with_vec(10, 10, vec![Colour::black()]);
     }
 
+    #[test]
+    fn loading_a_canvas_from_a_file() {
+        let c = Canvas::load("src/scene/tests/assets/texture.png").unwrap();
+
+        assert_eq!(c.width, 2);
+        assert_eq!(c.height, 2);
+
+        assert!(Canvas::load("missing-image.png").is_err());
+    }
+
+    #[test]
+    fn sampling_a_canvas_at_any_resolution() {
+        let c = Canvas::with_vec(
+            2,
+            2,
+            vec![
+                Colour::red(),
+                Colour::green(),
+                Colour::blue(),
+                Colour::white(),
+            ],
+        );
+
+        assert_approx_eq!(c.sample(0.0, 0.0), Colour::red());
+        assert_approx_eq!(c.sample(0.3, 0.49), Colour::red());
+        assert_approx_eq!(c.sample(0.75, 0.25), Colour::green());
+        assert_approx_eq!(c.sample(0.25, 0.75), Colour::blue());
+        assert_approx_eq!(c.sample(1.0, 1.0), Colour::white());
+    }
+
+    #[test]
+    fn converting_a_canvas_to_and_from_raw_floats() {
+        let mut c = Canvas::new(4, 3);
+
+        c.write_pixel(0, 0, &Colour::new(0.25, 0.5, 0.75));
+        c.write_pixel(3, 1, &Colour::new(1.5, -0.5, 0.125));
+        c.write_pixel(2, 2, &Colour::white());
+
+        let r = c.to_raw_rgb_f32();
+
+        assert_eq!(r.len(), 4 * 3 * 3);
+        assert_eq!(r[0..3], [0.25, 0.5, 0.75]);
+        assert_eq!(r[21..24], [1.5, -0.5, 0.125]);
+        assert_eq!(r[30..33], [1.0, 1.0, 1.0]);
+
+        let n = Canvas::from_raw_rgb_f32(4, 3, &r);
+
+        assert_eq!(n.width, 4);
+        assert_eq!(n.height, 3);
+
+        for (lhs, rhs) in n.pixels.iter().zip(&c.pixels) {
+            assert_approx_eq!(*lhs, *rhs);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Data must contain width * height * 3 values.")]
+    fn creating_a_canvas_with_invalid_raw_floats() {
+        let _ = Canvas::from_raw_rgb_f32(2, 2, &[0.0; 11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Canvas is too large to hold as raw floats.")]
+    fn creating_a_canvas_too_large_for_raw_floats() {
+        let _ = Canvas::from_raw_rgb_f32(u32::MAX, u32::MAX, &[]);
+    }
+
     #[test]
     fn writing_pixels_to_a_canvas() {
         let mut c = Canvas::new(10, 20);
@@ -217,4 +736,239 @@ P3
 255 204 153\n"
         );
     }
+
+    #[test]
+    fn generating_ppm_data_with_a_clamp_mode() {
+        let mut c = Canvas::new(1, 1);
+
+        c.write_pixel(0, 0, &Colour::new(2.0, 1.0, 0.5));
+
+        assert_eq!(c.to_ppm(), "P3\n1 1\n255\n255 255 128\n");
+        assert_eq!(
+            c.to_ppm_with_mode(ClampMode::HuePreserving),
+            "P3\n1 1\n255\n255 128 64\n"
+        );
+    }
+
+    #[test]
+    fn generating_ppm_data_with_gamma_and_exposure() {
+        let mut c = Canvas::new(2, 1);
+
+        c.write_pixel(0, 0, &Colour::new(0.5, 0.5, 0.5));
+        c.write_pixel(1, 0, &Colour::new(0.25, 0.0, 1.0));
+
+        assert_eq!(c.to_ppm(), "P3\n2 1\n255\n128 128 128\n64 0 255\n");
+        assert_eq!(
+            c.to_ppm_with(1.0, 0.0),
+            "P3\n2 1\n255\n128 128 128\n64 0 255\n"
+        );
+        assert_eq!(
+            c.to_ppm_with(2.2, 0.0),
+            "P3\n2 1\n255\n186 186 186\n136 0 255\n"
+        );
+        assert_eq!(
+            c.to_ppm_with(2.2, 1.0),
+            "P3\n2 1\n255\n255 255 255\n186 0 255\n"
+        );
+    }
+
+    #[test]
+    fn iterating_over_the_pixels_of_a_canvas() {
+        let mut c = Canvas::new(3, 2);
+
+        assert_eq!(c.pixels().len(), 6);
+
+        for (index, pixel) in c.pixels_mut().enumerate() {
+            let value = f64::from(u8::try_from(index).unwrap());
+
+            *pixel = Colour::new(value, 0.0, 0.0);
+        }
+
+        assert_approx_eq!(c.get_pixel(2, 0), Colour::new(2.0, 0.0, 0.0));
+        assert_approx_eq!(c.get_pixel(0, 1), Colour::new(3.0, 0.0, 0.0));
+
+        let mut pixels = c.pixels();
+
+        for y in 0..2 {
+            for x in 0..3 {
+                assert_approx_eq!(*pixels.next().unwrap(), c.get_pixel(x, y));
+            }
+        }
+
+        assert!(pixels.next().is_none());
+    }
+
+    #[test]
+    fn mapping_a_canvas_in_parallel() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let c = Canvas::with_vec(
+            16,
+            8,
+            (0..16 * 8)
+                .map(|_| Colour::new(r.gen(), r.gen(), r.gen()))
+                .collect(),
+        );
+
+        let balance = |colour: &Colour| {
+            Colour::new(colour.red * 1.1, colour.green, colour.blue * 0.9)
+        };
+
+        let m = c.par_map(balance);
+
+        assert_eq!(m.width, 16);
+        assert_eq!(m.height, 8);
+
+        for (lhs, rhs) in m.pixels().zip(c.pixels().map(balance)) {
+            assert_approx_eq!(*lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn applying_exposure_to_a_canvas() {
+        let mut c = Canvas::new(2, 1);
+
+        c.write_pixel(0, 0, &Colour::new(0.2, 0.4, 0.1));
+
+        c.apply_exposure(1.0);
+
+        assert_approx_eq!(c.get_pixel(0, 0), Colour::new(0.4, 0.8, 0.2));
+        assert_approx_eq!(c.get_pixel(1, 0), Colour::black());
+
+        c.apply_exposure(-2.0);
+
+        assert_approx_eq!(c.get_pixel(0, 0), Colour::new(0.1, 0.2, 0.05));
+    }
+
+    #[test]
+    fn finding_the_histogram_and_stats_of_a_canvas() {
+        let mut c = Canvas::new(100, 1);
+
+        for x in 0..100_u32 {
+            let value = (f64::from(x) + 0.5) / 100.0;
+
+            c.write_pixel(x as usize, 0, &Colour::new(value, 0.5, 2.0 * value));
+        }
+
+        let h = c.histogram(10);
+
+        // The red gradient is spread evenly, green is constant and half of
+        // blue is above 1.0 so lands in the last bin.
+        assert_eq!(h.red, vec![10; 10]);
+        assert_eq!(h.green, vec![0, 0, 0, 0, 0, 100, 0, 0, 0, 0]);
+        assert_eq!(h.blue, vec![5, 5, 5, 5, 5, 5, 5, 5, 5, 55]);
+        assert_eq!(h.luminance.iter().sum::<usize>(), 100);
+
+        let s = c.stats();
+
+        assert_approx_eq!(s.minimum, Colour::new(0.005, 0.5, 0.01));
+        assert_approx_eq!(s.maximum, Colour::new(0.995, 0.5, 1.99));
+        assert_approx_eq!(s.mean, Colour::new(0.5, 0.5, 1.0));
+        assert_eq!(s.clipped, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "A histogram needs at least one bin")]
+    fn finding_a_histogram_with_no_bins() {
+        let _ = Canvas::new(10, 10).histogram(0);
+    }
+
+    #[test]
+    fn denoising_a_canvas_preserves_edges() {
+        let clean = edge_canvas();
+
+        let mut noisy = clean.clone();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        for pixel in &mut noisy.pixels {
+            let n = r.gen_range(-0.15..0.15);
+
+            *pixel += Colour::new(n, n, n);
+        }
+
+        let denoised = noisy.denoise_bilateral(1.5, 0.3);
+
+        assert!(error(&denoised, &clean) < error(&noisy, &clean) / 2.0);
+
+        for y in 0..10 {
+            assert!(denoised.get_pixel(9, y).red < 0.1);
+            assert!(denoised.get_pixel(10, y).red > 0.9);
+        }
+    }
+
+    #[test]
+    fn denoising_salt_and_pepper_noise() {
+        let clean = edge_canvas();
+
+        let mut noisy = clean.clone();
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        for pixel in &mut noisy.pixels {
+            if r.gen_bool(0.1) {
+                *pixel = if r.gen_bool(0.5) {
+                    Colour::white()
+                } else {
+                    Colour::black()
+                };
+            }
+        }
+
+        // An impulse differs from its neighbours as much as the edge does, a
+        // small sigma_colour treats each one as an edge and keeps it.
+        let kept = noisy.denoise_bilateral(1.5, 0.3);
+
+        assert_approx_eq!(
+            error(&kept, &clean),
+            error(&noisy, &clean),
+            epsilon = 0.001
+        );
+
+        // Spanning the jump lets the many flat neighbours outweigh it.
+        let denoised = noisy.denoise_bilateral(1.5, 1.0);
+
+        assert!(error(&denoised, &clean) < error(&noisy, &clean) / 2.0);
+    }
+
+    #[test]
+    fn denoising_a_canvas_with_a_guide() {
+        let guide = edge_canvas();
+
+        let mut c = Canvas::new(20, 10);
+        c.write_pixel(9, 5, &Colour::white());
+        c.write_pixel(10, 5, &Colour::red());
+
+        let d = c.denoise_bilateral_with_guide(1.0, 0.1, &guide);
+
+        // The bright pixel is spread over its own side of the edge only.
+        assert!(d.get_pixel(8, 5).red > 0.0);
+        assert!(d.get_pixel(8, 5).blue > 0.0);
+        assert_approx_eq!(d.get_pixel(11, 5).blue, 0.0, epsilon = 0.000_01);
+        assert!(d.get_pixel(11, 5).red > 0.0);
+        assert_approx_eq!(d.get_pixel(7, 5).red, d.get_pixel(7, 5).blue);
+    }
+
+    #[test]
+    fn denoising_with_a_sigma_of_zero_leaves_the_canvas_unchanged() {
+        let c = edge_canvas();
+
+        for d in [
+            c.denoise_bilateral(0.0, 0.3),
+            c.denoise_bilateral(1.5, 0.0),
+            c.denoise_bilateral_with_guide(0.0, 0.0, &c),
+        ] {
+            for (lhs, rhs) in d.pixels.iter().zip(&c.pixels) {
+                assert_approx_eq!(*lhs, *rhs);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Guide must be the same size as the canvas.")]
+    fn denoising_with_an_invalid_guide() {
+        let _ = Canvas::new(5, 5).denoise_bilateral_with_guide(
+            1.0,
+            0.1,
+            &Canvas::new(4, 5),
+        );
+    }
 }
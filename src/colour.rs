@@ -1,11 +1,51 @@
-use std::ops::{Mul, MulAssign};
+use std::{
+    ops::{Mul, MulAssign},
+    str::FromStr,
+};
 
+use anyhow::bail;
 use derive_more::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign,
 };
 use derive_new::new;
+use float_cmp::{ApproxEq, F64Margin};
+use serde::{de::Error, Deserialize, Deserializer};
+
+use crate::math::Vector;
+
+/// `ClampMode` determines how a `Colour` with channels outside the range
+/// 0.0..1.0 is converted into a displayable value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClampMode {
+    /// Clamp each channel independently, this can shift the hue of over bright
+    /// colours.
+    #[default]
+    PerChannel,
+    /// Scale all channels down by the largest channel when it exceeds 1.0,
+    /// preserving the ratio between channels and therefore the hue.
+    HuePreserving,
+}
 
-use crate::{math::float::impl_approx_eq, util::impl_deserialize_tuple};
+impl FromStr for ClampMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "per-channel" => Ok(Self::PerChannel),
+            "hue-preserving" => Ok(Self::HuePreserving),
+            _ => bail!("Unknown clamp mode '{s}'"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ClampMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(Error::custom)
+    }
+}
 
 /// A Colour represents an RGB colour in the image, values generally range from
 /// 0.0..1.0 but can go outside this range before final processing.
@@ -59,8 +99,160 @@ impl Colour {
         Self::new(0.0, 1.0, 1.0)
     }
 
+    /// Look up a colour by name, the names of the colour constructors above
+    /// map to those colours with the remaining names taken from the CSS named
+    /// colours. Also accepts hex strings in the form "#rrggbb" or "#rgb". Hex
+    /// values, including those behind the CSS names, are 8-bit sRGB so are
+    /// converted into linear space as image texels are.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+
+        if let Some(hex) = name.strip_prefix('#') {
+            return Self::from_hex(hex);
+        }
+
+        let colour = match name.as_str() {
+            "black" => Self::black(),
+            "white" => Self::white(),
+            "red" => Self::red(),
+            "green" => Self::green(),
+            "blue" => Self::blue(),
+            "yellow" => Self::yellow(),
+            "purple" | "magenta" | "fuchsia" => Self::purple(),
+            "cyan" | "aqua" => Self::cyan(),
+            _ => {
+                let hex = match name.as_str() {
+                    "brown" => "a52a2a",
+                    "coral" => "ff7f50",
+                    "cornflowerblue" => "6495ed",
+                    "crimson" => "dc143c",
+                    "gold" => "ffd700",
+                    "gray" | "grey" => "808080",
+                    "indigo" => "4b0082",
+                    "ivory" => "fffff0",
+                    "lavender" => "e6e6fa",
+                    "lime" => "00ff00",
+                    "maroon" => "800000",
+                    "navy" => "000080",
+                    "olive" => "808000",
+                    "orange" => "ffa500",
+                    "pink" => "ffc0cb",
+                    "salmon" => "fa8072",
+                    "silver" => "c0c0c0",
+                    "skyblue" => "87ceeb",
+                    "tan" => "d2b48c",
+                    "teal" => "008080",
+                    "turquoise" => "40e0d0",
+                    "violet" => "ee82ee",
+                    _ => return None,
+                };
+
+                return Self::from_hex(hex);
+            }
+        };
+
+        Some(colour)
+    }
+
+    #[must_use]
+    fn from_hex(hex: &str) -> Option<Self> {
+        let channel = |value: &str| {
+            u8::from_str_radix(value, 16).ok().map(|c| f64::from(c) / 255.0)
+        };
+
+        let colour = match hex.len() {
+            6 => Self::new(
+                channel(hex.get(0..2)?)?,
+                channel(hex.get(2..4)?)?,
+                channel(hex.get(4..6)?)?,
+            ),
+            3 => {
+                let double = |index| {
+                    let c = hex.get(index..=index)?;
+                    channel(&format!("{c}{c}"))
+                };
+
+                Self::new(double(0)?, double(1)?, double(2)?)
+            }
+            _ => return None,
+        };
+
+        Some(colour.to_linear())
+    }
+
+    /// Convert a colour encoded with the sRGB transfer function, as is the case
+    /// for most images, into linear space so it can be used in lighting
+    /// calculations.
+    #[must_use]
+    pub fn to_linear(&self) -> Self {
+        let convert = |c: f64| {
+            if c <= 0.040_45 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self::new(convert(self.red), convert(self.green), convert(self.blue))
+    }
+
+    /// Apply gamma correction for display, raising each channel to the power
+    /// of one over `gamma`. Negative channels become zero.
+    #[must_use]
+    pub fn gamma(&self, gamma: f64) -> Self {
+        let convert = |c: f64| c.max(0.0).powf(gamma.recip());
+
+        Self::new(convert(self.red), convert(self.green), convert(self.blue))
+    }
+
+    /// The perceived brightness of the colour using the Rec. 709 weights for
+    /// each channel.
+    #[must_use]
+    pub fn luminance(&self) -> f64 {
+        0.212_6 * self.red + 0.715_2 * self.green + 0.072_2 * self.blue
+    }
+
+    /// Encode a unit normal as a colour for display, mapping each axis from
+    /// -1.0..1.0 to 0.0..1.0 in the red, green and blue channels.
+    #[must_use]
+    pub fn from_normal(normal: &Vector) -> Self {
+        (Self::new(normal.x, normal.y, normal.z) + Self::white()) / 2.0
+    }
+
+    /// Check that no channel is NaN or infinite, a bad calculation such as a
+    /// refraction with a zero index can otherwise quietly spread through an
+    /// image.
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.red.is_finite() && self.green.is_finite() && self.blue.is_finite()
+    }
+
+    #[must_use]
+    fn is_nan(&self) -> bool {
+        self.red.is_nan() || self.green.is_nan() || self.blue.is_nan()
+    }
+
     #[must_use]
     pub fn to_u8(&self) -> [u8; 3] {
+        self.to_u8_with_mode(ClampMode::default())
+    }
+
+    #[must_use]
+    pub fn to_u8_with_mode(&self, mode: ClampMode) -> [u8; 3] {
+        let colour = match mode {
+            ClampMode::PerChannel => *self,
+            ClampMode::HuePreserving => {
+                let max = self.red.max(self.green).max(self.blue);
+
+                if max > 1.0 {
+                    *self / max
+                } else {
+                    *self
+                }
+            }
+        };
+
         // There is no nice way to do a conversion from f64 to a u8 so we are
         // forced to use `as` and the clamp and multiplication guarantee that we
         // are within the value of a u8.
@@ -68,7 +260,7 @@ impl Colour {
         #[allow(clippy::cast_sign_loss)]
         let convert = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
 
-        [convert(self.red), convert(self.green), convert(self.blue)]
+        [convert(colour.red), convert(colour.green), convert(colour.blue)]
     }
 }
 
@@ -100,9 +292,43 @@ impl MulAssign for Colour {
     }
 }
 
-impl_approx_eq!(Colour { red, green, blue });
+/// Comparing NaNs with `float_cmp` can succeed when their bits match, so any
+/// colour with a NaN channel is treated as unequal to every colour, including
+/// itself.
+impl ApproxEq for Colour {
+    type Margin = F64Margin;
 
-impl_deserialize_tuple!(Colour);
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+
+        !self.is_nan()
+            && !other.is_nan()
+            && self.red.approx_eq(other.red, margin)
+            && self.green.approx_eq(other.green, margin)
+            && self.blue.approx_eq(other.blue, margin)
+    }
+}
+
+impl<'de> Deserialize<'de> for Colour {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Colour {
+            Rgb([f64; 3]),
+            Name(String),
+        }
+
+        match Colour::deserialize(deserializer)? {
+            Colour::Rgb([red, green, blue]) => Ok(Self::new(red, green, blue)),
+            Colour::Name(name) => Self::from_name(&name).ok_or_else(|| {
+                Error::custom(format!("Unknown colour '{name}'"))
+            }),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -136,6 +362,58 @@ mod tests {
 
         assert_eq!(Colour::new(-0.3, 0.5, 1.0).to_u8(), [0, 128, 255]);
         assert_eq!(Colour::new(0.2, 0.51, 0.9).to_u8(), [51, 130, 230]);
+
+        assert_eq!(
+            Colour::new(0.2, 0.51, 0.9).to_u8_with_mode(ClampMode::PerChannel),
+            [51, 130, 230]
+        );
+        assert_eq!(
+            Colour::new(2.0, 1.0, 1.0).to_u8_with_mode(ClampMode::PerChannel),
+            [255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn generating_hue_preserving_u8_values_from_a_colour() {
+        let c = Colour::new(2.0, 1.0, 0.5);
+
+        assert_eq!(c.to_u8_with_mode(ClampMode::HuePreserving), [255, 128, 64]);
+
+        let [red, green, blue] = c.to_u8_with_mode(ClampMode::HuePreserving);
+        assert_approx_eq!(
+            f64::from(green) / f64::from(red),
+            0.5,
+            epsilon = 0.01
+        );
+        assert_approx_eq!(
+            f64::from(blue) / f64::from(red),
+            0.25,
+            epsilon = 0.01
+        );
+
+        assert_eq!(
+            Colour::new(-0.3, 0.5, 1.0)
+                .to_u8_with_mode(ClampMode::HuePreserving),
+            [0, 128, 255]
+        );
+        assert_eq!(ClampMode::default(), ClampMode::PerChannel);
+    }
+
+    #[test]
+    fn parsing_clamp_modes() {
+        assert_eq!(
+            "per-channel".parse::<ClampMode>().unwrap(),
+            ClampMode::PerChannel
+        );
+        assert_eq!(
+            from_str::<ClampMode>("hue-preserving").unwrap(),
+            ClampMode::HuePreserving
+        );
+
+        assert_eq!(
+            from_str::<ClampMode>("foo").unwrap_err().to_string(),
+            "Unknown clamp mode 'foo'"
+        );
     }
 
     #[test]
@@ -219,10 +497,109 @@ mod tests {
         assert_approx_ne!(c1, c3);
     }
 
+    #[test]
+    fn the_luminance_of_a_colour() {
+        assert_approx_eq!(Colour::white().luminance(), 1.0);
+        assert_approx_eq!(Colour::black().luminance(), 0.0);
+        assert_approx_eq!(Colour::green().luminance(), 0.715_2);
+        assert_approx_eq!(Colour::new(0.5, 0.5, 0.5).luminance(), 0.5);
+    }
+
+    #[test]
+    fn encoding_a_normal_as_a_colour() {
+        assert_approx_eq!(
+            Colour::from_normal(&Vector::y_axis()),
+            Colour::new(0.5, 1.0, 0.5)
+        );
+        assert_approx_eq!(
+            Colour::from_normal(&-Vector::z_axis()),
+            Colour::new(0.5, 0.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn detecting_non_finite_colours() {
+        let c = Colour::new(0.1, f64::NAN, 0.9);
+
+        assert!(Colour::new(0.1, 0.7, 0.9).is_finite());
+        assert!(!c.is_finite());
+        assert!(!Colour::new(f64::INFINITY, 0.0, 0.0).is_finite());
+
+        assert_approx_ne!(c, c);
+        assert_approx_ne!(c, Colour::new(0.1, 0.7, 0.9));
+        assert_approx_ne!(Colour::new(0.1, 0.7, 0.9), c);
+    }
+
+    #[test]
+    fn gamma_correcting_a_colour() {
+        let c = Colour::new(0.5, -0.2, 1.0).gamma(2.2);
+
+        assert_approx_eq!(c.red, 0.5_f64.powf(1.0 / 2.2));
+        assert_approx_eq!(c.red, 0.729_74, epsilon = 0.000_01);
+        assert_approx_eq!(c.green, 0.0);
+        assert_approx_eq!(c.blue, 1.0);
+
+        assert_approx_eq!(
+            Colour::new(0.3, 0.6, 0.9).gamma(1.0),
+            Colour::new(0.3, 0.6, 0.9)
+        );
+    }
+
+    #[test]
+    fn converting_a_colour_to_linear_space() {
+        let c = Colour::new(0.5, 0.02, 1.0).to_linear();
+
+        assert_approx_eq!(c.red, ((0.5 + 0.055) / 1.055_f64).powf(2.4));
+        assert_approx_eq!(c.red, 0.214_04, epsilon = 0.000_01);
+        assert_approx_eq!(c.green, 0.02 / 12.92);
+        assert_approx_eq!(c.blue, 1.0);
+
+        assert_approx_eq!(Colour::black().to_linear(), Colour::black());
+    }
+
     #[test]
     fn deserialize_colour() {
         let c: Colour = from_str("[0.5, 0.3, 0.8]").unwrap();
 
         assert_approx_eq!(c, Colour::new(0.5, 0.3, 0.8));
     }
+
+    #[test]
+    fn deserialize_named_colour() {
+        let c: Colour = from_str("red").unwrap();
+
+        assert_approx_eq!(c, Colour::red());
+
+        let c: Colour = from_str("\"Cyan\"").unwrap();
+
+        assert_approx_eq!(c, Colour::cyan());
+
+        let c: Colour = from_str("cornflowerblue").unwrap();
+
+        assert_approx_eq!(
+            c,
+            Colour::new(100.0 / 255.0, 149.0 / 255.0, 237.0 / 255.0)
+                .to_linear()
+        );
+
+        assert_eq!(
+            from_str::<Colour>("notacolour").unwrap_err().to_string(),
+            "Unknown colour 'notacolour'"
+        );
+    }
+
+    #[test]
+    fn deserialize_hex_colour() {
+        let c: Colour = from_str("\"#ff8800\"").unwrap();
+
+        assert_approx_eq!(c, Colour::new(1.0, 0.246_2, 0.0), epsilon = 0.000_1);
+        assert_approx_eq!(c, Colour::new(1.0, 136.0 / 255.0, 0.0).to_linear());
+
+        let c: Colour = from_str("\"#0f0\"").unwrap();
+
+        assert_approx_eq!(c, Colour::green());
+
+        assert!(from_str::<Colour>("\"#ff880\"").is_err());
+        assert!(from_str::<Colour>("\"#gg8800\"").is_err());
+    }
 }
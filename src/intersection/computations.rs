@@ -2,7 +2,7 @@ use derive_new::new;
 
 use crate::{
     math::{Point, Vector},
-    Object,
+    Colour, Object,
 };
 
 /// The `Computations` struct is a helper structure to store precomputed values
@@ -21,6 +21,20 @@ pub struct Computations<'a> {
     pub reflect: Vector,
     pub n1: f64,
     pub n2: f64,
+    /// The wavelength carried by the ray, see `Ray`.
+    #[new(default)]
+    pub wavelength: Option<f64>,
+    /// Whether the ray was reflected from a rough surface, see `Ray`.
+    #[new(default)]
+    pub glossy: bool,
+    /// The colour seen by rays that miss everything, overriding the
+    /// background of the `World`, such as when a `Camera` sets its own.
+    #[new(default)]
+    pub background: Option<Colour>,
+    /// The colour blended from the vertex colours of the object, replacing
+    /// the colour of its material's pattern, see `Triangle::with_colours`.
+    #[new(default)]
+    pub vertex_colour: Option<Colour>,
 }
 
 impl<'a> Computations<'a> {
@@ -43,6 +57,23 @@ impl<'a> Computations<'a> {
 
         r0 + (1.0 - r0) * (1.0 - cos).powi(5)
     }
+
+    /// Use Snell's Law to find the direction of the refracted ray, returns
+    /// `None` under total internal reflection.
+    #[must_use]
+    pub fn refracted_direction(&self) -> Option<Vector> {
+        let n_ratio = self.n1 / self.n2;
+        let cos_i = self.eye.dot(&self.normal);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+
+        if sin2_t > 1.0 {
+            return None;
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+
+        Some(self.normal * (n_ratio * cos_i - cos_t) - self.eye * n_ratio)
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +87,73 @@ mod tests {
         Material, Object,
     };
 
+    #[test]
+    fn the_refracted_direction_depends_on_wavelength() {
+        let o = Object::sphere_builder()
+            .material(Material::builder().refractive_index(1.5).build())
+            .build();
+
+        let r = Ray::new(Point::new(0.0, 0.5, -5.0), Vector::z_axis());
+
+        let l = o.intersect(&r).unwrap();
+
+        let c = l[0].prepare_computations(&r, &l);
+
+        assert!(c.wavelength.is_none());
+
+        let d1 = c.refracted_direction().unwrap();
+
+        let d2 = l[0]
+            .prepare_computations(&r.with_wavelength(Some(0.45)), &l)
+            .refracted_direction()
+            .unwrap();
+
+        assert_approx_eq!(d1, d2);
+
+        let o = Object::sphere_builder()
+            .material(
+                Material::builder()
+                    .refractive_index(1.5)
+                    .dispersion(0.01)
+                    .build(),
+            )
+            .build();
+
+        let l = o.intersect(&r).unwrap();
+
+        let c1 = l[0].prepare_computations(&r.with_wavelength(Some(0.45)), &l);
+        let c2 = l[0].prepare_computations(&r.with_wavelength(Some(0.65)), &l);
+
+        assert_approx_eq!(c1.wavelength.unwrap(), 0.45);
+        assert!(c1.n2 > c2.n2);
+
+        // Shorter wavelengths bend further towards the normal.
+        let d1 = c1.refracted_direction().unwrap();
+        let d2 = c2.refracted_direction().unwrap();
+
+        assert_approx_ne!(d1, d2);
+        assert!(d1.dot(&-c1.normal) > d2.dot(&-c2.normal));
+    }
+
+    #[test]
+    fn refracting_under_total_internal_reflection() {
+        let o = Object::sphere_builder().material(Material::glass()).build();
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(Point::new(0.0, 0.0, sqrt_2_div_2), Vector::y_axis());
+
+        let l = List::from(vec![
+            Intersection::new(&o, -sqrt_2_div_2),
+            Intersection::new(&o, sqrt_2_div_2),
+        ]);
+
+        assert!(l[1]
+            .prepare_computations(&r, &l)
+            .refracted_direction()
+            .is_none());
+    }
+
     #[test]
     fn the_schlick_approximation_under_total_internal_reflection() {
         let o = Object::sphere_builder().material(Material::glass()).build();
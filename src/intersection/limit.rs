@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::List;
+
+/// A `Limit` caps the number of intersections we keep for any single ray,
+/// guarding against pathological scenes where a ray passes through a huge
+/// number of coincident surfaces. It also keeps count of how many times a list
+/// has been truncated so that this can be reported once rendering is complete.
+#[derive(Debug)]
+pub struct Limit {
+    maximum: usize,
+    truncated: AtomicUsize,
+}
+
+impl Limit {
+    #[must_use]
+    pub const fn new(maximum: usize) -> Self {
+        Self { maximum, truncated: AtomicUsize::new(0) }
+    }
+
+    #[must_use]
+    pub const fn maximum(&self) -> usize {
+        self.maximum
+    }
+
+    #[must_use]
+    pub fn truncated(&self) -> usize {
+        self.truncated.load(Ordering::Relaxed)
+    }
+
+    /// Reset the count of truncated lists, this is done at the start of each
+    /// render so the count only covers that render.
+    pub fn reset(&self) {
+        self.truncated.store(0, Ordering::Relaxed);
+    }
+
+    /// Truncate the list to at most `maximum` intersections in front of the ray
+    /// origin, nearest first, so the hit is always kept. Intersections at or
+    /// behind the origin are all kept as refraction needs them to find which
+    /// media the ray starts in. The list is left sorted if it was truncated.
+    pub fn apply(&self, list: &mut List) {
+        let in_front =
+            list.iter().filter(|intersection| intersection.t > 0.0).count();
+
+        if in_front <= self.maximum {
+            return;
+        }
+
+        list.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        let first = list
+            .iter()
+            .position(|intersection| intersection.t > 0.0)
+            .unwrap_or(list.len());
+
+        list.truncate(first + self.maximum);
+
+        self.truncated.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Clone for Limit {
+    fn clone(&self) -> Self {
+        Self {
+            maximum: self.maximum,
+            truncated: AtomicUsize::new(self.truncated()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{intersection::Intersection, math::float::*, Object};
+
+    #[test]
+    fn creating_a_limit() {
+        let l = Limit::new(5);
+
+        assert_eq!(l.maximum(), 5);
+        assert_eq!(l.truncated(), 0);
+    }
+
+    #[test]
+    fn applying_a_limit_to_a_list() {
+        let o = Object::test_builder().build();
+
+        let mut i = List::from(vec![
+            Intersection::new(&o, 4.0),
+            Intersection::new(&o, -0.5),
+            Intersection::new(&o, 2.0),
+            Intersection::new(&o, 3.0),
+            Intersection::new(&o, -2.0),
+        ]);
+
+        let l = Limit::new(5);
+        l.apply(&mut i);
+
+        assert_eq!(i.len(), 5);
+        assert_eq!(l.truncated(), 0);
+
+        let l = Limit::new(3);
+        l.apply(&mut i);
+
+        assert_eq!(i.len(), 5);
+        assert_eq!(l.truncated(), 0);
+
+        let l = Limit::new(2);
+        l.apply(&mut i);
+
+        assert_eq!(i.len(), 4);
+        assert_eq!(l.truncated(), 1);
+        assert_approx_eq!(i[0].t, -2.0);
+        assert_approx_eq!(i[1].t, -0.5);
+        assert_approx_eq!(i[2].t, 2.0);
+        assert_approx_eq!(i[3].t, 3.0);
+
+        let l = Limit::new(1);
+        let mut i = List::from(vec![
+            Intersection::new(&o, 6.0),
+            Intersection::new(&o, -0.1),
+            Intersection::new(&o, 2.0),
+        ]);
+        l.apply(&mut i);
+
+        assert_eq!(i.len(), 2);
+        assert_approx_eq!(i[0].t, -0.1);
+        assert_approx_eq!(i.hit().unwrap().t, 2.0);
+
+        let c = l.clone();
+        assert_eq!(c.truncated(), 1);
+
+        l.reset();
+        assert_eq!(l.truncated(), 0);
+    }
+
+    #[test]
+    fn applying_a_limit_to_a_list_with_nan() {
+        let o = Object::test_builder().build();
+
+        let mut i = List::from(vec![
+            Intersection::new(&o, f64::NAN),
+            Intersection::new(&o, 3.0),
+            Intersection::new(&o, 1.0),
+        ]);
+
+        let l = Limit::new(1);
+        l.apply(&mut i);
+
+        assert_eq!(i.len(), 1);
+        assert_approx_eq!(i[0].t, 1.0);
+        assert_eq!(l.truncated(), 1);
+    }
+}
@@ -22,20 +22,40 @@ impl<'a> List<'a> {
     /// values and +-Inf return orderings when compared.
     #[must_use]
     pub fn hit(&self) -> Option<Intersection<'a>> {
+        self.hit_within(0.0, f64::INFINITY)
+    }
+
+    /// Find the intersection with the smallest t value greater than `minimum`
+    /// and no greater than `maximum`. Assumes the list of intersections is not
+    /// sorted. Of the intersections with the same t value the one added to the
+    /// list first wins, lists are filled by walking the objects in the order
+    /// they were added to the world so the same one wins on every run.
+    ///
+    /// NaN t values never fall within the range so are ignored.
+    #[must_use]
+    pub fn hit_within(
+        &self,
+        minimum: f64,
+        maximum: f64,
+    ) -> Option<Intersection<'a>> {
         self.0
             .iter()
-            .filter(|val| val.t > 0.0)
-            .min_by(|a, b| {
-                a.t.partial_cmp(&b.t).unwrap_or_else(|| unreachable!())
-            })
+            .filter(|val| val.t > minimum && val.t <= maximum)
+            .min_by(|a, b| a.t.total_cmp(&b.t))
             .copied()
     }
 
     #[must_use]
+    #[allow(clippy::should_implement_trait)]
     pub fn into_iter(self) -> IntoIter<Intersection<'a>> {
         self.0.into_iter()
     }
 
+    /// Sort the list by ascending t value.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any of the t values are NaN.
     pub fn sort(&mut self) {
         self.sort_by(|a, b| {
             a.t.partial_cmp(&b.t).unwrap_or_else(|| {
@@ -64,7 +84,10 @@ impl<'a> Default for List<'a> {
 
 #[cfg(test)]
 mod tests {
-    use std::f64::{INFINITY, NEG_INFINITY};
+    use std::{
+        f64::{INFINITY, NEG_INFINITY},
+        ptr,
+    };
 
     use super::*;
     use crate::{math::float::*, Object};
@@ -149,6 +172,26 @@ mod tests {
         assert!(List::from(vec![i1, i2]).hit().is_none());
     }
 
+    #[test]
+    fn the_hit_within_a_range_of_t_values() {
+        let o = Object::test_builder().build();
+
+        let l = List::from(vec![
+            Intersection::new(&o, 5.0),
+            Intersection::new(&o, 7.0),
+            Intersection::new(&o, -3.0),
+            Intersection::new(&o, 2.0),
+        ]);
+
+        assert_approx_eq!(l.hit_within(0.0, f64::INFINITY).unwrap().t, 2.0);
+        assert_approx_eq!(l.hit_within(2.0, f64::INFINITY).unwrap().t, 5.0);
+        assert_approx_eq!(l.hit_within(3.0, 7.0).unwrap().t, 5.0);
+        assert_approx_eq!(l.hit_within(5.5, 7.0).unwrap().t, 7.0);
+        assert_approx_eq!(l.hit_within(-5.0, 0.0).unwrap().t, -3.0);
+        assert!(l.hit_within(7.0, f64::INFINITY).is_none());
+        assert!(l.hit_within(2.5, 4.5).is_none());
+    }
+
     #[test]
     fn the_hit_is_always_the_lowest_nonnegative_intersection() {
         let o = Object::test_builder().build();
@@ -183,6 +226,36 @@ mod tests {
 
         assert_approx_eq!(h.object, &o);
         assert_approx_eq!(h.t, 2.5);
+
+        assert!(List::from(vec![
+            Intersection::new(&o, f64::NAN),
+            Intersection::new(&o, -f64::NAN),
+        ])
+        .hit()
+        .is_none());
+    }
+
+    #[test]
+    fn the_hit_with_equal_t_values_is_the_first_added() {
+        let o1 = Object::test_builder().build();
+        let o2 = Object::sphere_builder().build();
+
+        let i1 = Intersection::new(&o1, 3.0);
+        let i2 = Intersection::new(&o2, 3.0);
+        let i3 = Intersection::new(&o2, 4.0);
+
+        let h1 = List::from(vec![i1, i2, i3]).hit().unwrap();
+        let h2 = List::from(vec![i3, i2, i1]).hit().unwrap();
+
+        assert!(ptr::eq(h1.object, i1.object));
+        assert!(ptr::eq(h2.object, i2.object));
+        assert_approx_eq!(h1.t, 3.0);
+        assert_approx_eq!(h2.t, 3.0);
+
+        let h = List::from(vec![i3, i2]).hit().unwrap();
+
+        assert!(ptr::eq(h.object, i2.object));
+        assert_approx_eq!(h.t, 3.0);
     }
 
     #[test]
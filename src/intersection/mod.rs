@@ -1,4 +1,5 @@
 mod computations;
+mod limit;
 mod list;
 mod t_list;
 mod t_values;
@@ -8,7 +9,8 @@ use std::f64::EPSILON;
 use float_cmp::{ApproxEq, F64Margin};
 
 pub use self::{
-    computations::Computations, list::List, t_list::TList, t_values::TValues,
+    computations::Computations, limit::Limit, list::List, t_list::TList,
+    t_values::TValues,
 };
 use crate::{
     math::{float::approx_eq, Ray},
@@ -23,12 +25,15 @@ pub struct Intersection<'a> {
     pub object: &'a Object,
     pub t: f64,
     pub u_v: Option<(f64, f64)>,
+    /// The outermost CSG that the object is part of, all of its surfaces bound
+    /// a single solid so refraction treats them as one medium.
+    pub csg: Option<&'a Object>,
 }
 
 impl<'a> Intersection<'a> {
     #[must_use]
     pub const fn new(object: &'a Object, t: f64) -> Self {
-        Self { object, t, u_v: None }
+        Self { object, t, u_v: None, csg: None }
     }
 
     #[must_use]
@@ -38,7 +43,7 @@ impl<'a> Intersection<'a> {
         u: f64,
         v: f64,
     ) -> Self {
-        Self { object, t, u_v: Some((u, v)) }
+        Self { object, t, u_v: Some((u, v)), csg: None }
     }
 
     #[must_use]
@@ -59,7 +64,17 @@ impl<'a> Intersection<'a> {
             false
         };
 
-        let mut container = Vec::<&Object>::new();
+        let refractive_index = |object: &Object| {
+            let material = object.material();
+
+            ray.wavelength.map_or(material.refractive_index, |wavelength| {
+                material.refractive_index_at(wavelength)
+            })
+        };
+
+        // Each entry holds the object that bounds a medium, a CSG for shapes
+        // within one, and the shape whose material was entered.
+        let mut container = Vec::<(&Object, &Object)>::new();
 
         let mut n1 = f64::NAN;
         let mut n2 = f64::NAN;
@@ -70,30 +85,32 @@ impl<'a> Intersection<'a> {
             if is_hit {
                 n1 = container.last().map_or_else(
                     || 1.0,
-                    |object| object.material().refractive_index,
+                    |(_, object)| refractive_index(object),
                 );
             }
 
+            let medium = intersection.csg.unwrap_or(intersection.object);
+
             if let Some(index) = container
                 .iter()
-                .position(|object| approx_eq!(object, intersection.object))
+                .position(|(object, _)| approx_eq!(*object, medium))
             {
                 container.remove(index);
             } else {
-                container.push(intersection.object);
+                container.push((medium, intersection.object));
             }
 
             if is_hit {
                 n2 = container.last().map_or_else(
                     || 1.0,
-                    |object| object.material().refractive_index,
+                    |(_, object)| refractive_index(object),
                 );
 
                 break;
             }
         }
 
-        Computations::new(
+        let mut computations = Computations::new(
             self.object,
             self.t,
             point,
@@ -105,7 +122,13 @@ impl<'a> Intersection<'a> {
             ray.direction.reflect(&normal),
             n1,
             n2,
-        )
+        );
+
+        computations.wavelength = ray.wavelength;
+        computations.glossy = ray.glossy;
+        computations.vertex_colour = self.object.vertex_colour(self);
+
+        computations
     }
 }
 
@@ -127,7 +150,7 @@ mod tests {
     use super::*;
     use crate::{
         math::{float::*, Point, Transformation, Vector},
-        Material, Object,
+        Material, Object, Operation,
     };
 
     #[test]
@@ -269,6 +292,37 @@ mod tests {
         test(5, 1.5, 1.0);
     }
 
+    #[test]
+    fn finding_n1_and_n2_through_a_hollow_csg_sphere() {
+        let o = Object::new_csg(
+            Operation::Difference,
+            Object::sphere_builder().material(Material::glass()).build(),
+            Object::sphere_builder()
+                .transformation(Transformation::new().scale(0.5, 0.5, 0.5))
+                .material(Material::glass())
+                .build(),
+        );
+
+        let r = Ray::new(Point::new(0.0, 0.0, -4.0), Vector::z_axis());
+
+        let l = o.intersect(&r).unwrap();
+
+        assert_eq!(l.len(), 4);
+
+        let test = |idx: usize, n1: f64, n2: f64| {
+            let c = l[idx].prepare_computations(&r, &l);
+
+            assert_approx_eq!(c.n1, n1);
+            assert_approx_eq!(c.n2, n2);
+        };
+
+        // Into the shell, out into the hollow, back into the shell and out.
+        test(0, 1.0, 1.5);
+        test(1, 1.5, 1.0);
+        test(2, 1.0, 1.5);
+        test(3, 1.5, 1.0);
+    }
+
     #[test]
     fn the_under_point_is_offset_below_the_surface() {
         let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
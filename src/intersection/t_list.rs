@@ -14,6 +14,9 @@ impl TList {
         Self(Vec::new())
     }
 
+    /// Convert the t values into a `List` of `Intersection`s with the given
+    /// object. Shapes only ever deal with `TList`s, `Object` takes care of
+    /// converting them once the intersected object is known.
     #[must_use]
     pub fn into_list(self, object: &Object) -> List {
         List::from(
@@ -1,26 +1,69 @@
 //! Split code into a library and binary for organisational purposes.
+//!
+//! Without the default `std` feature only the `math` module is built, under
+//! `no_std` with `libm` providing the floating point functions from `std`.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod animation;
+#[cfg(feature = "std")]
 mod camera;
+#[cfg(feature = "std")]
 mod canvas;
+#[cfg(feature = "std")]
 mod colour;
+#[cfg(feature = "std")]
 mod intersection;
+#[cfg(feature = "std")]
 mod light;
+#[cfg(feature = "std")]
 mod material;
 pub mod math;
+#[cfg(feature = "std")]
 mod object;
+#[cfg(feature = "std")]
 mod output;
+#[cfg(feature = "std")]
 mod pattern;
+#[cfg(feature = "std")]
 mod scene;
+#[cfg(feature = "std")]
+mod summary;
+#[cfg(feature = "std")]
 mod util;
+#[cfg(feature = "std")]
 mod world;
 
-pub use camera::Camera;
-pub use canvas::Canvas;
-pub use colour::Colour;
+#[cfg(feature = "std")]
+pub use animation::{Animation, Frame};
+#[cfg(feature = "std")]
+pub use camera::{AntiAliasing, Aovs, Camera, RenderMode, RenderStats};
+#[cfg(feature = "std")]
+pub use canvas::{Canvas, CanvasStats, Histogram};
+#[cfg(feature = "std")]
+pub use colour::{ClampMode, Colour};
+#[cfg(feature = "std")]
+pub use intersection::{Intersection, List, TList, TValues};
+#[cfg(feature = "std")]
 pub use light::Light;
+#[cfg(feature = "std")]
 pub use material::Material;
-pub use object::{Object, Operation};
+#[cfg(feature = "std")]
+pub use object::{
+    Bounded, BoundingBox, CustomShape, Handedness, Intersectable, Object,
+    Operation, Shading, UpAxis,
+};
+#[cfg(feature = "std")]
 pub use output::Output;
-pub use pattern::Pattern;
-pub use scene::Scene;
-pub use world::World;
+#[cfg(feature = "std")]
+pub use pattern::{CubeMap, Pattern};
+#[cfg(feature = "std")]
+pub use scene::{RenderSettings, Scene, Warning};
+#[cfg(feature = "std")]
+pub use summary::Summary;
+#[cfg(feature = "std")]
+pub use world::{AllObjects, Intersector, World};
@@ -1,9 +1,14 @@
 use rand::prelude::*;
 
-use super::Lightable;
+use super::{
+    lightable::{sample_fraction, scale_samples},
+    Lightable,
+};
 use crate::{
-    math::{float::impl_approx_eq, Point, Vector},
-    Colour, World,
+    math::{
+        float::impl_approx_eq, Point, Transformable, Transformation, Vector,
+    },
+    Colour,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -13,7 +18,6 @@ pub struct Area {
     u_steps: u32,
     v: Vector,
     v_steps: u32,
-    samples: u32,
     intensity: Colour,
 }
 
@@ -33,15 +37,7 @@ impl Area {
         let u = u / u_steps_float;
         let v = v / v_steps_float;
 
-        Self {
-            corner,
-            u,
-            u_steps,
-            v,
-            v_steps,
-            samples: u_steps * v_steps,
-            intensity,
-        }
+        Self { corner, u, u_steps, v, v_steps, intensity }
     }
 
     #[must_use]
@@ -66,27 +62,65 @@ impl Lightable for Area {
         positions
     }
 
+    /// Take fewer samples the smaller the light looks from `point`, keeping
+    /// the samples spread over a coarser grid across the whole light.
+    fn positions_at<R: Rng>(&self, point: &Point, rng: &mut R) -> Vec<Point> {
+        let u_size = self.u * f64::from(self.u_steps);
+        let v_size = self.v * f64::from(self.v_steps);
+
+        let half_diagonal = (u_size + v_size) / 2.0;
+
+        let fraction = sample_fraction(
+            half_diagonal.magnitude(),
+            (self.corner + half_diagonal - *point).magnitude(),
+        )
+        .sqrt();
+
+        let u_steps = scale_samples(self.u_steps, fraction);
+        let v_steps = scale_samples(self.v_steps, fraction);
+
+        if u_steps == self.u_steps && v_steps == self.v_steps {
+            return self.positions(rng);
+        }
+
+        Self::new(self.corner, u_size, u_steps, v_size, v_steps, self.intensity)
+            .positions(rng)
+    }
+
     #[must_use]
     fn intensity(&self) -> Colour {
         self.intensity
     }
 
-    #[must_use]
-    fn intensity_at<R: Rng>(
-        &self,
-        point: &Point,
-        world: &World,
-        rng: &mut R,
-    ) -> f64 {
-        let mut intensity = 0.0;
-
-        for position in self.positions(rng) {
-            if !world.is_shadowed(&position, point) {
-                intensity += 1.0;
-            }
-        }
+    fn total_power(&self) -> f64 {
+        let intensity =
+            (self.intensity.red + self.intensity.green + self.intensity.blue)
+                / 3.0;
+
+        let area = (self.u * f64::from(self.u_steps))
+            .cross(&(self.v * f64::from(self.v_steps)))
+            .magnitude();
+
+        intensity * area
+    }
 
-        intensity / f64::from(self.samples)
+    fn centre(&self) -> Option<Point> {
+        Some(
+            self.corner
+                + self.u * f64::from(self.u_steps) / 2.0
+                + self.v * f64::from(self.v_steps) / 2.0,
+        )
+    }
+}
+
+impl Transformable for Area {
+    fn apply(&self, transformation: &Transformation) -> Self {
+        Self {
+            corner: self.corner.apply(transformation),
+            u: self.u.apply(transformation),
+            v: self.v.apply(transformation),
+            ..*self
+        }
     }
 }
 
@@ -115,7 +149,10 @@ mod tests {
         assert_eq!(a.u_steps, 4);
         assert_approx_eq!(a.v, Vector::new(0.0, 0.0, 0.5));
         assert_eq!(a.v_steps, 2);
-        assert_eq!(a.samples, 8);
+        assert_eq!(
+            a.positions(&mut Xoshiro256PlusPlus::seed_from_u64(0)).len(),
+            8
+        );
         assert_approx_eq!(a.intensity, Colour::white());
         assert_approx_eq!(a.intensity(), Colour::white());
     }
@@ -160,6 +197,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_number_of_samples_depends_on_the_size_of_the_light() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let samples = |corner, size, r: &mut Xoshiro256PlusPlus| {
+            Area::new(
+                corner,
+                Vector::x_axis() * size,
+                8,
+                Vector::z_axis() * size,
+                8,
+                Colour::white(),
+            )
+            .positions_at(&Point::origin(), r)
+        };
+
+        let large = samples(Point::new(-2.0, 2.0, -2.0), 4.0, &mut r);
+        let small = samples(Point::new(-0.05, 50.0, -0.05), 0.1, &mut r);
+
+        assert_eq!(large.len(), 64);
+        assert_eq!(small.len(), 1);
+
+        // The fewer samples still cover the whole light.
+        let coarse = samples(Point::new(-1.0, 10.0, -1.0), 2.0, &mut r);
+
+        assert!(coarse.len() > 1 && coarse.len() < 64);
+        assert!(coarse.iter().all(
+            |p| (-1.0..=1.0).contains(&p.x) && (-1.0..=1.0).contains(&p.z)
+        ));
+        assert!(coarse.iter().any(|p| p.x > 0.0));
+        assert!(coarse.iter().any(|p| p.x < 0.0));
+    }
+
+    #[test]
+    fn the_total_power_of_an_area_light() {
+        let a = Area::new(
+            Point::origin(),
+            Vector::new(2.0, 0.0, 0.0),
+            4,
+            Vector::new(0.0, 0.0, 3.0),
+            2,
+            Colour::new(0.5, 1.0, 1.5),
+        );
+
+        assert_approx_eq!(a.total_power(), 6.0);
+    }
+
     #[test]
     fn area_light_intensity() {
         let w = test_world();
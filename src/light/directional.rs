@@ -0,0 +1,117 @@
+use rand::prelude::*;
+
+use super::Lightable;
+use crate::{
+    math::{
+        float::impl_approx_eq, Point, Transformable, Transformation, Vector,
+    },
+    Colour,
+};
+
+/// How far away a `Directional` light is placed from the origin. This needs to
+/// be far enough that rays to the light are effectively parallel over a scene.
+const DISTANCE: f64 = 1_000_000.0;
+
+/// A `Directional` light is infinitely far away with all of its light
+/// travelling in the same direction, like sunlight.
+#[derive(Clone, Copy, Debug)]
+pub struct Directional {
+    direction: Vector,
+    intensity: Colour,
+}
+
+impl Directional {
+    #[must_use]
+    pub fn new(direction: Vector, intensity: Colour) -> Self {
+        Self { direction: direction.normalise(), intensity }
+    }
+
+    /// The direction light travels in.
+    #[must_use]
+    pub const fn direction(&self) -> Vector {
+        self.direction
+    }
+
+    /// The distant position used to stand in for a light at infinity.
+    #[must_use]
+    pub fn position(&self) -> Point {
+        Point::origin() - self.direction * DISTANCE
+    }
+}
+
+impl Lightable for Directional {
+    fn positions<R: Rng>(&self, _rng: &mut R) -> Vec<Point> {
+        vec![self.position()]
+    }
+
+    fn intensity(&self) -> Colour {
+        self.intensity
+    }
+
+    fn centre(&self) -> Option<Point> {
+        None
+    }
+}
+
+/// Only the direction of the light is affected by a transformation.
+impl Transformable for Directional {
+    fn apply(&self, transformation: &Transformation) -> Self {
+        Self::new(self.direction.apply(transformation), self.intensity)
+    }
+}
+
+impl_approx_eq!(Directional { direction, intensity });
+
+#[cfg(test)]
+mod tests {
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{math::float::*, world::test_world};
+
+    #[test]
+    fn creating_a_directional_light() {
+        let d = Directional::new(Vector::new(0.0, -2.0, 0.0), Colour::red());
+
+        assert_approx_eq!(d.direction(), -Vector::y_axis());
+        assert_approx_eq!(d.intensity(), Colour::red());
+        assert_approx_eq!(d.position(), Point::new(0.0, DISTANCE, 0.0));
+    }
+
+    #[test]
+    fn a_directional_light_casts_parallel_shadows() {
+        let w = test_world();
+
+        let d = Directional::new(Vector::z_axis(), Colour::white());
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        assert_approx_eq!(
+            d.intensity_at(&Point::new(0.0, 0.0, 5.0), &w, &mut r),
+            0.0
+        );
+        assert_approx_eq!(
+            d.intensity_at(&Point::new(0.0, 0.9, 5.0), &w, &mut r),
+            0.0
+        );
+        assert_approx_eq!(
+            d.intensity_at(&Point::new(0.0, 1.1, 5.0), &w, &mut r),
+            1.0
+        );
+        assert_approx_eq!(
+            d.intensity_at(&Point::new(0.0, 0.0, -5.0), &w, &mut r),
+            1.0
+        );
+    }
+
+    #[test]
+    fn comparing_directional_lights() {
+        let d1 = Directional::new(Vector::x_axis(), Colour::white());
+        let d2 = Directional::new(Vector::new(2.0, 0.0, 0.0), Colour::white());
+        let d3 = Directional::new(Vector::y_axis(), Colour::white());
+
+        assert_approx_eq!(d1, d2);
+
+        assert_approx_ne!(d1, d3);
+    }
+}
@@ -9,14 +9,137 @@ pub trait Lightable {
     #[must_use]
     fn positions<R: Rng>(&self, rng: &mut R) -> Vec<Point>;
 
+    /// The positions to sample when lighting `point`. Lights with a size can
+    /// take fewer samples when they look small from the point as the shadows
+    /// they cast there are sharp, by default this is the same as `positions`.
+    #[must_use]
+    fn positions_at<R: Rng>(&self, _point: &Point, rng: &mut R) -> Vec<Point> {
+        self.positions(rng)
+    }
+
     #[must_use]
     fn intensity(&self) -> Colour;
 
+    /// The centre of the light, `None` for lights infinitely far away.
+    #[must_use]
+    fn centre(&self) -> Option<Point>;
+
+    /// The total power given out by the light, used to pick an exposure for a
+    /// scene. This is the average of the colour channels of the intensity,
+    /// lights with an area scale this by it.
+    #[must_use]
+    fn total_power(&self) -> f64 {
+        let intensity = self.intensity();
+
+        (intensity.red + intensity.green + intensity.blue) / 3.0
+    }
+
+    /// A rough estimate of how much the light contributes at `point`, used to
+    /// choose which lights to sample. Only the average intensity and the
+    /// distance to the light are considered.
+    #[must_use]
+    fn importance(&self, point: &Point) -> f64 {
+        let intensity = self.intensity();
+        let intensity =
+            (intensity.red + intensity.green + intensity.blue) / 3.0;
+
+        self.centre().map_or(intensity, |centre| {
+            let distance = (centre - *point).magnitude();
+
+            // Stop lights sitting right on the point swamping all others.
+            intensity / distance.max(1.0).powi(2)
+        })
+    }
+
+    /// The fraction of the light visible from `point`, sampling new positions
+    /// on the light, see `positions_at`.
     #[must_use]
     fn intensity_at<R: Rng>(
         &self,
         point: &Point,
         world: &World,
         rng: &mut R,
-    ) -> f64;
+    ) -> f64 {
+        self.intensity_at_positions(
+            &self.positions_at(point, rng),
+            point,
+            world,
+        )
+    }
+
+    /// The fraction of the given light positions visible from `point`, this
+    /// lets positions already sampled for shading be reused.
+    #[must_use]
+    fn intensity_at_positions(
+        &self,
+        positions: &[Point],
+        point: &Point,
+        world: &World,
+    ) -> f64 {
+        if positions.is_empty() {
+            return 0.0;
+        }
+
+        let visible = positions
+            .iter()
+            .filter(|position| !world.is_shadowed(position, point))
+            .count();
+
+        #[allow(clippy::cast_precision_loss)]
+        let intensity = visible as f64 / positions.len() as f64;
+
+        intensity
+    }
+}
+
+/// The angle in radians a light must cover, seen from a point, for it to take
+/// its full number of samples.
+const FULL_SAMPLE_ANGLE: f64 = 0.25;
+
+/// The fraction of its samples a light of `radius` should take when lit from
+/// `distance` away, between 0.0 and 1.0. This scales with the angle the light
+/// covers so lights that look large, and so cast wide penumbras, take the most
+/// samples.
+#[must_use]
+pub(super) fn sample_fraction(radius: f64, distance: f64) -> f64 {
+    if distance <= radius {
+        return 1.0;
+    }
+
+    ((radius / distance).asin() / FULL_SAMPLE_ANGLE).min(1.0)
+}
+
+/// Scale a number of `samples` by `fraction`, always keeping at least one.
+#[must_use]
+pub(super) fn scale_samples(samples: u32, fraction: f64) -> u32 {
+    if samples == 0 {
+        return 0;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let scaled = (f64::from(samples) * fraction).ceil() as u32;
+
+    scaled.clamp(1, samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::float::*;
+
+    #[test]
+    fn the_fraction_of_samples_for_a_light() {
+        assert_approx_eq!(sample_fraction(1.0, 0.5), 1.0);
+        assert_approx_eq!(sample_fraction(1.0, 2.0), 1.0);
+        assert_approx_eq!(
+            sample_fraction(0.1, 10.0),
+            0.01_f64.asin() / FULL_SAMPLE_ANGLE
+        );
+        assert_approx_eq!(sample_fraction(0.0, 10.0), 0.0);
+
+        assert_eq!(scale_samples(16, 1.0), 16);
+        assert_eq!(scale_samples(16, 0.3), 5);
+        assert_eq!(scale_samples(16, 0.0), 1);
+        assert_eq!(scale_samples(0, 0.5), 0);
+    }
 }
@@ -1,16 +1,19 @@
 mod area;
+mod directional;
 mod lightable;
 mod point;
+mod sphere;
 
 use enum_dispatch::enum_dispatch;
 use float_cmp::{ApproxEq, F64Margin};
 use rand::Rng;
-use serde::{Deserialize, Deserializer};
+use serde::{de::Error, Deserialize, Deserializer};
 
-use self::area::Area;
+pub use self::directional::Directional;
 pub use self::lightable::Lightable;
+use self::{area::Area, sphere::Sphere};
 use crate::{
-    math::{Point, Vector},
+    math::{Point, Transformable, Transformation, Vector},
     Colour, World,
 };
 
@@ -19,7 +22,9 @@ use crate::{
 #[enum_dispatch]
 pub enum Light {
     Area(Area),
+    Directional(Directional),
     Point(point::Point),
+    Sphere(Sphere),
 }
 
 impl Light {
@@ -35,10 +40,60 @@ impl Light {
         Self::Area(Area::new(corner, u, u_steps, v, v_steps, intensity))
     }
 
+    /// Create a light infinitely far away shining along `direction`.
+    #[must_use]
+    pub fn new_directional(direction: Vector, intensity: Colour) -> Self {
+        Self::Directional(Directional::new(direction, intensity))
+    }
+
     #[must_use]
     pub fn new_point(position: Point, intensity: Colour) -> Self {
         Self::Point(point::Point::new(position, intensity))
     }
+
+    /// Create a spherical area light which takes `samples` random positions
+    /// on the surface of the sphere each time it is sampled.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is not positive or `samples` is 0.
+    #[must_use]
+    pub fn new_sphere_area(
+        centre: Point,
+        radius: f64,
+        samples: u32,
+        intensity: Colour,
+    ) -> Self {
+        assert!(radius > 0.0, "A sphere light needs a positive radius.");
+        assert!(samples > 0, "A sphere light needs at least one sample.");
+
+        Self::Sphere(Sphere::new(centre, radius, samples, intensity))
+    }
+
+    /// A short lower case name for the kind of light, used when summarising
+    /// scenes.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Area(_) => "area",
+            Self::Directional(_) => "directional",
+            Self::Point(_) => "point",
+            Self::Sphere(_) => "sphere",
+        }
+    }
+}
+
+impl Transformable for Light {
+    fn apply(&self, transformation: &Transformation) -> Self {
+        match self {
+            Self::Area(area) => Self::Area(area.apply(transformation)),
+            Self::Directional(directional) => {
+                Self::Directional(directional.apply(transformation))
+            }
+            Self::Point(point) => Self::Point(point.apply(transformation)),
+            Self::Sphere(sphere) => Self::Sphere(sphere.apply(transformation)),
+        }
+    }
 }
 
 impl ApproxEq for Light {
@@ -49,7 +104,13 @@ impl ApproxEq for Light {
 
         match (self, other) {
             (Self::Area(lhs), Self::Area(rhs)) => lhs.approx_eq(rhs, margin),
+            (Self::Directional(lhs), Self::Directional(rhs)) => {
+                lhs.approx_eq(rhs, margin)
+            }
             (Self::Point(lhs), Self::Point(rhs)) => lhs.approx_eq(rhs, margin),
+            (Self::Sphere(lhs), Self::Sphere(rhs)) => {
+                lhs.approx_eq(rhs, margin)
+            }
             (_, _) => false,
         }
     }
@@ -62,6 +123,7 @@ impl<'de> Deserialize<'de> for Light {
     {
         #[derive(Deserialize)]
         #[serde(untagged)]
+        #[allow(clippy::enum_variant_names)]
         pub enum Light {
             PointLight {
                 at: Point,
@@ -79,6 +141,17 @@ impl<'de> Deserialize<'de> for Light {
                 v_steps: u32,
                 intensity: Colour,
             },
+            DirectionalLight {
+                direction: Vector,
+                intensity: Colour,
+            },
+            SphereLight {
+                #[serde(alias = "center")]
+                centre: Point,
+                radius: f64,
+                samples: u32,
+                intensity: Colour,
+            },
         }
 
         let light = Light::deserialize(deserializer)?;
@@ -90,6 +163,24 @@ impl<'de> Deserialize<'de> for Light {
             Light::AreaLight { corner, u, u_steps, v, v_steps, intensity } => {
                 Ok(Self::new_area(corner, u, u_steps, v, v_steps, intensity))
             }
+            Light::DirectionalLight { direction, intensity } => {
+                Ok(Self::new_directional(direction, intensity))
+            }
+            Light::SphereLight { centre, radius, samples, intensity } => {
+                if radius <= 0.0 {
+                    return Err(Error::custom(
+                        "A sphere light needs a positive radius.",
+                    ));
+                }
+
+                if samples == 0 {
+                    return Err(Error::custom(
+                        "A sphere light needs at least one sample.",
+                    ));
+                }
+
+                Ok(Self::new_sphere_area(centre, radius, samples, intensity))
+            }
         }
     }
 }
@@ -132,6 +223,49 @@ mod tests {
         assert_approx_ne!(l4, l1);
     }
 
+    #[test]
+    fn transforming_lights() {
+        let t =
+            Transformation::new().scale(2.0, 2.0, 2.0).translate(1.0, 0.0, 0.0);
+
+        assert_approx_eq!(
+            Light::new_point(Point::new(1.0, 2.0, 3.0), Colour::white())
+                .apply(&t),
+            Light::new_point(Point::new(3.0, 4.0, 6.0), Colour::white())
+        );
+
+        assert_approx_eq!(
+            Light::new_area(
+                Point::origin(),
+                Vector::x_axis(),
+                2,
+                Vector::y_axis(),
+                2,
+                Colour::white()
+            )
+            .apply(&t),
+            Light::new_area(
+                Point::new(1.0, 0.0, 0.0),
+                Vector::new(2.0, 0.0, 0.0),
+                2,
+                Vector::new(0.0, 2.0, 0.0),
+                2,
+                Colour::white()
+            )
+        );
+
+        assert_approx_eq!(
+            Light::new_sphere_area(Point::origin(), 0.5, 8, Colour::white())
+                .apply(&t),
+            Light::new_sphere_area(
+                Point::new(1.0, 0.0, 0.0),
+                1.0,
+                8,
+                Colour::white()
+            )
+        );
+    }
+
     #[test]
     fn deserialize_point_light() {
         let l: Light = from_str(
@@ -175,4 +309,89 @@ intensity: [0.5, 0.5, 0.8]",
             )
         );
     }
+
+    #[test]
+    fn deserialize_directional_light() {
+        let l: Light = from_str(
+            "\
+direction: [0, -1, 1]
+intensity: [1, 1, 0.9]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            l,
+            Light::new_directional(
+                Vector::new(0.0, -1.0, 1.0),
+                Colour::new(1.0, 1.0, 0.9)
+            )
+        );
+
+        assert_eq!(l.kind(), "directional");
+    }
+
+    #[test]
+    fn deserialize_sphere_light() {
+        let l: Light = from_str(
+            "\
+centre: [1, 2, 3]
+radius: 0.5
+samples: 16
+intensity: [1, 1, 0.9]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            l,
+            Light::new_sphere_area(
+                Point::new(1.0, 2.0, 3.0),
+                0.5,
+                16,
+                Colour::new(1.0, 1.0, 0.9)
+            )
+        );
+
+        let l: Light = from_str(
+            "\
+center: [1, 2, 3]
+radius: 0.5
+samples: 16
+intensity: [1, 1, 0.9]",
+        )
+        .unwrap();
+
+        assert_eq!(l.kind(), "sphere");
+
+        assert!(from_str::<Light>(
+            "\
+centre: [1, 2, 3]
+radius: 0
+samples: 16
+intensity: [1, 1, 0.9]"
+        )
+        .is_err());
+
+        assert!(from_str::<Light>(
+            "\
+centre: [1, 2, 3]
+radius: 0.5
+samples: 0
+intensity: [1, 1, 0.9]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "A sphere light needs a positive radius.")]
+    fn creating_a_sphere_light_with_no_radius() {
+        let _ =
+            Light::new_sphere_area(Point::origin(), 0.0, 8, Colour::white());
+    }
+
+    #[test]
+    #[should_panic(expected = "A sphere light needs at least one sample.")]
+    fn creating_a_sphere_light_with_no_samples() {
+        let _ =
+            Light::new_sphere_area(Point::origin(), 0.5, 0, Colour::white());
+    }
 }
@@ -3,8 +3,8 @@ use rand::prelude::*;
 
 use super::Lightable;
 use crate::{
-    math::{self, float::impl_approx_eq},
-    Colour, World,
+    math::{self, float::impl_approx_eq, Transformable, Transformation},
+    Colour,
 };
 
 /// A `Point` is a light source that has no size and radiates light in all
@@ -24,17 +24,14 @@ impl Lightable for Point {
         self.intensity
     }
 
-    fn intensity_at<R: Rng>(
-        &self,
-        point: &math::Point,
-        world: &World,
-        _rng: &mut R,
-    ) -> f64 {
-        if world.is_shadowed(&self.position, point) {
-            0.0
-        } else {
-            1.0
-        }
+    fn centre(&self) -> Option<math::Point> {
+        Some(self.position)
+    }
+}
+
+impl Transformable for Point {
+    fn apply(&self, transformation: &Transformation) -> Self {
+        Self::new(self.position.apply(transformation), self.intensity)
     }
 }
 
@@ -53,6 +50,7 @@ mod tests {
 
         assert_approx_eq!(l.position, math::Point::origin());
         assert_approx_eq!(l.intensity, Colour::green());
+        assert_approx_eq!(l.total_power(), 1.0 / 3.0);
     }
 
     #[test]
@@ -0,0 +1,186 @@
+use std::f64::consts::TAU;
+
+use derive_new::new;
+use rand::prelude::*;
+
+use super::{
+    lightable::{sample_fraction, scale_samples},
+    Lightable,
+};
+use crate::{
+    math::{
+        float::impl_approx_eq, Point, Transformable, Transformation, Vector,
+    },
+    Colour,
+};
+
+/// A `Sphere` is a spherical area light, positions are sampled uniformly over
+/// its surface giving soft shadows that behave like those of a light bulb.
+#[derive(Clone, Copy, Debug, new)]
+pub struct Sphere {
+    centre: Point,
+    radius: f64,
+    samples: u32,
+    intensity: Colour,
+}
+
+impl Sphere {
+    #[must_use]
+    fn point_on_light<R: Rng>(&self, rng: &mut R) -> Point {
+        let z: f64 = rng.gen_range(-1.0..=1.0);
+        let phi = rng.gen_range(0.0..TAU);
+
+        let r = (1.0 - z * z).sqrt();
+
+        self.centre + Vector::new(r * phi.cos(), r * phi.sin(), z) * self.radius
+    }
+}
+
+impl Lightable for Sphere {
+    fn positions<R: Rng>(&self, rng: &mut R) -> Vec<Point> {
+        (0..self.samples).map(|_| self.point_on_light(rng)).collect()
+    }
+
+    /// Take fewer samples the smaller the light looks from `point`.
+    fn positions_at<R: Rng>(&self, point: &Point, rng: &mut R) -> Vec<Point> {
+        let samples = scale_samples(
+            self.samples,
+            sample_fraction(self.radius, (self.centre - *point).magnitude()),
+        );
+
+        (0..samples).map(|_| self.point_on_light(rng)).collect()
+    }
+
+    fn intensity(&self) -> Colour {
+        self.intensity
+    }
+
+    fn centre(&self) -> Option<Point> {
+        Some(self.centre)
+    }
+}
+
+/// The radius is scaled by the length of a transformed unit vector along x so
+/// only uniform scaling keeps the light spherical.
+impl Transformable for Sphere {
+    fn apply(&self, transformation: &Transformation) -> Self {
+        Self {
+            centre: self.centre.apply(transformation),
+            radius: self.radius
+                * Vector::x_axis().apply(transformation).magnitude(),
+            ..*self
+        }
+    }
+}
+
+impl_approx_eq!(Sphere { centre, radius, eq samples, intensity });
+
+#[cfg(test)]
+mod tests {
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{math::float::*, world::test_world};
+
+    #[test]
+    fn creating_a_sphere_light() {
+        let s = Sphere::new(Point::new(1.0, 2.0, 3.0), 0.5, 16, Colour::red());
+
+        assert_approx_eq!(s.centre, Point::new(1.0, 2.0, 3.0));
+        assert_approx_eq!(s.radius, 0.5);
+        assert_eq!(s.samples, 16);
+        assert_approx_eq!(s.intensity, Colour::red());
+        assert_approx_eq!(s.intensity(), Colour::red());
+    }
+
+    #[test]
+    fn sampled_positions_lie_on_the_sphere() {
+        let c = Point::new(1.0, -2.0, 0.5);
+        let s = Sphere::new(c, 2.5, 100, Colour::white());
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let p = s.positions(&mut r);
+
+        assert_eq!(p.len(), 100);
+
+        for position in &p {
+            assert_approx_eq!((*position - c).magnitude(), 2.5);
+        }
+
+        // Samples should be spread over the whole sphere.
+        assert!(p.iter().any(|p| p.x > c.x) && p.iter().any(|p| p.x < c.x));
+        assert!(p.iter().any(|p| p.y > c.y) && p.iter().any(|p| p.y < c.y));
+        assert!(p.iter().any(|p| p.z > c.z) && p.iter().any(|p| p.z < c.z));
+    }
+
+    #[test]
+    fn the_number_of_samples_depends_on_the_size_of_the_light() {
+        let p = Point::origin();
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let samples = |centre, radius, r: &mut Xoshiro256PlusPlus| {
+            Sphere::new(centre, radius, 64, Colour::white())
+                .positions_at(&p, r)
+                .len()
+        };
+
+        let large = samples(Point::new(0.0, 2.0, 0.0), 1.0, &mut r);
+        let small = samples(Point::new(0.0, 50.0, 0.0), 0.1, &mut r);
+
+        assert_eq!(large, 64);
+        assert_eq!(small, 1);
+        assert!(samples(Point::new(0.0, 10.0, 0.0), 1.0, &mut r) > small);
+        assert!(samples(Point::new(0.0, 10.0, 0.0), 1.0, &mut r) < large);
+
+        // Points inside the light always take every sample.
+        assert_eq!(samples(Point::new(0.0, 0.5, 0.0), 1.0, &mut r), 64);
+    }
+
+    #[test]
+    fn penumbra_width_scales_with_the_radius() {
+        let w = test_world();
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let mut penumbra = |radius| {
+            let s = Sphere::new(
+                Point::new(0.0, 0.0, -5.0),
+                radius,
+                64,
+                Colour::white(),
+            );
+
+            (0..=100)
+                .map(|x| {
+                    s.intensity_at(
+                        &Point::new(f64::from(x) * 0.03, 0.0, 3.0),
+                        &w,
+                        &mut r,
+                    )
+                })
+                .filter(|intensity| *intensity > 0.0 && *intensity < 1.0)
+                .count()
+        };
+
+        let p0 = penumbra(0.0);
+        let p1 = penumbra(0.5);
+        let p2 = penumbra(1.5);
+
+        assert_eq!(p0, 0);
+        assert!(p1 > 0);
+        assert!(p2 > p1);
+    }
+
+    #[test]
+    fn comparing_sphere_lights() {
+        let s1 = Sphere::new(Point::origin(), 1.0, 8, Colour::white());
+        let s2 = Sphere::new(Point::origin(), 1.0, 8, Colour::white());
+        let s3 = Sphere::new(Point::origin(), 1.000_1, 8, Colour::white());
+
+        assert_approx_eq!(s1, s2);
+
+        assert_approx_ne!(s1, s3);
+    }
+}
@@ -1,19 +1,14 @@
 mod arguments;
 
-use std::{
-    fs::write,
-    io::{stdout, Write},
-    path::Path,
-};
+use std::io::{stdout, Write};
 
 use anyhow::Result;
 use clap::Parser;
-use image::{ImageBuffer, Rgb};
 use rand::prelude::*;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use raytracer::{Output, Scene};
+use raytracer::{AntiAliasing, Output, RenderSettings, Scene};
 
-use crate::arguments::Arguments;
+use crate::arguments::{Arguments, Exposure};
 
 fn main() -> Result<()> {
     let arguments = Arguments::parse();
@@ -37,39 +32,77 @@ fn main() -> Result<()> {
     };
     writeln!(output, "{scene_text}")?;
 
-    let scene = if arguments.sphere_scene {
+    // Warnings are held back until the progress line has been replaced.
+    let mut warnings = Vec::new();
+
+    let mut scene = if arguments.sphere_scene {
         Scene::generate_random_spheres(arguments.scale, &mut rng)
     } else {
-        Scene::from_file(arguments.scene, arguments.scale, &mut rng)?
+        Scene::from_file(
+            arguments.scene,
+            arguments.scale,
+            &mut Output::new(&mut warnings),
+            &mut rng,
+        )?
     };
 
     output.clear_last_line()?;
 
     writeln!(output, "{scene_text}done")?;
 
-    let canvas = scene.render(
-        arguments.depth,
-        arguments.single_threaded,
-        &mut output,
-        &mut rng,
+    output.write_all(&warnings)?;
+
+    let coplanar = if arguments.check_coplanar {
+        scene.find_coplanar_overlaps()
+    } else {
+        Vec::new()
+    };
+
+    for warning in scene.validate().into_iter().chain(coplanar) {
+        writeln!(output, "Warning: {warning}")?;
+    }
+
+    write!(output, "{}", scene.summary())?;
+    writeln!(
+        output,
+        "Estimated memory: {} KiB",
+        scene.estimated_memory() / 1024
     )?;
 
-    writeln!(output, "Writing to file {}", arguments.out)?;
+    scene.set_max_intersections(arguments.max_intersections);
+    scene.set_spectral(arguments.spectral);
+    scene.set_light_samples(arguments.light_samples);
+    scene.set_russian_roulette(arguments.russian_roulette);
 
-    let filename = Path::new(&arguments.out);
-    if filename.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("ppm"))
-    {
-        write(filename, canvas.to_ppm())?;
-    } else {
-        #[allow(clippy::cast_possible_truncation)]
-        let image = ImageBuffer::from_fn(
-            scene.horizontal_size(),
-            scene.vertical_size(),
-            |x, y| Rgb(canvas.get_pixel(x as usize, y as usize).to_u8()),
-        );
-
-        image.save(filename)?;
+    if let Some(backplate) = &arguments.backplate {
+        scene.load_backplate(backplate)?;
     }
 
+    scene.override_settings(
+        &RenderSettings::builder()
+            .depth(arguments.depth)
+            .samples(arguments.samples)
+            .tonemap(arguments.tonemap)
+            .exposure(arguments.exposure.map(|exposure| match exposure {
+                Exposure::Auto => scene.auto_exposure(),
+                Exposure::Stops(stops) => stops,
+            }))
+            .mode(arguments.mode)
+            .sampling_seed(arguments.sampling_seed)
+            .build(),
+    );
+
+    scene.render_to_file(
+        &arguments.out,
+        arguments.single_threaded,
+        if arguments.edge_aa {
+            AntiAliasing::Edges
+        } else {
+            AntiAliasing::None
+        },
+        &mut output,
+        &mut rng,
+    )?;
+
     Ok(())
 }
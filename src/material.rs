@@ -1,10 +1,11 @@
+use float_cmp::{ApproxEq, F64Margin};
 use rand::prelude::*;
 use serde::{de::Error, Deserialize, Deserializer};
 use typed_builder::TypedBuilder;
 
 use crate::{
     light::Lightable,
-    math::{float::impl_approx_eq, Point, Vector},
+    math::{Point, Vector},
     Colour, Light, Object, Pattern,
 };
 
@@ -25,12 +26,54 @@ pub struct Material {
     pub shininess: f64,
     #[builder(default = 0.0)]
     pub reflective: f64,
+    /// How rough a reflective surface is, 0.0 gives a perfect mirror while
+    /// larger values blur reflections by sampling `reflection_samples` rays.
+    #[builder(default = 0.0)]
+    pub roughness: f64,
+    #[builder(default = 16)]
+    pub reflection_samples: u32,
     #[builder(default = 0.0)]
     pub transparency: f64,
     #[builder(default = 1.0)]
     pub refractive_index: f64,
+    /// How strongly the refractive index varies with wavelength, given as the
+    /// second coefficient of Cauchy's equation in square micrometres. The
+    /// `refractive_index` is the index at `REFERENCE_WAVELENGTH`.
+    #[builder(default = 0.0)]
+    pub dispersion: f64,
+    /// Caps the recursion depth for reflections and refractions from this
+    /// material, the smaller of this and the global depth is used.
+    #[builder(default = None)]
+    pub max_depth: Option<u32>,
+    /// Light thin surfaces such as leaves or paper from both sides, lights
+    /// behind the surface as seen from the eye light it as if it faced them.
+    #[builder(default = false)]
+    pub two_sided: bool,
+    /// Masks out parts of a surface, hits where the average of the channels
+    /// of the pattern is below `ALPHA_CUTOFF` are skipped so rays pass
+    /// straight through to whatever is behind.
+    #[builder(default = None)]
+    pub alpha_map: Option<Pattern>,
+    /// Perturbs the normals of triangles with texture coordinates, the red,
+    /// green and blue channels of the pattern give a normal in the tangent
+    /// space of the triangle with each axis mapped from 0.0..1.0 to -1.0..1.0.
+    #[builder(default = None)]
+    pub normal_map: Option<Pattern>,
+    /// Makes the surface invisible to rays and stops it casting shadows while
+    /// still receiving them, the shadows only appear in the matte render mode
+    /// so objects can be composited onto photographs.
+    #[builder(default = false)]
+    pub shadow_catcher: bool,
 }
 
+/// The wavelength in micrometres that the refractive index of a dispersive
+/// material is given at, the sodium D line commonly used to quote indices.
+const REFERENCE_WAVELENGTH: f64 = 0.589_3;
+
+/// The alpha below which a point on a surface with an alpha map is treated as
+/// fully transparent.
+const ALPHA_CUTOFF: f64 = 0.5;
+
 impl Material {
     #[must_use]
     pub fn glass() -> Self {
@@ -42,6 +85,30 @@ impl Material {
             .build()
     }
 
+    /// The refractive index at the given wavelength in micrometres, using
+    /// Cauchy's equation.
+    #[must_use]
+    pub fn refractive_index_at(&self, wavelength: f64) -> f64 {
+        self.refractive_index
+            + self.dispersion
+                * (wavelength.powi(-2) - REFERENCE_WAVELENGTH.powi(-2))
+    }
+
+    /// Check if the given point is solid according to the alpha map, points on
+    /// materials without an alpha map are always solid.
+    #[must_use]
+    pub fn is_opaque(&self, object: &Object, point: &Point) -> bool {
+        self.alpha_map.as_ref().is_none_or(|alpha_map| {
+            let alpha = alpha_map.pattern_at(object, point);
+
+            (alpha.red + alpha.green + alpha.blue) / 3.0 >= ALPHA_CUTOFF
+        })
+    }
+
+    /// Calculate the colour at the given point from the given light. The
+    /// colour of the light tints every term, `intensity` is only the fraction
+    /// of the light visible from the point and so scales the diffuse and
+    /// specular terms to give soft shadows without losing the light's colour.
     #[must_use]
     #[allow(clippy::too_many_arguments)]
     pub fn lighting<R: Rng>(
@@ -54,19 +121,86 @@ impl Material {
         intensity: f64,
         rng: &mut R,
     ) -> Colour {
-        let colour = self.pattern.pattern_at(object, point) * light.intensity();
+        self.lighting_from_positions(
+            object,
+            light,
+            &light.positions_at(point, rng),
+            point,
+            eye,
+            normal,
+            intensity,
+        )
+    }
+
+    /// Calculate the lighting as `lighting` does but using positions already
+    /// sampled from the light, typically those used to find `intensity`.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn lighting_from_positions(
+        &self,
+        object: &Object,
+        light: &Light,
+        light_positions: &[Point],
+        point: &Point,
+        eye: &Vector,
+        normal: &Vector,
+        intensity: f64,
+    ) -> Colour {
+        let (ambient, direct) = self.lighting_terms(
+            object,
+            light,
+            light_positions,
+            point,
+            eye,
+            normal,
+            intensity,
+            None,
+        );
+
+        ambient + direct
+    }
+
+    /// Calculate the lighting as `lighting_from_positions` does but keep the
+    /// ambient term apart from the direct diffuse and specular terms, so that
+    /// effects such as ambient occlusion can darken just the ambient light. A
+    /// `vertex_colour` from the intersection replaces the colour of the
+    /// pattern.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn lighting_terms(
+        &self,
+        object: &Object,
+        light: &Light,
+        light_positions: &[Point],
+        point: &Point,
+        eye: &Vector,
+        normal: &Vector,
+        intensity: f64,
+        vertex_colour: Option<Colour>,
+    ) -> (Colour, Colour) {
+        debug_assert!(
+            normal.is_normalised(Vector::NORMALISED_EPSILON),
+            "Normal {normal:?} is not normalised"
+        );
+        debug_assert!(
+            eye.is_normalised(Vector::NORMALISED_EPSILON),
+            "Eye vector {eye:?} is not normalised"
+        );
+
+        let colour = vertex_colour
+            .unwrap_or_else(|| self.pattern.pattern_at(object, point))
+            * light.intensity();
 
         let ambient = colour * self.ambient;
 
         let mut diffuse = Colour::black();
         let mut specular = Colour::black();
 
-        let light_positions = light.positions(rng);
         #[allow(clippy::cast_precision_loss)]
         let samples = light_positions.len() as f64;
 
         for light_position in light_positions {
-            let light_vector = (light_position - *point).normalise();
+            let light_vector = (*light_position - *point).normalise();
             let light_dot_normal = light_vector.dot(normal);
 
             if light_dot_normal >= 0.0 {
@@ -83,7 +217,7 @@ impl Material {
             };
         }
 
-        ambient + (diffuse + specular) / samples * intensity
+        (ambient, (diffuse + specular) / samples * intensity)
     }
 }
 
@@ -93,16 +227,37 @@ impl Default for Material {
     }
 }
 
-impl_approx_eq!(&Material {
-    ref pattern,
-    ambient,
-    diffuse,
-    specular,
-    shininess,
-    reflective,
-    transparency,
-    refractive_index
-});
+impl ApproxEq for &Material {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+
+        let maps_match =
+            |lhs: &Option<Pattern>, rhs: &Option<Pattern>| match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => lhs.approx_eq(rhs, margin),
+                (None, None) => true,
+                _ => false,
+            };
+
+        self.pattern.approx_eq(&other.pattern, margin)
+            && self.ambient.approx_eq(other.ambient, margin)
+            && self.diffuse.approx_eq(other.diffuse, margin)
+            && self.specular.approx_eq(other.specular, margin)
+            && self.shininess.approx_eq(other.shininess, margin)
+            && self.reflective.approx_eq(other.reflective, margin)
+            && self.roughness.approx_eq(other.roughness, margin)
+            && self.reflection_samples == other.reflection_samples
+            && self.transparency.approx_eq(other.transparency, margin)
+            && self.refractive_index.approx_eq(other.refractive_index, margin)
+            && self.dispersion.approx_eq(other.dispersion, margin)
+            && self.max_depth == other.max_depth
+            && self.two_sided == other.two_sided
+            && maps_match(&self.alpha_map, &other.alpha_map)
+            && maps_match(&self.normal_map, &other.normal_map)
+            && self.shadow_catcher == other.shadow_catcher
+    }
+}
 
 impl<'de> Deserialize<'de> for Material {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -119,8 +274,16 @@ impl<'de> Deserialize<'de> for Material {
             specular: Option<f64>,
             shininess: Option<f64>,
             reflective: Option<f64>,
+            roughness: Option<f64>,
+            reflection_samples: Option<u32>,
             transparency: Option<f64>,
             refractive_index: Option<f64>,
+            dispersion: Option<f64>,
+            max_depth: Option<u32>,
+            two_sided: Option<bool>,
+            alpha_map: Option<Pattern>,
+            normal_map: Option<Pattern>,
+            shadow_catcher: Option<bool>,
         }
 
         let material = Material::deserialize(deserializer)?;
@@ -148,17 +311,31 @@ impl<'de> Deserialize<'de> for Material {
             .specular(material.specular.unwrap_or(default.specular))
             .shininess(material.shininess.unwrap_or(default.shininess))
             .reflective(material.reflective.unwrap_or(default.reflective))
+            .roughness(material.roughness.unwrap_or(default.roughness))
+            .reflection_samples(
+                material
+                    .reflection_samples
+                    .unwrap_or(default.reflection_samples),
+            )
             .transparency(material.transparency.unwrap_or(default.transparency))
             .refractive_index(
                 material.refractive_index.unwrap_or(default.refractive_index),
             )
+            .dispersion(material.dispersion.unwrap_or(default.dispersion))
+            .max_depth(material.max_depth.or(default.max_depth))
+            .two_sided(material.two_sided.unwrap_or(default.two_sided))
+            .alpha_map(material.alpha_map)
+            .normal_map(material.normal_map)
+            .shadow_catcher(
+                material.shadow_catcher.unwrap_or(default.shadow_catcher),
+            )
             .build())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::SQRT_2;
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
 
     use rand_xoshiro::Xoshiro256PlusPlus;
     use serde_yaml::from_str;
@@ -182,8 +359,13 @@ mod tests {
             .specular(1.5)
             .shininess(25.6)
             .reflective(0.6)
+            .roughness(0.2)
+            .reflection_samples(4)
             .transparency(0.5)
             .refractive_index(1.5)
+            .dispersion(0.004)
+            .max_depth(Some(3))
+            .two_sided(true)
             .build();
 
         assert_approx_eq!(
@@ -195,8 +377,13 @@ mod tests {
         assert_approx_eq!(m.specular, 1.5);
         assert_approx_eq!(m.shininess, 25.6);
         assert_approx_eq!(m.reflective, 0.6);
+        assert_approx_eq!(m.roughness, 0.2);
+        assert_eq!(m.reflection_samples, 4);
         assert_approx_eq!(m.transparency, 0.5);
         assert_approx_eq!(m.refractive_index, 1.5);
+        assert_approx_eq!(m.dispersion, 0.004);
+        assert_eq!(m.max_depth, Some(3));
+        assert!(m.two_sided);
 
         assert_approx_eq!(
             Material::default(),
@@ -207,8 +394,16 @@ mod tests {
                 specular: 0.9,
                 shininess: 200.0,
                 reflective: 0.0,
+                roughness: 0.0,
+                reflection_samples: 16,
                 transparency: 0.0,
-                refractive_index: 1.0
+                refractive_index: 1.0,
+                dispersion: 0.0,
+                max_depth: None,
+                two_sided: false,
+                alpha_map: None,
+                normal_map: None,
+                shadow_catcher: false
             }
         );
 
@@ -221,12 +416,90 @@ mod tests {
                 specular: 0.9,
                 shininess: 200.0,
                 reflective: 0.0,
+                roughness: 0.0,
+                reflection_samples: 16,
                 transparency: 1.0,
-                refractive_index: 1.5
+                refractive_index: 1.5,
+                dispersion: 0.0,
+                max_depth: None,
+                two_sided: false,
+                alpha_map: None,
+                normal_map: None,
+                shadow_catcher: false
             }
         );
     }
 
+    #[test]
+    fn the_refractive_index_at_a_wavelength() {
+        let m = Material::glass();
+
+        assert_approx_eq!(m.refractive_index_at(0.45), 1.5);
+        assert_approx_eq!(m.refractive_index_at(0.65), 1.5);
+
+        let m =
+            Material::builder().refractive_index(1.5).dispersion(0.004).build();
+
+        assert_approx_eq!(m.refractive_index_at(REFERENCE_WAVELENGTH), 1.5);
+        assert_approx_eq!(
+            m.refractive_index_at(0.45),
+            1.508_235,
+            epsilon = 0.000_001
+        );
+        assert_approx_eq!(
+            m.refractive_index_at(0.65),
+            1.497_949,
+            epsilon = 0.000_001
+        );
+    }
+
+    #[test]
+    fn checking_if_a_point_is_opaque() {
+        let o = Object::test_builder().build();
+
+        assert!(Material::default().is_opaque(&o, &Point::new(0.5, 0.0, 0.0)));
+
+        let m = Material::builder()
+            .alpha_map(Some(
+                Pattern::stripe_builder(
+                    Colour::white().into(),
+                    Colour::new(0.4, 0.5, 0.5).into(),
+                )
+                .build(),
+            ))
+            .build();
+
+        assert!(m.is_opaque(&o, &Point::new(0.5, 0.0, 0.0)));
+        assert!(!m.is_opaque(&o, &Point::new(1.5, 0.0, 0.0)));
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn separating_the_ambient_lighting_term() {
+        let m = Material::default();
+        let p = Point::origin();
+
+        let e = -Vector::z_axis();
+        let n = -Vector::z_axis();
+
+        let l = Light::new_point(Point::new(0.0, 0.0, -10.0), Colour::white());
+        let o = Object::test_builder().build();
+
+        let positions = l.positions(&mut rng());
+
+        let (ambient, direct) =
+            m.lighting_terms(&o, &l, &positions, &p, &e, &n, 1.0, None);
+
+        assert_approx_eq!(ambient, Colour::new(0.1, 0.1, 0.1));
+        assert_approx_eq!(direct, Colour::new(1.8, 1.8, 1.8));
+
+        let (ambient, direct) =
+            m.lighting_terms(&o, &l, &positions, &p, &e, &n, 0.0, None);
+
+        assert_approx_eq!(ambient, Colour::new(0.1, 0.1, 0.1));
+        assert_approx_eq!(direct, Colour::black());
+    }
+
     #[test]
     #[allow(clippy::many_single_char_names)]
     fn lighting_with_the_surface_in_shadow() {
@@ -345,7 +618,7 @@ mod tests {
         let m = Material::default();
         let p = Point::origin();
 
-        let e = Vector::new(0.0, 0.0, 0.3);
+        let e = Vector::z_axis();
         let n = -Vector::z_axis();
 
         let l = Light::new_point(Point::new(0.0, 0.0, -10.0), Colour::white());
@@ -469,7 +742,7 @@ mod tests {
 
         let test = |p: Point| {
             let e = (e - p).normalise();
-            let n = Vector::new(p.x, p.y, p.z);
+            let n = Vector::new(p.x, p.y, p.z).normalise();
 
             o.material().lighting(&o, &l, &p, &e, &n, 1.0, &mut rng())
         };
@@ -480,12 +753,107 @@ mod tests {
             epsilon = 0.000_01
         );
         assert_approx_eq!(
-            test(Point::new(0.0, SQRT_2, -SQRT_2)),
-            Colour::new(0.780_05, 0.780_05, 0.780_05),
+            test(Point::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)),
+            Colour::new(0.606_92, 0.606_92, 0.606_92),
             epsilon = 0.000_01
         );
     }
 
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn lighting_with_a_coloured_area_light_tints_the_penumbra() {
+        let w = test_world();
+
+        let l = Light::new_area(
+            Point::new(-0.5, -0.5, -5.0),
+            Vector::x_axis(),
+            2,
+            Vector::y_axis(),
+            2,
+            Colour::red(),
+        );
+
+        let o = Object::plane_builder()
+            .material(
+                Material::builder()
+                    .pattern(Colour::white().into())
+                    .specular(0.0)
+                    .build(),
+            )
+            .build();
+
+        let mut r = rng();
+
+        let mut test = |p: Point| {
+            let e = -Vector::z_axis();
+            let i = l.intensity_at(&p, &w, &mut r);
+
+            o.material().lighting(&o, &l, &p, &e, &e, i, &mut r)
+        };
+
+        let c1 = test(Point::new(0.0, 0.0, 2.0));
+        let c2 = test(Point::new(1.0, -1.0, 2.0));
+        let c3 = test(Point::new(1.25, 1.25, 3.0));
+        let c4 = test(Point::new(0.0, 0.0, -2.0));
+
+        for c in [c1, c2, c3, c4] {
+            assert!(c.red > 0.0);
+            assert_approx_eq!(c.green, 0.0);
+            assert_approx_eq!(c.blue, 0.0);
+        }
+
+        assert_approx_eq!(c1, Colour::new(0.1, 0.0, 0.0));
+        assert!(c1.red < c2.red);
+        assert!(c2.red < c3.red);
+        assert!(c3.red < c4.red);
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn lighting_with_reused_area_light_positions() {
+        let w = test_world();
+
+        let l = Light::new_area(
+            Point::new(-0.5, -0.5, -5.0),
+            Vector::x_axis(),
+            8,
+            Vector::y_axis(),
+            8,
+            Colour::white(),
+        );
+
+        let o = Object::plane_builder()
+            .material(
+                Material::builder()
+                    .pattern(Colour::white().into())
+                    .specular(0.0)
+                    .build(),
+            )
+            .build();
+
+        let e = -Vector::z_axis();
+
+        for p in [
+            Point::new(0.0, 0.0, 2.0),
+            Point::new(1.0, -1.0, 2.0),
+            Point::new(1.25, 1.25, 3.0),
+            Point::new(0.0, 0.0, -2.0),
+        ] {
+            let mut r = rng();
+
+            let i = l.intensity_at(&p, &w, &mut r);
+            let expected = o.material().lighting(&o, &l, &p, &e, &e, i, &mut r);
+
+            let positions = l.positions_at(&p, &mut rng());
+            let i = l.intensity_at_positions(&positions, &p, &w);
+            let c = o
+                .material()
+                .lighting_from_positions(&o, &l, &positions, &p, &e, &e, i);
+
+            assert_approx_eq!(c, expected, epsilon = 0.05);
+        }
+    }
+
     #[test]
     fn comparing_materials() {
         let m1 = Material::builder()
@@ -530,7 +898,11 @@ mod tests {
             "\
 color: [1, 0, 0]
 ambient: 0.6
-reflective: 0.5",
+reflective: 0.5
+roughness: 0.1
+reflection_samples: 8
+dispersion: 0.01
+shadow_catcher: true",
         )
         .unwrap();
 
@@ -540,6 +912,10 @@ reflective: 0.5",
                 .pattern(Colour::red().into())
                 .ambient(0.6)
                 .reflective(0.5)
+                .roughness(0.1)
+                .reflection_samples(8)
+                .dispersion(0.01)
+                .shadow_catcher(true)
                 .build()
         );
 
@@ -573,7 +949,9 @@ specular: 0.5",
             "\
 shininess: 125.0
 transparency: 0.4
-refractive_index: 1.2",
+refractive_index: 1.2
+max_depth: 2
+two_sided: true",
         )
         .unwrap();
 
@@ -583,6 +961,8 @@ refractive_index: 1.2",
                 .shininess(125.0)
                 .transparency(0.4)
                 .refractive_index(1.2)
+                .max_depth(Some(2))
+                .two_sided(true)
                 .build()
         );
     }
@@ -1,14 +1,17 @@
-use std::ops::Mul;
+use core::ops::Mul;
 
 use derive_more::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
 };
-use exmex::eval_str;
 use paste::paste;
+#[cfg(feature = "std")]
 use serde::{de::Error, Deserialize, Deserializer};
+#[cfg(feature = "std")]
 use serde_yaml::Value;
 
-use super::float::impl_approx_eq;
+use super::float::{self, impl_approx_eq};
+#[cfg(feature = "std")]
+use crate::util::parse_f64;
 
 /// An `Angle` represents a geometric angle, it is simply a wrapper around a
 /// value in radians but by using it rather than raw f64's we get type safety
@@ -24,13 +27,13 @@ macro_rules! add_trigonometric_fns {
     ($fn:ident) => {
         #[must_use]
         pub fn $fn(&self) -> f64 {
-            self.0.$fn()
+            float::$fn(self.0)
         }
 
         paste! {
             #[must_use]
             pub fn [<a $fn>](ratio: f64) -> Self {
-                Self(ratio.[<a $fn>]())
+                Self(float::[<a $fn>](ratio))
             }
         }
     };
@@ -53,12 +56,12 @@ impl Angle {
 
     #[must_use]
     pub fn sin_cos(&self) -> (f64, f64) {
-        self.0.sin_cos()
+        float::sin_cos(self.0)
     }
 
     #[must_use]
     pub fn atan2(y: f64, x: f64) -> Self {
-        Self(y.atan2(x))
+        Self(float::atan2(y, x))
     }
 }
 
@@ -72,6 +75,7 @@ impl Mul<Angle> for f64 {
 
 impl_approx_eq!(Angle { newtype });
 
+#[cfg(feature = "std")]
 impl<'de> Deserialize<'de> for Angle {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -84,22 +88,15 @@ impl<'de> Deserialize<'de> for Angle {
             Radians(Value),
         }
 
-        let parse = |value| match value {
-            Value::Number(number) => {
-                number.as_f64().map_or_else(|| unreachable!(), Ok)
-            }
-            Value::String(string) => {
-                eval_str::<f64>(&string).map_err(Error::custom)
-            }
-            _ => Err(Error::custom(format!(
-                "Unable to parse '{value:?}' as a float"
-            ))),
+        let parse = |value| {
+            parse_f64(value)
+                .map_err(|error| Error::custom(format!("{error:#}")))
         };
 
         match Angle::deserialize(deserializer)? {
-            Angle::Radians(radians) => Ok(Self(parse(radians)?)),
+            Angle::Radians(radians) => Ok(Self(parse(&radians)?)),
             Angle::Degrees { degrees } => {
-                Ok(Self::from_degrees(parse(degrees)?))
+                Ok(Self::from_degrees(parse(&degrees)?))
             }
         }
     }
@@ -109,6 +106,7 @@ impl<'de> Deserialize<'de> for Angle {
 mod tests {
     use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_4, FRAC_PI_6, PI};
 
+    #[cfg(feature = "std")]
     use serde_yaml::from_str;
 
     use super::*;
@@ -222,6 +220,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn deserialize_angle() {
         let a: Angle = from_str("0.5").unwrap();
 
@@ -247,5 +246,11 @@ mod tests {
             from_str::<Angle>("true").unwrap_err().to_string(),
             "Unable to parse 'Bool(true)' as a float"
         );
+
+        // The reason the expression failed to evaluate follows the message.
+        assert!(from_str::<Angle>("1 +* 2")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Unable to parse 'String(\"1 +* 2\")' as a float: "));
     }
 }
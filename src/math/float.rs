@@ -6,6 +6,43 @@
 //! the defaults for epsilon and ulps are "good enough" for our usage but they
 //! can be overwritten if needed in certain places.
 
+/// This macro defines functions for the floating point operations that `core`
+/// does not provide. They call the methods in `std` or, without the `std`
+/// feature, the functions of the same name in `libm`.
+macro_rules! std_fns {
+    ($($fn:ident($($arg:ident),*)),+ $(,)?) => {$(
+        #[cfg(feature = "std")]
+        #[must_use]
+        #[inline]
+        pub fn $fn(value: f64 $(, $arg: f64)*) -> f64 {
+            value.$fn($($arg),*)
+        }
+
+        #[cfg(not(feature = "std"))]
+        #[must_use]
+        #[inline]
+        pub fn $fn(value: f64 $(, $arg: f64)*) -> f64 {
+            libm::$fn(value $(, $arg)*)
+        }
+    )+};
+}
+
+std_fns!(sqrt(), sin(), cos(), tan(), asin(), acos(), atan(), atan2(x),);
+
+#[cfg(feature = "std")]
+#[must_use]
+#[inline]
+pub fn sin_cos(value: f64) -> (f64, f64) {
+    value.sin_cos()
+}
+
+#[cfg(not(feature = "std"))]
+#[must_use]
+#[inline]
+pub fn sin_cos(value: f64) -> (f64, f64) {
+    libm::sincos(value)
+}
+
 /// Compare if two values are almost equal. See float-cmp documentation.
 macro_rules! approx_eq {
     ($lhs:expr, $rhs:expr) => {
@@ -25,11 +62,13 @@ macro_rules! approx_eq {
 pub(crate) use approx_eq;
 
 /// Compare if two values are not almost equal. See float-cmp documentation.
+#[cfg_attr(not(feature = "std"), allow(unused_macros))]
 macro_rules! approx_ne {
     ($($tt:tt)+) => {
         !crate::math::float::approx_eq!($($tt)+)
     };
 }
+#[cfg_attr(not(feature = "std"), allow(unused_imports))]
 pub(crate) use approx_ne;
 
 /// Helper macro so we don't have to duplicate code between eq and ne asserts.
@@ -235,4 +274,24 @@ mod tests {
         assert_approx_ne!(a, b, ulps = 1, epsilon = 0.5 * EPSILON);
         assert_approx_ne!(b, a, epsilon = 0.5 * EPSILON, ulps = 1);
     }
+
+    #[test]
+    fn float_functions_match_std() {
+        for value in [0.0, 0.25, 0.5, 1.0, 2.0, 10.0] {
+            assert_approx_eq!(sqrt(value), value.sqrt());
+            assert_approx_eq!(sin(value), value.sin());
+            assert_approx_eq!(cos(value), value.cos());
+            assert_approx_eq!(tan(value), value.tan());
+            assert_approx_eq!(atan(value), value.atan());
+            assert_approx_eq!(atan2(value, -1.5), value.atan2(-1.5));
+
+            let (sin, cos) = sin_cos(value);
+            assert_approx_eq!(sin, value.sin());
+            assert_approx_eq!(cos, value.cos());
+
+            let ratio = value / 10.0;
+            assert_approx_eq!(asin(ratio), ratio.asin());
+            assert_approx_eq!(acos(ratio), ratio.acos());
+        }
+    }
 }
@@ -3,12 +3,16 @@
 
 mod angle;
 pub mod float;
+#[cfg(feature = "std")]
+mod noise;
 mod point;
 mod ray;
 mod transformation;
 mod vector;
 
 pub use angle::Angle;
+#[cfg(feature = "std")]
+pub use noise::{noise, Noise};
 pub use point::Point;
 pub use ray::Ray;
 pub use transformation::{Transformable, Transformation};
@@ -0,0 +1,71 @@
+use libnoise::{Generator, Simplex, Source};
+
+use super::Point;
+
+/// `Noise` is the seeded simplex noise used by the built in patterns, it only
+/// varies in x and z so is constant along y.
+///
+/// Values lie between -1.0 and 1.0 and vary smoothly, points close together
+/// always give close values. The same seed always gives the same noise.
+#[derive(Clone, Debug)]
+pub struct Noise(Simplex<2>);
+
+impl Noise {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(Source::simplex(seed))
+    }
+
+    #[must_use]
+    pub fn sample(&self, point: &Point) -> f64 {
+        self.0.sample([point.x, point.z])
+    }
+}
+
+/// Sample the noise for `seed` at `point`, see `Noise`. This sets up the noise
+/// each time it is called so when taking many samples with the same seed
+/// create a `Noise` instead.
+#[must_use]
+pub fn noise(point: &Point, seed: u64) -> f64 {
+    Noise::new(seed).sample(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::float::*;
+
+    #[test]
+    fn noise_is_deterministic() {
+        let p = Point::new(0.3, 1.2, -4.7);
+
+        assert_approx_eq!(noise(&p, 5), noise(&p, 5));
+        assert_approx_eq!(noise(&p, 5), Noise::new(5).sample(&p));
+        assert_approx_ne!(noise(&p, 5), noise(&p, 6));
+
+        // The noise does not vary along y.
+        assert_approx_eq!(noise(&p, 5), noise(&Point::new(0.3, 9.0, -4.7), 5));
+    }
+
+    #[test]
+    fn noise_is_bounded_and_continuous() {
+        let n = Noise::new(42);
+
+        let step = 0.000_1;
+
+        for i in 0..1_000 {
+            let x = f64::from(i) * 0.037;
+            let z = f64::from(i) * -0.011;
+
+            let value = n.sample(&Point::new(x, 0.0, z));
+
+            assert!((-1.0..=1.0).contains(&value));
+
+            for neighbour in
+                [Point::new(x + step, 0.0, z), Point::new(x, 0.0, z + step)]
+            {
+                assert_approx_eq!(n.sample(&neighbour), value, epsilon = 0.001);
+            }
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use core::ops::{Add, AddAssign, Sub, SubAssign};
 
 use derive_new::new;
 
 use super::{float::impl_approx_eq, Vector};
+#[cfg(feature = "std")]
 use crate::util::impl_deserialize_tuple;
 
 /// A Point is a representation of a geometric position within the 3 dimensional
@@ -19,6 +20,20 @@ impl Point {
     pub fn origin() -> Self {
         Self::new(0.0, 0.0, 0.0)
     }
+
+    #[must_use]
+    pub fn distance(&self, other: &Self) -> f64 {
+        (*self - *other).magnitude()
+    }
+
+    /// The square of the distance between two points, this avoids a square
+    /// root when only comparing distances.
+    #[must_use]
+    pub fn distance_squared(&self, other: &Self) -> f64 {
+        let vector = *self - *other;
+
+        vector.dot(&vector)
+    }
 }
 
 impl Add<Vector> for Point {
@@ -71,10 +86,15 @@ impl SubAssign<Vector> for Point {
 
 impl_approx_eq!(Point { x, y, z });
 
+#[cfg(feature = "std")]
 impl_deserialize_tuple!(Point);
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "std")]
+    use std::f64::consts::TAU;
+
+    #[cfg(feature = "std")]
     use serde_yaml::from_str;
 
     use super::*;
@@ -130,6 +150,22 @@ mod tests {
         assert_approx_eq!(p, Point::new(1.2, -0.1, -1.0));
     }
 
+    #[test]
+    fn the_distance_between_two_points() {
+        let p1 = Point::new(1.0, 2.0, 3.0);
+        let p2 = Point::new(4.0, 6.0, 3.0);
+
+        assert_approx_eq!(p1.distance(&p2), 5.0);
+        assert_approx_eq!(p2.distance(&p1), 5.0);
+        assert_approx_eq!(p1.distance_squared(&p2), 25.0);
+
+        assert_approx_eq!(p1.distance(&p1), 0.0);
+        assert_approx_eq!(
+            Point::origin().distance(&Point::new(-1.0, 1.0, -1.0)),
+            3.0_f64.sqrt()
+        );
+    }
+
     #[test]
     fn comparing_points() {
         let p1 = Point::new(1.0, 2.0, 3.0);
@@ -142,9 +178,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn deserialize_point() {
         let p: Point = from_str("[1.0, 0.5, 2]").unwrap();
 
         assert_approx_eq!(p, Point::new(1.0, 0.5, 2.0));
+
+        let p: Point = from_str("[1/4, 2 * PI, '-3']").unwrap();
+
+        assert_approx_eq!(p, Point::new(0.25, TAU, -3.0));
     }
 }
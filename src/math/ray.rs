@@ -5,11 +5,18 @@ use super::{
 };
 
 /// A Ray represents a geometric vector with a specific origin point and
-/// pointing in some direction.
+/// pointing in some direction. Rays normally carry every wavelength of light,
+/// when rendering spectrally a ray may instead carry a single wavelength in
+/// micrometres. Rays reflected from rough surfaces are marked as glossy so that
+/// any further rough surfaces they hit only trace a single reflection ray.
 #[derive(Clone, Copy, Debug, new)]
 pub struct Ray {
     pub origin: Point,
     pub direction: Vector,
+    #[new(default)]
+    pub wavelength: Option<f64>,
+    #[new(default)]
+    pub glossy: bool,
 }
 
 impl Ray {
@@ -17,6 +24,29 @@ impl Ray {
     pub fn position(&self, t: f64) -> Point {
         self.origin + self.direction * t
     }
+
+    /// Transform the ray, the same as `apply`. The direction is deliberately
+    /// not normalised afterwards, a ray moved into the space of a scaled
+    /// object must keep its scaled length so that t values found there are
+    /// still distances along the original ray.
+    #[must_use]
+    pub fn transform_by(&self, transformation: &Transformation) -> Self {
+        self.apply(transformation)
+    }
+
+    #[must_use]
+    pub const fn with_wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn with_glossy(mut self, glossy: bool) -> Self {
+        self.glossy = glossy;
+
+        self
+    }
 }
 
 impl Transformable for Ray {
@@ -25,6 +55,8 @@ impl Transformable for Ray {
             self.origin.apply(transformation),
             self.direction.apply(transformation),
         )
+        .with_wavelength(self.wavelength)
+        .with_glossy(self.glossy)
     }
 }
 
@@ -34,6 +66,8 @@ impl_approx_eq!(Ray { origin, direction });
 mod tests {
     use super::*;
     use crate::math::float::*;
+    #[cfg(feature = "std")]
+    use crate::Object;
 
     #[test]
     fn creating_a_ray() {
@@ -65,6 +99,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transforming_a_ray_keeps_its_wavelength() {
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        assert!(r.wavelength.is_none());
+
+        let r = r
+            .with_wavelength(Some(0.5))
+            .apply(&Transformation::new().translate(1.0, 0.0, 0.0));
+
+        assert_approx_eq!(r.wavelength.unwrap(), 0.5);
+    }
+
     #[test]
     fn scaling_a_ray() {
         assert_approx_eq!(
@@ -74,6 +121,26 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn transforming_a_ray_keeps_the_scale_of_its_direction() {
+        let t = Transformation::new().scale(2.0, 2.0, 2.0);
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let object_space = r.transform_by(&t.invert());
+
+        assert_approx_eq!(object_space, r.apply(&t.invert()));
+        assert_approx_eq!(object_space.direction.magnitude(), 0.5);
+
+        // Were the direction normalised the hits would be at 1.5 and 3.5.
+        let o = Object::sphere_builder().transformation(t).build();
+        let i = o.intersect(&r).unwrap();
+
+        assert_eq!(i.len(), 2);
+        assert_approx_eq!(i[0].t, 3.0);
+        assert_approx_eq!(i[1].t, 7.0);
+    }
+
     #[test]
     fn comparing_rays() {
         let r1 =
@@ -0,0 +1,272 @@
+use super::{matrix::Matrix, Transformation};
+use crate::math::float::{self, approx_eq};
+
+/// A `Decomposed` transformation split into a translation, a rotation stored
+/// as a unit quaternion (w, x, y, z) and a scale. Any shear in the original
+/// transformation is lost.
+#[derive(Clone, Copy, Debug)]
+struct Decomposed {
+    translation: [f64; 3],
+    rotation: [f64; 4],
+    scale: [f64; 3],
+}
+
+impl Decomposed {
+    #[must_use]
+    fn new(matrix: &Matrix<4>) -> Self {
+        let translation = [matrix[0][3], matrix[1][3], matrix[2][3]];
+
+        let mut scale = [0.0; 3];
+        for (col, scale) in scale.iter_mut().enumerate() {
+            *scale = float::sqrt(
+                (0..3).map(|row| matrix[row][col] * matrix[row][col]).sum(),
+            );
+        }
+
+        // A reflection can't be represented by a rotation so fold it into
+        // the scale instead.
+        if matrix.determinant() < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        let mut rotation = [[0.0; 3]; 3];
+        for (row, rotation) in rotation.iter_mut().enumerate() {
+            for (col, value) in rotation.iter_mut().enumerate() {
+                *value = if approx_eq!(scale[col], 0.0) {
+                    0.0
+                } else {
+                    matrix[row][col] / scale[col]
+                };
+            }
+        }
+
+        Self { translation, rotation: quaternion(&rotation), scale }
+    }
+
+    #[must_use]
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        let mut translation = [0.0; 3];
+        let mut scale = [0.0; 3];
+        for index in 0..3 {
+            translation[index] =
+                lerp(self.translation[index], other.translation[index]);
+            scale[index] = lerp(self.scale[index], other.scale[index]);
+        }
+
+        Self {
+            translation,
+            rotation: slerp(&self.rotation, &other.rotation, t),
+            scale,
+        }
+    }
+
+    #[must_use]
+    fn matrix(&self) -> Matrix<4> {
+        let [w, x, y, z] = self.rotation;
+
+        let rotation = [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ];
+
+        let mut matrix = Matrix::<4>::identity();
+        for row in 0..3 {
+            for col in 0..3 {
+                matrix[row][col] = rotation[row][col] * self.scale[col];
+            }
+            matrix[row][3] = self.translation[row];
+        }
+
+        matrix
+    }
+}
+
+/// Convert a rotation matrix into a unit quaternion.
+#[must_use]
+fn quaternion(r: &[[f64; 3]; 3]) -> [f64; 4] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+
+    if trace > 0.0 {
+        let s = float::sqrt(trace + 1.0) * 2.0;
+        [
+            s / 4.0,
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+        ]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = float::sqrt(1.0 + r[0][0] - r[1][1] - r[2][2]) * 2.0;
+        [
+            (r[2][1] - r[1][2]) / s,
+            s / 4.0,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+        ]
+    } else if r[1][1] > r[2][2] {
+        let s = float::sqrt(1.0 + r[1][1] - r[0][0] - r[2][2]) * 2.0;
+        [
+            (r[0][2] - r[2][0]) / s,
+            (r[0][1] + r[1][0]) / s,
+            s / 4.0,
+            (r[1][2] + r[2][1]) / s,
+        ]
+    } else {
+        let s = float::sqrt(1.0 + r[2][2] - r[0][0] - r[1][1]) * 2.0;
+        [
+            (r[1][0] - r[0][1]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            s / 4.0,
+        ]
+    }
+}
+
+/// Spherically interpolate between two unit quaternions, always taking the
+/// shortest path.
+#[must_use]
+fn slerp(a: &[f64; 4], b: &[f64; 4], t: f64) -> [f64; 4] {
+    let mut b = *b;
+    let mut dot: f64 = a.iter().zip(&b).map(|(a, b)| a * b).sum();
+
+    if dot < 0.0 {
+        b = b.map(|value| -value);
+        dot = -dot;
+    }
+
+    let (weight_a, weight_b) = if dot > 0.999_5 {
+        // The quaternions are almost the same so a linear interpolation is
+        // accurate and avoids dividing by a tiny sine.
+        (1.0 - t, t)
+    } else {
+        let angle = float::acos(dot);
+        let sin = float::sin(angle);
+
+        (float::sin((1.0 - t) * angle) / sin, float::sin(t * angle) / sin)
+    };
+
+    let mut result = [0.0; 4];
+    for (index, value) in result.iter_mut().enumerate() {
+        *value = a[index] * weight_a + b[index] * weight_b;
+    }
+
+    let length = float::sqrt(result.iter().map(|value| value * value).sum());
+
+    result.map(|value| value / length)
+}
+
+impl Transformation {
+    /// Interpolate between two transformations where `t` of 0.0 gives `self`
+    /// and 1.0 gives `other`. The translation and scale are interpolated
+    /// linearly and the rotation spherically, shear is not supported and will
+    /// be lost.
+    #[must_use]
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self(
+            Decomposed::new(&self.0)
+                .interpolate(&Decomposed::new(&other.0), t)
+                .matrix(),
+        )
+    }
+
+    /// Find the transformation at `time` from a list of
+    /// `(time, Transformation)` keyframes sorted by time. Times before the
+    /// first or after the last keyframe hold that keyframe and no keyframes
+    /// gives the identity.
+    #[must_use]
+    pub fn from_keyframes(keyframes: &[(f64, Self)], time: f64) -> Self {
+        let Some(index) = keyframes.iter().position(|(at, _)| *at > time)
+        else {
+            return keyframes
+                .last()
+                .map_or_else(Self::new, |(_, transformation)| *transformation);
+        };
+
+        if index == 0 {
+            return keyframes[0].1;
+        }
+
+        let (start, from) = keyframes[index - 1];
+        let (end, to) = keyframes[index];
+
+        from.interpolate(&to, (time - start) / (end - start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::math::{float::*, Angle, Point};
+
+    #[test]
+    fn decomposing_a_transformation() {
+        let t = Transformation::new()
+            .scale(2.0, -3.0, 0.5)
+            .rotate_y(Angle(1.1))
+            .rotate_x(Angle(-0.4))
+            .translate(1.0, 2.0, -3.0);
+
+        assert_approx_eq!(Transformation(Decomposed::new(&t.0).matrix()), t);
+    }
+
+    #[test]
+    fn interpolating_a_transformation() {
+        let a = Transformation::new().translate(1.0, 0.0, 0.0);
+        let b = Transformation::new()
+            .scale(3.0, 3.0, 3.0)
+            .rotate_y(Angle(FRAC_PI_2))
+            .translate(3.0, 2.0, 0.0);
+
+        assert_approx_eq!(a.interpolate(&b, 0.0), a);
+        assert_approx_eq!(a.interpolate(&b, 1.0), b, epsilon = 0.000_01);
+        assert_approx_eq!(
+            a.interpolate(&b, 0.5),
+            Transformation::new()
+                .scale(2.0, 2.0, 2.0)
+                .rotate_y(Angle(FRAC_PI_2 / 2.0))
+                .translate(2.0, 1.0, 0.0),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn interpolating_keyframes() {
+        let keyframes = [
+            (1.0, Transformation::new().translate(0.0, 0.0, 0.0)),
+            (2.0, Transformation::new().translate(2.0, 0.0, 0.0)),
+            (4.0, Transformation::new().translate(2.0, 4.0, 0.0)),
+        ];
+
+        let at = |time| {
+            Transformation::from_keyframes(&keyframes, time)
+                .apply(&Point::origin())
+        };
+
+        assert_approx_eq!(at(0.0), Point::origin());
+        assert_approx_eq!(at(1.5), Point::new(1.0, 0.0, 0.0));
+        assert_approx_eq!(at(2.0), Point::new(2.0, 0.0, 0.0));
+        assert_approx_eq!(at(3.0), Point::new(2.0, 2.0, 0.0));
+        assert_approx_eq!(at(5.0), Point::new(2.0, 4.0, 0.0));
+
+        assert_approx_eq!(
+            Transformation::from_keyframes(&[], 1.0),
+            Transformation::new()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{
+use core::{
     fmt::{self, Debug, Formatter},
     ops::{Mul, MulAssign},
     slice::{Iter, IterMut},
@@ -7,6 +7,8 @@ use std::{
 use anyhow::{bail, Result};
 use derive_more::{Index, IndexMut, IntoIterator};
 use float_cmp::{ApproxEq, F64Margin};
+#[cfg(feature = "std")]
+use serde::{Serialize, Serializer};
 
 use crate::math::{float::approx_eq, Angle, Point, Vector};
 
@@ -219,6 +221,22 @@ impl Matrix<4> {
         matrix * Self::translate(-from.x, -from.y, -from.z)
     }
 
+    /// Unlike `view_transformation` the axes are normalised so that the
+    /// object is only rotated and moved, never scaled or sheared.
+    #[must_use]
+    pub fn look_at(from: Point, to: Point, up: Vector) -> Self {
+        let forward = (to - from).normalise();
+        let right = up.cross(&forward).normalise();
+        let true_up = forward.cross(&right);
+
+        Self([
+            [right.x, true_up.x, forward.x, from.x],
+            [right.y, true_up.y, forward.y, from.y],
+            [right.z, true_up.z, forward.z, from.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
     /// Attempt to invert the matrix.
     ///
     /// # Errors
@@ -345,6 +363,17 @@ impl<'a, const N: usize> IntoIterator for &'a mut Matrix<N> {
     }
 }
 
+#[cfg(feature = "std")]
+impl<const N: usize> Serialize for Matrix<N> {
+    /// Matrices are written as a list of rows.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter().map(<[f64; N]>::as_slice))
+    }
+}
+
 impl<const N: usize> Debug for Matrix<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(f, "Matrix<{N}>([")?;
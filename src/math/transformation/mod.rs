@@ -1,12 +1,26 @@
+mod interpolate;
 mod matrix;
 mod transformable;
 
-use serde::{de::Error, Deserialize, Deserializer};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Mul;
+
+#[cfg(feature = "std")]
+use anyhow::Context;
+#[cfg(feature = "std")]
+use serde::{
+    de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serialize,
+    Serializer,
+};
+#[cfg(feature = "std")]
 use serde_yaml::{from_value, Value};
 
 use self::matrix::Matrix;
 pub use self::transformable::Transformable;
 use super::{float::impl_approx_eq, Angle, Point, Vector};
+#[cfg(feature = "std")]
+use crate::util::parse_f64;
 
 /// A `Transformation` is a wrapper around a 4 dimensional matrix allowing a
 /// more ergonomic use of transformations. Transformations can be chained in an
@@ -43,11 +57,47 @@ impl Transformation {
         Self(Matrix::view_transformation(from, to, up))
     }
 
+    /// Orient an object at `from` so that its +z axis points at `to` and its
+    /// +y axis is as close to `up` as possible, the object equivalent of
+    /// `view_transformation`.
+    #[must_use]
+    pub fn look_at(from: Point, to: Point, up: Vector) -> Self {
+        Self(Matrix::look_at(from, to, up))
+    }
+
     #[must_use]
     pub fn apply<T: Transformable>(&self, object: &T) -> T {
         object.apply(self)
     }
 
+    /// Apply the transformation to every point in a slice, see
+    /// `apply_many_in_place`.
+    #[must_use]
+    pub fn apply_many(&self, points: &[Point]) -> Vec<Point> {
+        let mut points = points.to_vec();
+
+        self.apply_many_in_place(&mut points);
+
+        points
+    }
+
+    /// Apply the transformation to every point in a slice, replacing the
+    /// points. The rows of the matrix are copied out once up front rather than
+    /// being read for every point.
+    pub fn apply_many_in_place(&self, points: &mut [Point]) {
+        let [row0, row1, row2] = [self.0[0], self.0[1], self.0[2]];
+
+        for point in points {
+            let Point { x, y, z } = *point;
+
+            *point = Point::new(
+                row0[0] * x + row0[1] * y + row0[2] * z + row0[3],
+                row1[0] * x + row1[1] * y + row1[2] * z + row1[3],
+                row2[0] * x + row2[1] * y + row2[2] * z + row2[3],
+            );
+        }
+    }
+
     /// Unlike the other function on `Transform`, `invert` is not intended for
     /// chaining, instead it returns a new `Transform` with the inverted matrix.
     ///
@@ -68,6 +118,12 @@ impl Transformation {
         Self(self.0.transpose())
     }
 
+    /// Check that no value in the transformation is NaN or infinite.
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.0.iter().flatten().all(|value| value.is_finite())
+    }
+
     #[allow(clippy::return_self_not_must_use)]
     pub fn extend(&mut self, transformation: &Self) -> Self {
         self.0 = transformation.0 * self.0;
@@ -91,8 +147,56 @@ impl Default for Transformation {
     }
 }
 
+impl From<[[f64; 4]; 4]> for Transformation {
+    /// Create a transformation directly from the rows of a matrix.
+    fn from(rows: [[f64; 4]; 4]) -> Self {
+        Self(Matrix(rows))
+    }
+}
+
+/// Compose two transformations in matrix order, `a * b` applies `b` first and
+/// then `a`, so `a * b` is the same as `b.extend(&a)`.
+impl Mul for Transformation {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl Mul<Point> for Transformation {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Self::Output {
+        self.0 * rhs
+    }
+}
+
+impl Mul<Vector> for Transformation {
+    type Output = Vector;
+
+    fn mul(self, rhs: Vector) -> Self::Output {
+        self.0 * rhs
+    }
+}
+
 impl_approx_eq!(Transformation { newtype });
 
+#[cfg(feature = "std")]
+impl Serialize for Transformation {
+    /// Transformations are written as a single `matrix` operation so that any
+    /// dump can be read back in, the original operations are not kept.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut list = serializer.serialize_seq(Some(1))?;
+        list.serialize_element(&("matrix", &self.0))?;
+        list.end()
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'de> Deserialize<'de> for Transformation {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -124,12 +228,12 @@ Transformation '{op}' requires {len} arguments, found {vec_len}"
                 Ok(())
             };
 
-            let parse = |value: &Value| {
-                value.as_f64().ok_or_else(|| {
-                    Error::custom(format!(
-                        "Failed to parse '{value:?}' as an f64"
-                    ))
-                })
+            let parse = |value| {
+                parse_f64(value)
+                    .with_context(|| {
+                        format!("Failed to parse '{value:?}' as an f64")
+                    })
+                    .map_err(Error::custom)
             };
 
             match op {
@@ -175,6 +279,14 @@ Transformation '{op}' requires {len} arguments, found {vec_len}"
                         parse(&values[5])?,
                     )
                 }
+                "matrix" => {
+                    check_len(op, 1)?;
+
+                    final_transformation.extend(&Self::from(
+                        from_value::<[[f64; 4]; 4]>(values[0].clone())
+                            .map_err(Error::custom)?,
+                    ))
+                }
                 "translate" => {
                     check_len(op, 3)?;
 
@@ -200,7 +312,8 @@ Transformation '{op}' requires {len} arguments, found {vec_len}"
 mod tests {
     use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_6};
 
-    use serde_yaml::from_str;
+    #[cfg(feature = "std")]
+    use serde_yaml::{from_str, to_string};
 
     use super::*;
     use crate::math::float::*;
@@ -214,6 +327,19 @@ mod tests {
         assert_approx_eq!(t, Transformation::default());
     }
 
+    #[test]
+    fn checking_if_a_transformation_is_finite() {
+        assert!(Transformation::new().is_finite());
+        assert!(Transformation::new().scale(2.0, 1.0, -3.0).is_finite());
+
+        assert!(!Transformation::new()
+            .translate(f64::NAN, 0.0, 0.0)
+            .is_finite());
+        assert!(!Transformation::new()
+            .scale(f64::INFINITY, 1.0, 1.0)
+            .is_finite());
+    }
+
     #[test]
     fn ordering_of_transformations() {
         let p = Point::new(3.0, 2.0, 1.0);
@@ -244,6 +370,93 @@ mod tests {
         assert_approx_eq!(t.apply(&p), o);
     }
 
+    #[test]
+    fn creating_a_transformation_from_rows() {
+        let t = Transformation::new()
+            .rotate_y(Angle(0.4))
+            .translate(1.0, -2.0, 3.0);
+
+        assert_approx_eq!(Transformation::from(t.0 .0), t);
+        assert_approx_eq!(
+            Transformation::from([
+                [1.0, 0.0, 0.0, 5.0],
+                [0.0, 1.0, 0.0, -3.0],
+                [0.0, 0.0, 1.0, 2.0],
+                [0.0, 0.0, 0.0, 1.0]
+            ]),
+            Transformation::new().translate(5.0, -3.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn composing_transformations_by_multiplication() {
+        let p = Point::new(3.0, 2.0, 1.0);
+
+        let s = Transformation::new().scale(2.0, 2.0, 2.0);
+        let t = Transformation::new().translate(1.0, 2.0, 3.0);
+
+        let c = s * t;
+
+        assert_approx_eq!(
+            c,
+            Transformation::new().translate(1.0, 2.0, 3.0).scale(2.0, 2.0, 2.0)
+        );
+        assert_approx_eq!(c.apply(&p), Point::new(8.0, 8.0, 8.0));
+
+        let mut extended = t;
+        assert_approx_eq!(c, extended.extend(&s));
+
+        assert_approx_ne!(c, t * s);
+    }
+
+    #[test]
+    fn applying_a_transformation_by_multiplication() {
+        let t = Transformation::new()
+            .rotate_y(Angle(FRAC_PI_3))
+            .scale(1.0, 2.0, 3.0)
+            .translate(5.0, -2.0, 1.0);
+
+        let p = Point::new(1.0, -4.0, 2.5);
+        let v = Vector::new(-2.0, 0.5, 1.0);
+
+        assert_approx_eq!(t * p, t.apply(&p));
+        assert_approx_eq!(t * v, t.apply(&v));
+    }
+
+    #[test]
+    fn applying_a_transformation_to_many_points() {
+        let t = Transformation::new()
+            .rotate_x(Angle(FRAC_PI_3))
+            .scale(2.0, 0.5, 3.0)
+            .shear(1.0, 0.0, 0.5, 0.0, 0.0, 2.0)
+            .translate(-1.0, 4.0, 2.5);
+
+        let p: Vec<_> = (0..100)
+            .map(|i| {
+                let i = f64::from(i);
+
+                Point::new(i * 0.3 - 10.0, i.sin() * 5.0, 20.0 - i * 0.7)
+            })
+            .collect();
+
+        let m = t.apply_many(&p);
+
+        assert_eq!(m.len(), p.len());
+
+        for (many, point) in m.iter().zip(&p) {
+            assert_approx_eq!(*many, t.apply(point));
+        }
+
+        let mut i = p.clone();
+        t.apply_many_in_place(&mut i);
+
+        for (in_place, many) in i.iter().zip(&m) {
+            assert_approx_eq!(*in_place, *many);
+        }
+
+        assert!(t.apply_many(&[]).is_empty());
+    }
+
     #[test]
     fn creating_a_view_transformation() {
         let from = Point::new(1.0, 2.0, 3.0);
@@ -256,6 +469,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn orienting_an_object_to_look_at_a_point() {
+        let from = Point::new(1.0, 2.0, 3.0);
+        let to = Point::new(4.0, -1.0, 5.0);
+        let up = Vector::y_axis();
+
+        let t = Transformation::look_at(from, to, up);
+
+        assert_approx_eq!(Point::origin().apply(&t), from);
+        assert_approx_eq!(
+            Point::new(0.0, 0.0, (to - from).magnitude()).apply(&t),
+            to
+        );
+        assert_approx_eq!(Vector::z_axis().apply(&t), (to - from).normalise());
+
+        let y = Vector::y_axis().apply(&t);
+        assert_approx_eq!(y.dot(&(to - from)), 0.0, epsilon = 0.000_01);
+        assert!(y.dot(&up) > 0.0);
+
+        // The object is only rotated so it stays the same handedness.
+        assert_approx_eq!(
+            Vector::x_axis().apply(&t).cross(&y),
+            Vector::z_axis().apply(&t)
+        );
+
+        assert_approx_eq!(
+            Transformation::look_at(
+                Point::origin(),
+                Point::new(0.0, 0.0, 1.0),
+                up
+            ),
+            Transformation::new()
+        );
+    }
+
     #[test]
     fn applying_a_transformation() {
         let p = Point::new(1.5, 2.5, 3.5);
@@ -370,6 +618,29 @@ Tried to invert a Matrix that cannot be inverted - Matrix<4>([
         );
     }
 
+    /// Without `std` rotations take their sines and cosines from libm. Tests
+    /// always link `std` so its functions can be used as the reference.
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn rotating_and_inverting_without_std() {
+        let p = Point::new(0.0, 1.0, 0.0);
+
+        let t = Transformation::new()
+            .rotate_x(Angle(FRAC_PI_3))
+            .rotate_z(Angle(1.2))
+            .translate(1.0, -2.0, 0.5);
+
+        assert_approx_eq!(
+            t.apply(&p),
+            Point::new(
+                1.0 - 0.5 * 1.2_f64.sin(),
+                0.5 * 1.2_f64.cos() - 2.0,
+                FRAC_PI_3.sin() + 0.5
+            )
+        );
+        assert_approx_eq!(t.invert().apply(&t.apply(&p)), p);
+    }
+
     #[test]
     fn shearing_a_transformation() {
         assert_approx_eq!(
@@ -405,6 +676,7 @@ Tried to invert a Matrix that cannot be inverted - Matrix<4>([
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn deserialize_single_transformation() {
         assert_approx_eq!(
             from_str::<Transformation>("- [rotate-x, -0.5]").unwrap(),
@@ -441,6 +713,7 @@ Tried to invert a Matrix that cannot be inverted - Matrix<4>([
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn deserialize_multiple_transformations() {
         assert_approx_eq!(
             from_str::<Transformation>(
@@ -460,6 +733,75 @@ Tried to invert a Matrix that cannot be inverted - Matrix<4>([
     }
 
     #[test]
+    #[cfg(feature = "std")]
+    fn deserialize_transformations_with_expressions() {
+        assert_approx_eq!(
+            from_str::<Transformation>(
+                "\
+- [translate, 2 * PI, '1/3', -0.5]
+- [scale, 1, PI / 2, '3 - 1']"
+            )
+            .unwrap(),
+            Transformation::new()
+                .translate(2.0 * std::f64::consts::PI, 1.0 / 3.0, -0.5)
+                .scale(1.0, FRAC_PI_2, 2.0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn deserialize_a_matrix_transformation() {
+        assert_approx_eq!(
+            from_str::<Transformation>(
+                "\
+- [scale, 2, 2, 2]
+- [matrix, [[1, 0, 0, 1], [0, 1, 0, 2], [0, 0, 1, 3], [0, 0, 0, 1]]]"
+            )
+            .unwrap(),
+            Transformation::new().scale(2.0, 2.0, 2.0).translate(1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn serializing_a_transformation() {
+        assert_eq!(
+            to_string(&Transformation::new().translate(1.0, 2.0, 3.0)).unwrap(),
+            "\
+- - matrix
+  - - - 1.0
+      - 0.0
+      - 0.0
+      - 1.0
+    - - 0.0
+      - 1.0
+      - 0.0
+      - 2.0
+    - - 0.0
+      - 0.0
+      - 1.0
+      - 3.0
+    - - 0.0
+      - 0.0
+      - 0.0
+      - 1.0
+"
+        );
+
+        let t = Transformation::new()
+            .scale(1.5, 0.5, 2.0)
+            .shear(0.1, 0.0, 0.3, 0.0, 0.0, 0.2)
+            .rotate_y(Angle(FRAC_PI_3))
+            .translate(-4.0, 2.5, 1.0);
+
+        assert_approx_eq!(
+            from_str::<Transformation>(&to_string(&t).unwrap()).unwrap(),
+            t
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
     fn deserialize_invalid_transformation() {
         assert_eq!(
             from_str::<Transformation>("- [5, 1, 2, 3]")
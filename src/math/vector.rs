@@ -1,11 +1,15 @@
-use std::ops::Mul;
+use core::ops::Mul;
 
 use derive_more::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
 };
 use derive_new::new;
 
-use super::float::{approx_eq, impl_approx_eq};
+use super::{
+    float::{self, approx_eq, impl_approx_eq},
+    Angle,
+};
+#[cfg(feature = "std")]
 use crate::util::impl_deserialize_tuple;
 
 /// A Vector is a representation of a geometric vector, pointing in a given
@@ -37,7 +41,7 @@ impl Vector {
 
     #[must_use]
     pub fn magnitude(&self) -> f64 {
-        (self.dot(self)).sqrt()
+        float::sqrt(self.dot(self))
     }
 
     #[must_use]
@@ -51,6 +55,17 @@ impl Vector {
         *self / magnitude
     }
 
+    /// How far the magnitude of a vector expected to be normalised, such as a
+    /// normal or the eye vector used in lighting, can be from 1 before debug
+    /// builds complain.
+    pub const NORMALISED_EPSILON: f64 = 1e-6;
+
+    /// Check if the magnitude of the vector is within `epsilon` of 1.
+    #[must_use]
+    pub fn is_normalised(&self, epsilon: f64) -> bool {
+        (self.magnitude() - 1.0).abs() <= epsilon
+    }
+
     #[must_use]
     pub fn dot(&self, rhs: &Self) -> f64 {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
@@ -65,10 +80,45 @@ impl Vector {
         )
     }
 
+    /// The angle between two vectors in the range 0..PI, the cosine is clamped
+    /// so rounding errors on parallel vectors can not give a NaN. The angle to
+    /// a zero vector is 0.
+    #[must_use]
+    pub fn angle_between(&self, other: &Self) -> Angle {
+        let magnitudes = self.magnitude() * other.magnitude();
+
+        if approx_eq!(magnitudes, 0.0) {
+            return Angle(0.0);
+        }
+
+        Angle::acos((self.dot(other) / magnitudes).clamp(-1.0, 1.0))
+    }
+
     #[must_use]
     pub fn reflect(&self, normal: &Self) -> Self {
         *self - *normal * 2.0 * self.dot(normal)
     }
+
+    /// The component of this vector that lies along `other`. Projecting onto a
+    /// zero vector gives a zero vector.
+    #[must_use]
+    pub fn project_onto(&self, other: &Self) -> Self {
+        let length_squared = other.dot(other);
+
+        if approx_eq!(length_squared, 0.0) {
+            return Self::new(0.0, 0.0, 0.0);
+        }
+
+        *other * (self.dot(other) / length_squared)
+    }
+
+    /// The component of this vector that is perpendicular to `other`, so that
+    /// the projection and rejection sum to the original vector. Rejecting from
+    /// a zero vector gives the original vector.
+    #[must_use]
+    pub fn reject_from(&self, other: &Self) -> Self {
+        *self - self.project_onto(other)
+    }
 }
 
 impl Mul<Vector> for f64 {
@@ -81,12 +131,14 @@ impl Mul<Vector> for f64 {
 
 impl_approx_eq!(Vector { x, y, z });
 
+#[cfg(feature = "std")]
 impl_deserialize_tuple!(Vector);
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::SQRT_2;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI, SQRT_2};
 
+    #[cfg(feature = "std")]
     use serde_yaml::from_str;
 
     use super::*;
@@ -141,6 +193,18 @@ mod tests {
         assert_approx_eq!(v.magnitude(), 0.0);
     }
 
+    #[test]
+    fn checking_if_a_vector_is_normalised() {
+        assert!(Vector::x_axis().is_normalised(0.0));
+        assert!(Vector::new(1.0, 2.0, 3.0).normalise().is_normalised(1e-12));
+        assert!(Vector::new(0.0, 1.000_1, 0.0).is_normalised(0.001));
+
+        assert!(!Vector::new(0.0, 1.000_1, 0.0).is_normalised(0.000_01));
+        assert!(!Vector::new(1.0, 1.0, 0.0).is_normalised(0.1));
+        assert!(!Vector::new(0.0, 0.0, 0.0).is_normalised(0.5));
+        assert!(!Vector::new(f64::NAN, 0.0, 0.0).is_normalised(1.0));
+    }
+
     #[test]
     fn computing_the_dot_product_of_two_vectors() {
         let v1 = Vector::new(1.0, 2.0, 3.0);
@@ -178,6 +242,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_angle_between_two_vectors() {
+        assert_approx_eq!(
+            Vector::x_axis().angle_between(&Vector::y_axis()),
+            Angle(FRAC_PI_2)
+        );
+        assert_approx_eq!(
+            Vector::new(2.0, 0.0, 0.0)
+                .angle_between(&Vector::new(1.0, 1.0, 0.0)),
+            Angle(FRAC_PI_4)
+        );
+        assert_approx_eq!(
+            Vector::z_axis().angle_between(&-Vector::z_axis()),
+            Angle(PI)
+        );
+
+        let v = Vector::new(0.1, 0.7, 0.3);
+
+        assert_approx_eq!(v.angle_between(&(v * 3.0)), Angle(0.0));
+        assert_approx_eq!(
+            v.angle_between(&Vector::new(0.0, 0.0, 0.0)),
+            Angle(0.0)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn projecting_and_rejecting_vectors() {
+        let v = Vector::new(1.0, 1.0, 0.0);
+        let x = Vector::x_axis();
+
+        assert_approx_eq!(v.project_onto(&x), Vector::x_axis());
+        assert_approx_eq!(v.reject_from(&x), Vector::y_axis());
+        assert_approx_eq!(v.reject_from(&x).dot(&x), 0.0);
+        assert_approx_eq!(v.project_onto(&x) + v.reject_from(&x), v);
+
+        let o = Vector::new(2.0, -1.0, 3.0);
+        let p = Vector::new(0.5, 4.0, -2.5);
+
+        assert_approx_eq!(p.reject_from(&o).dot(&o), 0.0);
+        assert_approx_eq!(
+            p.project_onto(&o).cross(&o),
+            Vector::new(0.0, 0.0, 0.0)
+        );
+        assert_approx_eq!(
+            p.project_onto(&(o * 3.0)),
+            p.project_onto(&o),
+            epsilon = 0.000_01
+        );
+
+        let z = Vector::new(0.0, 0.0, 0.0);
+
+        assert_approx_eq!(v.project_onto(&z), z);
+        assert_approx_eq!(v.reject_from(&z), v);
+    }
+
     #[test]
     fn adding_two_vectors() {
         assert_approx_eq!(
@@ -265,6 +385,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn deserialize_vector() {
         let v: Vector = from_str("[1, -2, 3.7]").unwrap();
 
@@ -12,7 +12,7 @@ use crate::{
     intersection::TList,
     math::{
         float::{approx_eq, impl_approx_eq},
-        Point, Ray, Transformable, Transformation,
+        Point, Ray, Transformable, Transformation, Vector,
     },
 };
 
@@ -111,6 +111,66 @@ impl BoundingBox {
         (min, max)
     }
 
+    /// The eight corners of the box.
+    #[must_use]
+    pub fn corners(&self) -> Vec<Point> {
+        let (minimum, maximum) = (self.minimum, self.maximum);
+
+        vec![
+            minimum,
+            Point::new(minimum.x, minimum.y, maximum.z),
+            Point::new(minimum.x, maximum.y, minimum.z),
+            Point::new(minimum.x, maximum.y, maximum.z),
+            Point::new(maximum.x, minimum.y, minimum.z),
+            Point::new(maximum.x, minimum.y, maximum.z),
+            Point::new(maximum.x, maximum.y, minimum.z),
+            maximum,
+        ]
+    }
+
+    /// The twelve edges of the box as pairs of corners, the four edges along
+    /// x followed by those along y and z.
+    #[must_use]
+    pub fn edges(&self) -> Vec<(Point, Point)> {
+        let corners = self.corners();
+
+        // The corners are ordered so that each bit of their index picks the
+        // minimum or maximum along an axis, x in the highest bit.
+        [4, 2, 1]
+            .into_iter()
+            .flat_map(|bit| {
+                (0..8)
+                    .filter(move |index| index & bit == 0)
+                    .map(move |index| (index, index | bit))
+            })
+            .map(|(start, end)| (corners[start], corners[end]))
+            .collect()
+    }
+
+    /// The size of the overlap between two boxes along each axis, an axis is
+    /// negative when the boxes are separated along it.
+    #[must_use]
+    pub fn overlap(&self, other: &Self) -> Vector {
+        Vector::new(
+            self.maximum.x.min(other.maximum.x)
+                - self.minimum.x.max(other.minimum.x),
+            self.maximum.y.min(other.maximum.y)
+                - self.minimum.y.max(other.minimum.y),
+            self.maximum.z.min(other.maximum.z)
+                - self.minimum.z.max(other.minimum.z),
+        )
+    }
+
+    /// Check if the box is non empty and finite in every direction.
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        [self.minimum, self.maximum].iter().all(|point| {
+            point.x.is_finite() && point.y.is_finite() && point.z.is_finite()
+        }) && self.minimum.x <= self.maximum.x
+            && self.minimum.y <= self.maximum.y
+            && self.minimum.z <= self.maximum.z
+    }
+
     #[must_use]
     pub fn split(&self) -> (Self, Self) {
         let dx = (self.maximum.x - self.minimum.x).abs();
@@ -181,22 +241,11 @@ impl From<Vec<Point>> for BoundingBox {
 
 impl Transformable for BoundingBox {
     fn apply(&self, transformation: &Transformation) -> Self {
-        let p1 = self.minimum.apply(transformation);
-        let p2 = Point::new(self.minimum.x, self.minimum.y, self.maximum.z)
-            .apply(transformation);
-        let p3 = Point::new(self.minimum.x, self.maximum.y, self.minimum.z)
-            .apply(transformation);
-        let p4 = Point::new(self.minimum.x, self.maximum.y, self.maximum.z)
-            .apply(transformation);
-        let p5 = Point::new(self.maximum.x, self.minimum.y, self.minimum.z)
-            .apply(transformation);
-        let p6 = Point::new(self.maximum.x, self.minimum.y, self.maximum.z)
-            .apply(transformation);
-        let p7 = Point::new(self.maximum.x, self.maximum.y, self.minimum.z)
-            .apply(transformation);
-        let p8 = self.maximum.apply(transformation);
-
-        Self::from(vec![p1, p2, p3, p4, p5, p6, p7, p8])
+        let mut corners = self.corners();
+
+        transformation.apply_many_in_place(&mut corners);
+
+        Self::from(corners)
     }
 }
 
@@ -219,7 +268,7 @@ mod tests {
     };
 
     use super::*;
-    use crate::math::{float::*, Angle, Vector};
+    use crate::math::{float::*, Angle};
 
     #[test]
     fn creating_a_bounding_box() {
@@ -232,6 +281,72 @@ mod tests {
         assert_approx_eq!(b.maximum, Point::new(5.1, INFINITY, 10.6));
     }
 
+    #[test]
+    fn checking_if_a_bounding_box_is_finite() {
+        assert!(BoundingBox::new(
+            Point::new(-1.0, 0.0, 2.0),
+            Point::new(1.0, 3.0, 2.0)
+        )
+        .is_finite());
+
+        assert!(!BoundingBox::new(
+            Point::new(-1.0, f64::NEG_INFINITY, 2.0),
+            Point::new(1.0, 3.0, 2.0)
+        )
+        .is_finite());
+
+        assert!(!BoundingBox::default().is_finite());
+    }
+
+    #[test]
+    fn finding_the_edges_of_a_bounding_box() {
+        let b = BoundingBox::new(
+            Point::new(-1.0, 0.0, 2.0),
+            Point::new(3.0, 1.0, 5.0),
+        );
+
+        let edges = b.edges();
+
+        assert_eq!(edges.len(), 12);
+
+        assert_approx_eq!(edges[0].0, Point::new(-1.0, 0.0, 2.0));
+        assert_approx_eq!(edges[0].1, Point::new(3.0, 0.0, 2.0));
+        assert_approx_eq!(edges[11].0, Point::new(3.0, 1.0, 2.0));
+        assert_approx_eq!(edges[11].1, Point::new(3.0, 1.0, 5.0));
+
+        for (index, (start, end)) in edges.into_iter().enumerate() {
+            let (axis, length) = match index / 4 {
+                0 => (Vector::x_axis(), 4.0),
+                1 => (Vector::y_axis(), 1.0),
+                _ => (Vector::z_axis(), 3.0),
+            };
+
+            assert_approx_eq!(end - start, axis * length);
+            assert!(b.contains(&start) && b.contains(&end));
+        }
+    }
+
+    #[test]
+    fn finding_the_overlap_of_two_bounding_boxes() {
+        let b = BoundingBox::new(Point::origin(), Point::new(2.0, 2.0, 2.0));
+
+        assert_approx_eq!(
+            b.overlap(&BoundingBox::new(
+                Point::new(1.0, -1.0, 2.0),
+                Point::new(4.0, 0.5, 3.0)
+            )),
+            Vector::new(1.0, 0.5, 0.0)
+        );
+
+        assert_approx_eq!(
+            b.overlap(&BoundingBox::new(
+                Point::new(3.0, 0.0, 0.0),
+                Point::new(4.0, 1.0, 1.0)
+            )),
+            Vector::new(-1.0, 1.0, 1.0)
+        );
+    }
+
     #[test]
     fn adding_points_to_a_bounding_box() {
         let mut b = BoundingBox::default();
@@ -1,11 +1,16 @@
 mod operation;
 
+use std::{collections::HashSet, sync::Arc};
+
 pub use self::operation::Operation;
-use super::{Bounded, BoundingBox, Includes, Updatable};
+use super::{
+    Bounded, BoundingBox, EstimateMemory, Includes, Shading, Summarise,
+    Updatable,
+};
 use crate::{
     intersection::List,
     math::{float::impl_approx_eq, Ray, Transformation},
-    Material, Object,
+    Material, Object, Summary,
 };
 
 /// A `Csg` is a constructive solid geometry object which performs `Operations`
@@ -19,6 +24,11 @@ pub struct Csg {
 }
 
 impl Csg {
+    #[must_use]
+    pub(super) fn children(&self) -> [&Object; 2] {
+        [&self.left, &self.right]
+    }
+
     #[must_use]
     pub fn new(operation: Operation, left: Object, right: Object) -> Self {
         let mut csg = Self {
@@ -85,18 +95,18 @@ impl Csg {
     }
 
     #[must_use]
-    pub fn intersect(&self, ray: &Ray) -> Option<List> {
-        if !self.bounding_box.is_intersected_by(ray) {
+    pub fn intersect(&self, ray: &Ray, bounded: bool) -> Option<List<'_>> {
+        if bounded && !self.bounding_box.is_intersected_by(ray) {
             return None;
         }
 
         let mut intersections = List::new();
 
-        if let Some(left) = &mut self.left.intersect(ray) {
+        if let Some(left) = &mut self.left.intersect_with(ray, bounded) {
             intersections.append(left);
         }
 
-        if let Some(right) = &mut self.right.intersect(ray) {
+        if let Some(right) = &mut self.right.intersect_with(ray, bounded) {
             intersections.append(right);
         };
 
@@ -112,6 +122,15 @@ impl Csg {
 
         self
     }
+
+    #[must_use]
+    pub fn at_time(&self, time: f64) -> Self {
+        Self::new(
+            self.operation,
+            self.left.at_time(time),
+            self.right.at_time(time),
+        )
+    }
 }
 
 impl Updatable for Csg {
@@ -120,15 +139,20 @@ impl Updatable for Csg {
         self.right.update_transformation(transformation);
     }
 
-    fn replace_material(&mut self, material: &Material) {
-        self.left.replace_material(material);
-        self.right.replace_material(material);
+    fn share_material(&mut self, material: &Arc<Material>) {
+        self.left.share_material(material);
+        self.right.share_material(material);
     }
 
     fn update_casts_shadow(&mut self, casts_shadow: bool) {
         self.left.update_casts_shadow(casts_shadow);
         self.right.update_casts_shadow(casts_shadow);
     }
+
+    fn update_shading(&mut self, shading: Shading) {
+        self.left.update_shading(shading);
+        self.right.update_shading(shading);
+    }
 }
 
 impl Bounded for Csg {
@@ -149,6 +173,26 @@ impl Includes for Csg {
     }
 }
 
+impl EstimateMemory for Csg {
+    fn estimate_memory(
+        &self,
+        materials: &mut HashSet<*const Material>,
+    ) -> usize {
+        size_of::<Object>()
+            + self.left.estimate_memory(materials)
+            + self.right.estimate_memory(materials)
+    }
+}
+
+impl Summarise for Csg {
+    fn summarise(&self, summary: &mut Summary) {
+        summary.add_csg();
+
+        self.left.summarise(summary);
+        self.right.summarise(summary);
+    }
+}
+
 impl_approx_eq!(&Csg { eq operation, ref left, ref right });
 
 #[cfg(test)]
@@ -296,6 +340,77 @@ mod tests {
         assert_approx_eq!(l[1].t, 6.5);
     }
 
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn a_ray_originating_inside_a_csg_difference() {
+        let c = Object::cube_builder()
+            .transformation(Transformation::new().scale(2.0, 2.0, 2.0))
+            .build();
+        let s = Object::sphere_builder().build();
+
+        let o = Object::new_csg(Operation::Difference, c.clone(), s.clone());
+
+        // The ray starts inside the sphere that is carved out of the cube, the
+        // intersections behind it are needed to know that we are in the space
+        // removed from the cube.
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        let l = o.intersect(&r).unwrap();
+
+        assert_eq!(l.len(), 4);
+
+        assert_approx_eq!(l[0].object, &c);
+        assert_approx_eq!(l[0].t, -2.0);
+        assert_approx_eq!(l[1].object, &s);
+        assert_approx_eq!(l[1].t, -1.0);
+        assert_approx_eq!(l[2].object, &s);
+        assert_approx_eq!(l[2].t, 1.0);
+        assert_approx_eq!(l[3].object, &c);
+        assert_approx_eq!(l[3].t, 2.0);
+
+        let h = l.hit().unwrap();
+
+        assert_approx_eq!(h.object, &s);
+        assert_approx_eq!(h.t, 1.0);
+
+        let c = h.prepare_computations(&r, &l);
+
+        assert!(c.inside);
+        assert_approx_eq!(c.normal, -Vector::z_axis());
+
+        // Starting inside the cube but outside the carved out sphere we hit the
+        // sphere's surface first and then see nothing else of the cube until
+        // its far side.
+        let r = Ray::new(Point::new(0.0, 0.0, -1.5), Vector::z_axis());
+
+        let l = o.intersect(&r).unwrap();
+
+        assert_eq!(l.len(), 4);
+
+        let h = l.hit().unwrap();
+
+        assert_approx_eq!(h.object, &s);
+        assert_approx_eq!(h.t, 0.5);
+
+        // With the sphere offset so it no longer contains the origin the ray
+        // starts in solid cube and the sphere does not affect the hit.
+        let o = Object::new_csg(
+            Operation::Difference,
+            Object::cube_builder()
+                .transformation(Transformation::new().scale(2.0, 2.0, 2.0))
+                .build(),
+            Object::sphere_builder()
+                .transformation(Transformation::new().translate(0.0, 0.0, -5.0))
+                .build(),
+        );
+
+        let l =
+            o.intersect(&Ray::new(Point::origin(), Vector::z_axis())).unwrap();
+
+        assert_eq!(l.len(), 2);
+        assert_approx_eq!(l.hit().unwrap().t, 2.0);
+    }
+
     #[test]
     fn a_csg_shape_has_a_bounding_box_that_contains_its_children() {
         let o = Object::new_csg(
@@ -326,7 +441,10 @@ mod tests {
         let Object::Csg(c) = o else { unreachable!() };
 
         assert!(c
-            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::y_axis()))
+            .intersect(
+                &Ray::new(Point::new(0.0, 0.0, -5.0), Vector::y_axis()),
+                true,
+            )
             .is_none());
     }
 
@@ -341,7 +459,10 @@ mod tests {
         let Object::Csg(c) = o else { unreachable!() };
 
         assert!(c
-            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()))
+            .intersect(
+                &Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()),
+                true,
+            )
             .is_some());
     }
 
@@ -0,0 +1,17 @@
+use std::collections::HashSet;
+
+use enum_dispatch::enum_dispatch;
+
+use crate::Material;
+
+/// A trait that estimates the number of bytes used by an `Object`, including
+/// the `Object` itself and any buffers it owns. Materials are shared so each
+/// one is only counted the first time it is added to `materials`.
+#[enum_dispatch(Object)]
+pub trait EstimateMemory {
+    #[must_use]
+    fn estimate_memory(
+        &self,
+        materials: &mut HashSet<*const Material>,
+    ) -> usize;
+}
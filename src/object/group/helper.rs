@@ -1,9 +1,10 @@
 use typed_builder::{Optional, TypedBuilder};
 
 use super::{BoundingBox, Group, Object, Updatable};
-use crate::{math::Transformation, Material};
+use crate::{math::Transformation, Light, Material};
 
-pub type GroupBuilder = HelperBuilder<((), (), (), (Vec<Object>,))>;
+pub type GroupBuilder =
+    HelperBuilder<((), (), (), (Vec<Object>,), (Vec<Light>,))>;
 
 /// This is a helper struct for constructing `Groups`, since we don't actually
 /// store the transformation or material for a group but do use them to "push
@@ -30,9 +31,17 @@ pub struct Helper {
     ))]
     #[builder(via_mutators)]
     objects: Vec<Object>,
+    /// Lights that move with the group, see `Group::lights`.
+    #[builder(mutators(
+        pub fn add_light(self, light: Light) {
+            self.lights.push(light);
+        }
+    ))]
+    #[builder(via_mutators)]
+    lights: Vec<Light>,
 }
 
-impl<T, M, S> HelperBuilder<(T, M, S, (Vec<Object>,))>
+impl<T, M, S> HelperBuilder<(T, M, S, (Vec<Object>,), (Vec<Light>,))>
 where
     T: Optional<Transformation>,
     M: Optional<Option<Material>>,
@@ -46,10 +55,19 @@ where
         let material = group_helper.material;
         let casts_shadow = group_helper.casts_shadow;
 
-        let mut group = Group {
-            objects: group_helper.objects,
-            bounding_box: BoundingBox::default(),
-        };
+        let mut objects = group_helper.objects;
+        let mut lights = group_helper.lights;
+
+        // Lights are only kept by the outermost group so the world can find
+        // them without searching every object.
+        for object in &mut objects {
+            if let Object::Group(group) = object {
+                lights.append(&mut group.lights);
+            }
+        }
+
+        let mut group =
+            Group { objects, lights, bounding_box: BoundingBox::default() };
 
         group.update_transformation(&transformation);
 
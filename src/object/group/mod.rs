@@ -1,22 +1,29 @@
 mod helper;
 
+use std::{collections::HashSet, sync::Arc};
+
 use float_cmp::{ApproxEq, F64Margin};
 
 #[allow(clippy::module_name_repetitions)]
 pub use self::helper::GroupBuilder;
 use self::helper::Helper;
-use super::{Bounded, BoundingBox, Includes, Object, Updatable};
+use super::{
+    Bounded, BoundingBox, EstimateMemory, Includes, Object, Shading, Summarise,
+    Updatable,
+};
 use crate::{
     intersection::List,
-    math::{Ray, Transformation},
-    Material,
+    math::{Ray, Transformable, Transformation},
+    Light, Material, Summary,
 };
 
 /// A `Group` is a collection of `Object`s that can be treated as a single
-/// entity.
+/// entity. A group can also hold lights which are moved along with its objects
+/// whenever the group is transformed.
 #[derive(Clone, Debug)]
 pub struct Group {
     pub(super) objects: Vec<Object>,
+    pub(super) lights: Vec<Light>,
     bounding_box: BoundingBox,
 }
 
@@ -26,15 +33,15 @@ impl Group {
     }
 
     #[must_use]
-    pub fn intersect(&self, ray: &Ray) -> Option<List> {
-        if !self.bounding_box.is_intersected_by(ray) {
+    pub fn intersect(&self, ray: &Ray, bounded: bool) -> Option<List<'_>> {
+        if bounded && !self.bounding_box.is_intersected_by(ray) {
             return None;
         }
 
         let mut list = List::new();
 
         for object in &self.objects {
-            if let Some(object_list) = object.intersect(ray) {
+            if let Some(object_list) = object.intersect_with(ray, bounded) {
                 list.extend(object_list.iter());
             };
         }
@@ -103,6 +110,23 @@ impl Group {
 
         group
     }
+
+    #[must_use]
+    pub fn at_time(&self, time: f64) -> Self {
+        let mut group = Self {
+            objects: self
+                .objects
+                .iter()
+                .map(|object| object.at_time(time))
+                .collect(),
+            lights: self.lights.clone(),
+            bounding_box: BoundingBox::default(),
+        };
+
+        group.bounding_box = group.bounding_box();
+
+        group
+    }
 }
 
 impl Updatable for Group {
@@ -111,12 +135,16 @@ impl Updatable for Group {
             object.update_transformation(transformation);
         }
 
+        for light in &mut self.lights {
+            *light = light.apply(transformation);
+        }
+
         self.bounding_box = self.bounding_box();
     }
 
-    fn replace_material(&mut self, material: &Material) {
+    fn share_material(&mut self, material: &Arc<Material>) {
         for object in &mut self.objects {
-            object.replace_material(material);
+            object.share_material(material);
         }
     }
 
@@ -125,6 +153,12 @@ impl Updatable for Group {
             object.update_casts_shadow(casts_shadow);
         }
     }
+
+    fn update_shading(&mut self, shading: Shading) {
+        for object in &mut self.objects {
+            object.update_shading(shading);
+        }
+    }
 }
 
 impl Bounded for Group {
@@ -152,16 +186,52 @@ impl Includes for Group {
     }
 }
 
+impl Summarise for Group {
+    fn summarise(&self, summary: &mut Summary) {
+        summary.add_group();
+
+        for object in &self.objects {
+            object.summarise(summary);
+        }
+    }
+}
+
+impl EstimateMemory for Group {
+    fn estimate_memory(
+        &self,
+        materials: &mut HashSet<*const Material>,
+    ) -> usize {
+        // Children are stored inline in the vector and count themselves, only
+        // the unused capacity needs adding.
+        size_of::<Object>()
+            + (self.objects.capacity() - self.objects.len())
+                * size_of::<Object>()
+            + self
+                .objects
+                .iter()
+                .map(|object| object.estimate_memory(materials))
+                .sum::<usize>()
+    }
+}
+
 impl ApproxEq for &Group {
     type Margin = F64Margin;
 
     fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
-        if self.objects.len() != other.objects.len() {
+        if self.objects.len() != other.objects.len()
+            || self.lights.len() != other.lights.len()
+        {
             return false;
         }
 
         let margin = margin.into();
 
+        for (lhs, rhs) in self.lights.iter().zip(&other.lights) {
+            if !lhs.approx_eq(*rhs, margin) {
+                return false;
+            }
+        }
+
         for (lhs, rhs) in self.objects.iter().zip(&other.objects) {
             if !lhs.approx_eq(rhs, margin) {
                 return false;
@@ -213,6 +283,50 @@ mod tests {
         assert_eq!(g.objects.len(), 2);
     }
 
+    #[test]
+    fn lights_in_a_group_are_transformed_with_it() {
+        let l = Light::new_point(Point::new(1.0, 0.0, 0.0), Colour::white());
+
+        let o = Object::group_builder()
+            .transformation(Transformation::new().translate(0.0, 2.0, 0.0))
+            .add_light(l)
+            .add_object(
+                Object::group_builder()
+                    .transformation(Transformation::new().scale(2.0, 2.0, 2.0))
+                    .add_light(l)
+                    .add_object(Object::test_builder().build())
+                    .build(),
+            )
+            .build();
+
+        let expected = [
+            Light::new_point(Point::new(1.0, 2.0, 0.0), Colour::white()),
+            Light::new_point(Point::new(2.0, 2.0, 0.0), Colour::white()),
+        ];
+
+        let check = |o: &Object| {
+            assert_eq!(o.lights().len(), 2);
+
+            for (light, expected) in o.lights().iter().zip(expected) {
+                assert_approx_eq!(*light, expected);
+            }
+        };
+
+        check(&o);
+
+        // Lights are held by the outermost group only.
+        let Object::Group(g) = &o else { unreachable!() };
+        assert!(g.objects[0].lights().is_empty());
+
+        check(&o.at_time(1.0));
+        check(&o.clone().divide(1));
+
+        let mut o = o;
+        o.remove_lights();
+
+        assert!(o.lights().is_empty());
+    }
+
     #[test]
     fn intersecting_an_empty_group() {
         let o = Object::group_builder().build();
@@ -439,7 +553,10 @@ mod tests {
         let Object::Group(g) = o else { unreachable!() };
 
         assert!(g
-            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::y_axis()))
+            .intersect(
+                &Ray::new(Point::new(0.0, 0.0, -5.0), Vector::y_axis()),
+                true,
+            )
             .is_none());
     }
 
@@ -452,7 +569,10 @@ mod tests {
         let Object::Group(g) = o else { unreachable!() };
 
         assert!(g
-            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()))
+            .intersect(
+                &Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()),
+                true,
+            )
             .is_some());
     }
 
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+use crate::math::Transformation;
+
+/// `Handedness` is the coordinate convention of an imported mesh, the renderer
+/// is right handed so left handed meshes are mirrored along z on import.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Handedness {
+    #[default]
+    Right,
+    Left,
+}
+
+impl Handedness {
+    /// The transformation that turns a mesh with this handedness to be right
+    /// handed. Mirroring reverses the winding of faces so this needs to be
+    /// undone separately to keep normals pointing out.
+    #[must_use]
+    pub fn transformation(self) -> Transformation {
+        match self {
+            Self::Right => Transformation::new(),
+            Self::Left => Transformation::new().scale(1.0, 1.0, -1.0),
+        }
+    }
+
+    /// Whether the winding of faces needs to be reversed after applying
+    /// `transformation`.
+    #[must_use]
+    pub const fn reverses_winding(self) -> bool {
+        matches!(self, Self::Left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::from_str;
+
+    use super::*;
+    use crate::math::{float::*, Point, Transformable, Vector};
+
+    #[test]
+    fn turning_a_handedness_to_right_handed() {
+        let p = Point::new(1.0, 2.0, 3.0);
+        let v = Vector::new(1.0, 2.0, 3.0);
+
+        assert_approx_eq!(p.apply(&Handedness::Right.transformation()), p);
+        assert_approx_eq!(
+            p.apply(&Handedness::Left.transformation()),
+            Point::new(1.0, 2.0, -3.0)
+        );
+        assert_approx_eq!(
+            v.apply(&Handedness::Left.transformation()),
+            Vector::new(1.0, 2.0, -3.0)
+        );
+
+        assert!(!Handedness::Right.reverses_winding());
+        assert!(Handedness::Left.reverses_winding());
+    }
+
+    #[test]
+    fn deserialize_handedness() {
+        assert_eq!(from_str::<Handedness>("right").unwrap(), Handedness::Right);
+        assert_eq!(from_str::<Handedness>("left").unwrap(), Handedness::Left);
+        assert_eq!(Handedness::default(), Handedness::Right);
+    }
+}
@@ -1,21 +1,34 @@
 mod bounding_box;
 mod csg;
+mod estimate_memory;
 mod group;
+mod handedness;
 mod includes;
 mod obj_parser;
+mod shading;
 mod shape;
 mod shapes;
+mod summarise;
+mod up_axis;
 mod updatable;
 
-use std::path::Path;
+use std::{collections::HashSet, path::Path, sync::Arc};
 
 use anyhow::Result;
 use enum_dispatch::enum_dispatch;
 use float_cmp::{ApproxEq, F64Margin};
 use paste::paste;
 
-use self::{
+pub use self::{
     bounding_box::{Bounded, BoundingBox},
+    csg::Operation,
+    handedness::Handedness,
+    shading::Shading,
+    shapes::{CustomShape, Intersectable},
+    up_axis::UpAxis,
+    updatable::Updatable,
+};
+use self::{
     csg::Csg,
     group::{Group, GroupBuilder},
     includes::Includes,
@@ -23,13 +36,17 @@ use self::{
     shape::{Shape, ShapeBuilder},
     shapes::Shapes,
 };
-pub use self::{csg::Operation, updatable::Updatable};
+pub(crate) use self::{estimate_memory::EstimateMemory, summarise::Summarise};
 use crate::{
     intersection::{Intersection, List},
-    math::{Point, Ray, Transformable, Transformation, Vector},
-    Material,
+    math::{Angle, Point, Ray, Transformable, Transformation, Vector},
+    Colour, Light, Material, Summary,
 };
 
+/// The thickness of the edges drawn by `Object::bounding_box_wireframe` as a
+/// fraction of the size of the outermost bounding box.
+const BOUNDING_BOX_WIREFRAME_THICKNESS: f64 = 0.002;
+
 /// An 'Object' represents some entity in the scene that can be rendered.
 #[derive(Clone, Debug)]
 #[enum_dispatch]
@@ -42,9 +59,9 @@ pub enum Object {
 macro_rules! add_builder_fn {
     ($shape:ident($($args:ident : $ty:ty $(,)?)*)) => {
         paste! {
-            pub fn [<$shape:lower _builder>]($($args:$ty,)*) -> ShapeBuilder {
+            pub fn [<$shape:snake _builder>]($($args:$ty,)*) -> ShapeBuilder {
                 Shape::builder().shape(
-                    Shapes::[<new_ $shape:lower>]($($args,)*)
+                    Shapes::[<new_ $shape:snake>]($($args,)*)
                 )
             }
         }
@@ -56,6 +73,7 @@ impl Object {
     add_builder_fn!(Cube());
     add_builder_fn!(Cylinder(minimum: f64, maximum: f64, closed: bool));
     add_builder_fn!(Plane());
+    add_builder_fn!(RoundedBox(radius: f64));
     add_builder_fn!(Sphere());
     #[cfg(test)]
     add_builder_fn!(Test());
@@ -68,6 +86,33 @@ impl Object {
         normal3: Vector,
     ));
 
+    /// Create a builder for a cone limited to a wedge of angles around the y
+    /// axis, see `Cone::with_wedge`.
+    pub fn cone_wedge_builder(
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+        from: Angle,
+        to: Angle,
+    ) -> ShapeBuilder {
+        Shape::builder()
+            .shape(Shapes::new_cone_wedge(minimum, maximum, closed, from, to))
+    }
+
+    /// Create a builder for a cylinder limited to a wedge of angles around the
+    /// y axis, see `Cylinder::with_wedge`.
+    pub fn cylinder_wedge_builder(
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+        from: Angle,
+        to: Angle,
+    ) -> ShapeBuilder {
+        Shape::builder().shape(Shapes::new_cylinder_wedge(
+            minimum, maximum, closed, from, to,
+        ))
+    }
+
     pub fn flat_triangle_builder(
         point1: Point,
         point2: Point,
@@ -77,6 +122,27 @@ impl Object {
             .shape(Shapes::new_flat_triangle(point1, point2, point3))
     }
 
+    /// Create a builder for a smooth triangle which flips its normals to match
+    /// the winding of its vertices, see `Triangle::with_fixed_winding`.
+    pub fn fixed_winding_triangle_builder(
+        point1: Point,
+        point2: Point,
+        point3: Point,
+        normal1: Vector,
+        normal2: Vector,
+        normal3: Vector,
+    ) -> ShapeBuilder {
+        Shape::builder().shape(Shapes::new_fixed_winding_triangle(
+            point1, point2, point3, normal1, normal2, normal3,
+        ))
+    }
+
+    /// Create a builder for a shape defined outside of this crate, see
+    /// `CustomShape` for details.
+    pub fn custom_builder<S: CustomShape + 'static>(shape: S) -> ShapeBuilder {
+        Shape::builder().shape(Shapes::new_custom(shape))
+    }
+
     pub fn group_builder() -> GroupBuilder {
         Group::builder()
     }
@@ -86,6 +152,17 @@ impl Object {
         Csg::new(operation, left, right).into()
     }
 
+    /// Combine several objects with the same operation by nesting CSGs, each
+    /// object is combined with the result of those before it. So a difference
+    /// subtracts every other object from the first. A single object is returned
+    /// as is and there is no result without any objects.
+    #[must_use]
+    pub fn csg_tree(operation: Operation, objects: Vec<Self>) -> Option<Self> {
+        objects
+            .into_iter()
+            .reduce(|left, right| Self::new_csg(operation, left, right))
+    }
+
     /// Parse a given OBJ file and return a partially formed `Group` containing
     /// all the triangles from the OBJ file.
     ///
@@ -96,11 +173,44 @@ impl Object {
         Ok(ObjParser::parse(filename)?.into_group())
     }
 
+    /// Parse a given OBJ file as `from_file` does, rotating the triangles so
+    /// that `up_axis` in the file points along y and mirroring them if the
+    /// file has a different `handedness`.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if unable to read or parse the file.
+    pub fn from_file_with<P: AsRef<Path>>(
+        filename: P,
+        up_axis: UpAxis,
+        handedness: Handedness,
+    ) -> Result<GroupBuilder> {
+        Ok(ObjParser::parse_with(filename, up_axis, handedness)?.into_group())
+    }
+
     #[must_use]
     pub fn intersect(&self, ray: &Ray) -> Option<List> {
+        self.intersect_with(ray, true)
+    }
+
+    /// Intersect the ray with the object as `intersect` does. When
+    /// `bounded` is false groups and CSGs skip their bounding box checks and
+    /// test every child, which helps to tell whether a missing intersection is
+    /// caused by a wrong bounding box or by the shape itself.
+    #[must_use]
+    pub fn intersect_with(&self, ray: &Ray, bounded: bool) -> Option<List<'_>> {
         match self {
-            Self::Csg(csg) => csg.intersect(ray),
-            Self::Group(group) => group.intersect(ray),
+            Self::Csg(csg) => {
+                let mut list = csg.intersect(ray, bounded)?;
+
+                // Nested CSGs are intersected first so the outermost wins.
+                for intersection in list.iter_mut() {
+                    intersection.csg = Some(self);
+                }
+
+                Some(list)
+            }
+            Self::Group(group) => group.intersect(ray, bounded),
             Self::Shape(shape) => shape.intersect(ray, self),
         }
     }
@@ -125,11 +235,142 @@ impl Object {
         }
     }
 
+    /// The transformation of a shape, including any from the groups it is in.
+    #[must_use]
+    pub fn transformation(&self) -> Transformation {
+        match self {
+            Self::Csg(_) | Self::Group(_) => unreachable!(),
+            Self::Shape(shape) => shape.transformation,
+        }
+    }
+
+    /// The lights held by a group, these are already in world space. Lights in
+    /// nested groups are held by the outermost group and lights inside a CSG
+    /// are ignored.
+    #[must_use]
+    pub fn lights(&self) -> &[Light] {
+        match self {
+            Self::Group(group) => &group.lights,
+            Self::Csg(_) | Self::Shape(_) => &[],
+        }
+    }
+
+    /// Remove any lights held by a group.
+    pub fn remove_lights(&mut self) {
+        if let Self::Group(group) = self {
+            group.lights.clear();
+        }
+    }
+
+    /// Find every shape in the object, descending into groups and CSGs.
+    #[must_use]
+    pub fn shapes(&self) -> Vec<&Self> {
+        match self {
+            Self::Csg(csg) => {
+                csg.children().into_iter().flat_map(Self::shapes).collect()
+            }
+            Self::Group(group) => {
+                group.objects.iter().flat_map(Self::shapes).collect()
+            }
+            Self::Shape(_) => vec![self],
+        }
+    }
+
+    /// Give a triangle a colour at each vertex, see
+    /// `Shapes::with_vertex_colours`.
+    #[must_use]
+    pub fn with_vertex_colours(self, colours: [Colour; 3]) -> Self {
+        match self {
+            Self::Shape(mut shape) => {
+                shape.shape = shape.shape.with_vertex_colours(colours);
+
+                Self::Shape(shape)
+            }
+            Self::Csg(_) | Self::Group(_) => self,
+        }
+    }
+
+    /// Give a triangle texture coordinates at each vertex, see
+    /// `Shapes::with_uvs`.
+    #[must_use]
+    pub fn with_uvs(self, uvs: [(f64, f64); 3]) -> Self {
+        match self {
+            Self::Shape(mut shape) => {
+                shape.shape = shape.shape.with_uvs(uvs);
+
+                Self::Shape(shape)
+            }
+            Self::Csg(_) | Self::Group(_) => self,
+        }
+    }
+
+    /// Cap each end of a cone or cylinder separately, see `Shapes::with_caps`.
+    #[must_use]
+    pub fn with_caps(self, minimum: bool, maximum: bool) -> Self {
+        match self {
+            Self::Shape(mut shape) => {
+                shape.shape = shape.shape.with_caps(minimum, maximum);
+
+                Self::Shape(shape)
+            }
+            Self::Csg(_) | Self::Group(_) => self,
+        }
+    }
+
+    /// The colour at an intersection with a triangle with vertex colours, this
+    /// replaces the colour from the material's pattern when shading.
+    #[must_use]
+    pub fn vertex_colour(&self, intersection: &Intersection) -> Option<Colour> {
+        match self {
+            Self::Shape(shape) => shape.shape.vertex_colour(intersection.u_v),
+            Self::Csg(_) | Self::Group(_) => None,
+        }
+    }
+
+    /// The u and v values at an intersection, these are only worked out when
+    /// asked for as nothing but the uv render mode uses them for spheres,
+    /// cylinders and planes. See `Shapes::u_v_at`.
+    #[must_use]
+    pub fn u_v_at(
+        &self,
+        point: &Point,
+        intersection: &Intersection,
+    ) -> Option<(f64, f64)> {
+        match self {
+            Self::Shape(shape) => intersection
+                .u_v
+                .or_else(|| shape.shape.u_v_at(&shape.to_object_space(point))),
+            Self::Csg(_) | Self::Group(_) => None,
+        }
+    }
+
+    /// How close an intersection with a triangle is to its nearest edge, see
+    /// `Shapes::edge_distance`.
+    #[must_use]
+    pub fn edge_distance(&self, intersection: &Intersection) -> Option<f64> {
+        match self {
+            Self::Shape(shape) => shape.shape.edge_distance(intersection.u_v),
+            Self::Csg(_) | Self::Group(_) => None,
+        }
+    }
+
+    /// The plane a flat shape lies in as a point and normal in world space,
+    /// see `Shape::flat_surface`.
+    #[must_use]
+    pub fn flat_surface(&self) -> Option<(Point, Vector)> {
+        match self {
+            Self::Csg(_) | Self::Group(_) => unreachable!(),
+            Self::Shape(shape) => shape.flat_surface(),
+        }
+    }
+
     #[must_use]
     pub fn casts_shadow(&self) -> bool {
         match self {
             Self::Csg(_) | Self::Group(_) => unreachable!(),
-            Self::Shape(shape) => shape.casts_shadow,
+            Self::Shape(shape) => {
+                shape.casts_shadow && !shape.material.shadow_catcher
+            }
         }
     }
 
@@ -141,6 +382,84 @@ impl Object {
         }
     }
 
+    /// Build a group of thin boxes along the edges of the bounding box of the
+    /// object and of every group and CSG within it, showing how the object
+    /// has been divided when added to a world and rendered. Infinite bounding
+    /// boxes are skipped and the boxes do not cast shadows.
+    #[must_use]
+    pub fn bounding_box_wireframe(&self) -> Self {
+        let mut bounding_boxes = Vec::new();
+        self.collect_bounding_boxes(&mut bounding_boxes);
+
+        // The edges are the same thickness at every level so that boxes deep
+        // in the hierarchy are still visible.
+        let thickness = bounding_boxes.first().map_or(0.0, |bounding_box| {
+            let corners = bounding_box.corners();
+
+            (corners[7] - corners[0]).magnitude()
+                * BOUNDING_BOX_WIREFRAME_THICKNESS
+        });
+
+        let edge = |(start, end): (Point, Point)| {
+            let half = (end - start) / 2.0;
+            let centre = start + half;
+
+            Self::cube_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(
+                            half.x.abs() + thickness,
+                            half.y.abs() + thickness,
+                            half.z.abs() + thickness,
+                        )
+                        .translate(centre.x, centre.y, centre.z),
+                )
+                .build()
+        };
+
+        Self::group_builder()
+            .set_objects(
+                bounding_boxes
+                    .into_iter()
+                    .map(|bounding_box| {
+                        Self::group_builder()
+                            .set_objects(
+                                bounding_box
+                                    .edges()
+                                    .into_iter()
+                                    .map(edge)
+                                    .collect(),
+                            )
+                            .build()
+                    })
+                    .collect(),
+            )
+            .casts_shadow(false)
+            .build()
+    }
+
+    /// Add the finite bounding boxes of the object and every group and CSG
+    /// within it to `bounding_boxes`, parents before their children.
+    fn collect_bounding_boxes(&self, bounding_boxes: &mut Vec<BoundingBox>) {
+        let bounding_box = self.bounding_box();
+
+        if bounding_box.is_finite() {
+            bounding_boxes.push(bounding_box);
+        }
+
+        let children = match self {
+            Self::Csg(csg) => csg.children().to_vec(),
+            Self::Group(group) => group.objects.iter().collect(),
+            Self::Shape(_) => return,
+        };
+
+        for child in children {
+            if !matches!(child, Self::Shape(_)) {
+                child.collect_bounding_boxes(bounding_boxes);
+            }
+        }
+    }
+
     #[must_use]
     pub fn divide(self, threshold: u32) -> Self {
         match self {
@@ -149,6 +468,17 @@ impl Object {
             Self::Shape(_) => self,
         }
     }
+
+    /// Pose the object at `time`, moving every shape with keyframes to its
+    /// interpolated transformation and updating bounding boxes to match.
+    #[must_use]
+    pub fn at_time(&self, time: f64) -> Self {
+        match self {
+            Self::Csg(csg) => Self::Csg(csg.at_time(time)),
+            Self::Group(group) => Self::Group(group.at_time(time)),
+            Self::Shape(shape) => Self::Shape(shape.at_time(time)),
+        }
+    }
 }
 
 impl ApproxEq for &Object {
@@ -182,6 +512,162 @@ mod tests {
             .build();
     }
 
+    #[test]
+    fn building_a_bounding_box_wireframe() {
+        let boxes = |object: &Object| {
+            let Object::Group(wireframe) = object.bounding_box_wireframe()
+            else {
+                panic!("Expected a group");
+            };
+
+            wireframe.objects
+        };
+
+        let spheres = (0..8)
+            .map(|x| {
+                Object::sphere_builder()
+                    .transformation(Transformation::new().translate(
+                        f64::from(x) * 3.0,
+                        0.0,
+                        0.0,
+                    ))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let o = Object::group_builder().set_objects(spheres.clone()).build();
+        let undivided = boxes(&o);
+
+        assert_eq!(undivided.len(), 1);
+
+        let thickness = (23.0_f64.powi(2) + 8.0).sqrt() * 0.002;
+        assert_approx_eq!(
+            undivided[0].bounding_box(),
+            BoundingBox::new(
+                Point::new(
+                    -1.0 - thickness,
+                    -1.0 - thickness,
+                    -1.0 - thickness
+                ),
+                Point::new(22.0 + thickness, 1.0 + thickness, 1.0 + thickness)
+            ),
+            epsilon = 0.000_01
+        );
+
+        let Object::Group(edges) = &undivided[0] else {
+            panic!("Expected a group");
+        };
+        assert_eq!(edges.objects.len(), 12);
+        assert!(!edges.objects[0].casts_shadow());
+
+        assert!(boxes(&o.divide(2)).len() > undivided.len());
+
+        assert_eq!(boxes(&Object::sphere_builder().build()).len(), 1);
+
+        // Infinite bounding boxes can not be drawn.
+        let o = Object::group_builder()
+            .set_objects(spheres)
+            .add_object(Object::plane_builder().build())
+            .build();
+
+        assert!(boxes(&o).is_empty());
+    }
+
+    #[test]
+    fn carving_a_sphere_with_a_csg_tree() {
+        let cube = |x, z| {
+            Object::cube_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(0.5, 0.5, 0.5)
+                        .translate(x, 0.0, z),
+                )
+                .build()
+        };
+
+        let o = Object::csg_tree(
+            Operation::Difference,
+            vec![
+                Object::sphere_builder().build(),
+                cube(0.0, -1.0),
+                cube(1.0, 0.0),
+                cube(0.0, 1.0),
+            ],
+        )
+        .unwrap();
+
+        let Object::Csg(csg) = &o else { unreachable!() };
+        assert!(matches!(csg.children()[0], Object::Csg(_)));
+        assert!(matches!(csg.children()[1], Object::Shape(_)));
+
+        let test = |origin, direction, expected: &[f64]| {
+            let l = o.intersect(&Ray::new(origin, direction));
+
+            let t = l.map_or_else(Vec::new, |l| {
+                l.iter().map(|i| i.t).collect::<Vec<_>>()
+            });
+
+            assert_eq!(t.len(), expected.len());
+
+            for (t, expected) in t.iter().zip(expected) {
+                assert_approx_eq!(*t, *expected);
+            }
+        };
+
+        // Through the two cubes carved from the front and back.
+        test(Point::new(0.0, 0.0, -5.0), Vector::z_axis(), &[4.5, 5.5]);
+        // Into the sphere and out through the cube carved from the side.
+        test(Point::new(-5.0, 0.0, 0.0), Vector::x_axis(), &[4.0, 5.5]);
+        // Missing every cube.
+        test(Point::new(0.0, -5.0, 0.0), Vector::y_axis(), &[4.0, 6.0]);
+        // Only through a cube where it is outside the sphere.
+        test(Point::new(1.2, -5.0, 0.0), Vector::y_axis(), &[]);
+
+        assert!(Object::csg_tree(Operation::Union, Vec::new()).is_none());
+
+        let s = Object::sphere_builder().build();
+
+        assert_approx_eq!(
+            Object::csg_tree(Operation::Union, vec![s.clone()]).unwrap(),
+            &s
+        );
+    }
+
+    #[test]
+    fn finding_the_shapes_of_an_object() {
+        let s = Object::sphere_builder().build();
+
+        assert_eq!(s.shapes().len(), 1);
+        assert_approx_eq!(s.shapes()[0], &s);
+        assert_approx_eq!(s.transformation(), Transformation::new());
+
+        let o = Object::group_builder()
+            .transformation(Transformation::new().translate(1.0, 0.0, 0.0))
+            .add_object(Object::new_csg(
+                Operation::Union,
+                Object::cube_builder().build(),
+                Object::group_builder()
+                    .add_object(Object::plane_builder().build())
+                    .add_object(
+                        Object::cylinder_builder(0.0, 1.0, true).build(),
+                    )
+                    .build(),
+            ))
+            .add_object(s)
+            .build();
+
+        let s = o.shapes();
+
+        assert_eq!(s.len(), 4);
+
+        for shape in s {
+            assert_approx_eq!(
+                shape.transformation(),
+                Transformation::new().translate(1.0, 0.0, 0.0)
+            );
+        }
+    }
+
     #[test]
     fn comparing_objects() {
         let o1 = Object::group_builder().build();
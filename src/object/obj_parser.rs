@@ -5,46 +5,219 @@ use std::{
     path::Path,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use rayon::prelude::*;
 
-use super::{group::GroupBuilder, Object};
-use crate::math::{Point, Vector};
+use super::{group::GroupBuilder, Handedness, Object, UpAxis};
+use crate::{
+    math::{Point, Transformable, Vector},
+    Colour,
+};
 
 #[derive(Debug)]
 pub struct ObjParser {
     pub vertices: Vec<Point>,
+    /// The colour given after each vertex, if any.
+    pub colours: Vec<Option<Colour>>,
     pub normals: Vec<Vector>,
+    pub uvs: Vec<(f64, f64)>,
     pub groups: Vec<Object>,
     pub ignored: u32,
 }
 
+/// A `Face` holds the indices of a single triangle, triangles are only built
+/// once the whole file is read so that normals can be calculated for smoothing
+/// groups.
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    vertices: [usize; 3],
+    uvs: Option<[usize; 3]>,
+    normals: Option<[usize; 3]>,
+    smoothing: Option<u32>,
+}
+
+/// A `Corner` holds the indices given for a single vertex of a face.
+#[derive(Clone, Copy, Debug)]
+struct Corner {
+    vertex: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// An `ObjObject` holds the faces for a single `o` statement in the file,
+/// along with any named `g` groups inside it, in the order they appear. The
+/// faces before the first `o` statement are held in an unnamed object.
+#[derive(Debug, Default)]
+struct ObjObject {
+    name: Option<String>,
+    triangles: Vec<Face>,
+    groups: Vec<(String, Vec<Face>)>,
+}
+
+impl ObjObject {
+    fn current(&mut self) -> &mut Vec<Face> {
+        self.groups
+            .last_mut()
+            .map_or(&mut self.triangles, |(_, triangles)| triangles)
+    }
+
+    fn reverse_winding(&mut self) {
+        let faces = self
+            .triangles
+            .iter_mut()
+            .chain(self.groups.iter_mut().flat_map(|(_, faces)| faces));
+
+        for face in faces {
+            face.vertices.swap(1, 2);
+
+            if let Some(uvs) = &mut face.uvs {
+                uvs.swap(1, 2);
+            }
+
+            if let Some(normals) = &mut face.normals {
+                normals.swap(1, 2);
+            }
+        }
+    }
+
+    fn add_group(&mut self, name: &str) -> Result<()> {
+        if self.groups.iter().any(|(group, _)| group == name) {
+            bail!("Group {name} is repeated.");
+        }
+
+        self.groups.push((String::from(name), Vec::new()));
+
+        Ok(())
+    }
+
+    fn faces(&self) -> impl Iterator<Item = &Face> {
+        self.triangles
+            .iter()
+            .chain(self.groups.iter().flat_map(|(_, triangles)| triangles))
+    }
+
+    fn into_groups<F: Fn(&[Face]) -> Vec<Object>>(
+        self,
+        build: &F,
+    ) -> Vec<Object> {
+        [self.triangles]
+            .into_iter()
+            .chain(self.groups.into_iter().map(|(_, triangles)| triangles))
+            .filter(|triangles| !triangles.is_empty())
+            .map(|triangles| {
+                Object::group_builder().set_objects(build(&triangles)).build()
+            })
+            .collect()
+    }
+
+    fn into_objects<F: Fn(&[Face]) -> Vec<Object>>(
+        self,
+        build: &F,
+    ) -> Vec<Object> {
+        if self.name.is_none() {
+            return self.into_groups(build);
+        }
+
+        let mut objects = Vec::new();
+
+        for (_, triangles) in self.groups {
+            if !triangles.is_empty() {
+                objects.push(
+                    Object::group_builder()
+                        .set_objects(build(&triangles))
+                        .build(),
+                );
+            }
+        }
+
+        let objects = build(&self.triangles)
+            .into_iter()
+            .chain(objects)
+            .collect::<Vec<_>>();
+
+        if objects.is_empty() {
+            return Vec::new();
+        }
+
+        vec![Object::group_builder().set_objects(objects).build()]
+    }
+}
+
 impl ObjParser {
     #[must_use]
     fn new() -> Self {
         Self {
             vertices: Vec::new(),
+            colours: Vec::new(),
             normals: Vec::new(),
+            uvs: Vec::new(),
             groups: Vec::new(),
             ignored: 0,
         }
     }
 
-    /// Parse a given OBJ file.
+    /// Parse a given OBJ file. Each `o` statement becomes a child group and
+    /// any `g` statements within it become groups nested inside that; `g`
+    /// statements before the first `o` become top level groups. Groups are kept
+    /// in the order they appear in the file.
+    ///
+    /// Vertices may be followed by a red, green and blue colour, faces where
+    /// every vertex has a colour blend them across the triangle, see
+    /// `Triangle::with_colours`.
+    ///
+    /// Faces where every vertex has texture coordinates (`vt`) give the
+    /// triangle a tangent space for normal maps, see `Triangle::with_uvs`.
+    ///
+    /// Faces that supply vertex normals always use them. Faces without normals
+    /// that are in a smoothing group (`s`) are given normals averaged from all
+    /// the faces sharing each vertex in that group, weighted by area, and the
+    /// remaining faces are flat.
+    ///
+    /// Triangles are built in parallel once the whole file has been read.
     ///
     /// # Errors
     ///
     /// Will return errors if unable to read or parse the file.
     pub fn parse<P: AsRef<Path>>(filename: P) -> Result<Self> {
-        let file = File::open(filename)?;
+        Self::parse_with(filename, UpAxis::default(), Handedness::default())
+    }
+
+    /// Parse a given OBJ file as `parse` does, rotating the vertices and
+    /// normals so that `up_axis` points along y. Left handed files are then
+    /// mirrored along z with the winding of every face reversed so that
+    /// normals still point out of the mesh.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if unable to read or parse the file.
+    pub fn parse_with<P: AsRef<Path>>(
+        filename: P,
+        up_axis: UpAxis,
+        handedness: Handedness,
+    ) -> Result<Self> {
+        Self::parse_all(filename, up_axis, handedness, true)
+    }
+
+    /// Parse a given OBJ file as `parse_with` does, optionally building the
+    /// triangles one at a time.
+    fn parse_all<P: AsRef<Path>>(
+        filename: P,
+        up_axis: UpAxis,
+        handedness: Handedness,
+        parallel: bool,
+    ) -> Result<Self> {
+        let filename = filename.as_ref();
+
+        let file = File::open(filename).with_context(|| {
+            format!("Unable to open OBJ file '{}'", filename.display())
+        })?;
 
         let buffer = BufReader::new(file).lines();
 
         let mut parser = Self::new();
 
-        let mut groups = HashMap::from([(String::from("default"), Vec::new())]);
-
-        let mut current_group =
-            groups.get_mut("default").unwrap_or_else(|| unreachable!());
+        let mut objects = vec![ObjObject::default()];
+        let mut smoothing = None;
 
         for line in buffer {
             let line = line?;
@@ -54,26 +227,85 @@ impl ObjParser {
                 parser.parse_vertex(line)?;
             } else if line.starts_with("vn ") {
                 parser.parse_normal(line)?;
+            } else if line.starts_with("vt ") {
+                parser.parse_uv(line)?;
             } else if line.starts_with("f ") {
-                parser.parse_face(line, current_group)?;
+                let object =
+                    objects.last_mut().unwrap_or_else(|| unreachable!());
+
+                Self::parse_face(
+                    line,
+                    &parser.vertices,
+                    parser.uvs.len(),
+                    smoothing,
+                    object.current(),
+                )?;
+            } else if line.starts_with("s ") {
+                smoothing = Self::parse_smoothing(line)?;
             } else if line.starts_with("g ") {
-                current_group = Self::parse_group(line, &mut groups)?;
+                objects
+                    .last_mut()
+                    .unwrap_or_else(|| unreachable!())
+                    .add_group(line[1..].trim())?;
+            } else if line.starts_with("o ") {
+                let name = line[1..].trim();
+
+                if objects
+                    .iter()
+                    .any(|object| object.name.as_deref() == Some(name))
+                {
+                    bail!("Object {name} is repeated.");
+                }
+
+                objects.push(ObjObject {
+                    name: Some(String::from(name)),
+                    ..Default::default()
+                });
             } else {
                 parser.ignored += 1;
             }
         }
 
-        let mut groups = groups.into_iter().collect::<Vec<_>>();
-        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        if up_axis != UpAxis::Y || handedness != Handedness::Right {
+            let transformation =
+                up_axis.transformation().extend(&handedness.transformation());
 
-        for (_, triangles) in groups {
-            if !triangles.is_empty() {
-                parser.groups.push(
-                    Object::group_builder().set_objects(triangles).build(),
-                );
+            for vertex in &mut parser.vertices {
+                *vertex = vertex.apply(&transformation);
+            }
+
+            for normal in &mut parser.normals {
+                *normal = normal.apply(&transformation);
+            }
+        }
+
+        if handedness.reverses_winding() {
+            for object in &mut objects {
+                object.reverse_winding();
             }
         }
 
+        let smoothed = parser.smoothed_normals(&objects);
+        let build_triangle =
+            |face: &Face| parser.build_triangle(face, &smoothed);
+
+        // Faces are independent once every vertex and normal is known, the
+        // order of the triangles is kept either way.
+        let build = |faces: &[Face]| -> Vec<Object> {
+            if parallel {
+                faces.par_iter().map(build_triangle).collect()
+            } else {
+                faces.iter().map(build_triangle).collect()
+            }
+        };
+
+        let groups = objects
+            .into_iter()
+            .flat_map(|object| object.into_objects(&build))
+            .collect();
+
+        parser.groups = groups;
+
         Ok(parser)
     }
 
@@ -99,10 +331,11 @@ Found {}.",
     fn parse_vertex(&mut self, line: &str) -> Result<()> {
         let items = Self::split(line);
 
-        if items.len() != 4 {
+        if items.len() != 4 && items.len() != 7 {
             bail!(
                 "\
-Expected 'v' followed by 3 space separated numbers for a vertex.
+Expected 'v' followed by 3 space separated numbers for a vertex and optionally
+3 more for its colour.
 Found {} items.",
                 items.len()
             );
@@ -114,6 +347,18 @@ Found {} items.",
 
         self.vertices.push(Point::new(x, y, z));
 
+        let colour = if items.len() == 7 {
+            Some(Colour::new(
+                items[4].parse()?,
+                items[5].parse()?,
+                items[6].parse()?,
+            ))
+        } else {
+            None
+        };
+
+        self.colours.push(colour);
+
         Ok(())
     }
 
@@ -138,10 +383,30 @@ Found {} items.",
         Ok(())
     }
 
+    fn parse_uv(&mut self, line: &str) -> Result<()> {
+        let items = Self::split(line);
+
+        if items.len() != 3 && items.len() != 4 {
+            bail!(
+                "\
+Expected 'vt' followed by 2 space separated numbers for texture coordinates and
+optionally a third which is ignored.
+Found {} items.",
+                items.len()
+            );
+        }
+
+        self.uvs.push((items[1].parse()?, items[2].parse()?));
+
+        Ok(())
+    }
+
     fn parse_face(
-        &mut self,
         line: &str,
-        group: &mut Vec<Object>,
+        vertices: &[Point],
+        uv_count: usize,
+        smoothing: Option<u32>,
+        group: &mut Vec<Face>,
     ) -> Result<()> {
         let items = Self::split(line);
 
@@ -154,77 +419,274 @@ Found {} items.",
             );
         }
 
-        let get_vertex_normal = |item: &str| -> Result<(usize, Option<usize>)> {
+        let get_corner = |item: &str| -> Result<Corner> {
             let values = Self::split_face(item)?;
 
             let vertex = values[0].parse::<u32>()? - 1;
-            let normal = if values.len() == 1 {
-                None
+            let (uv, normal) = if values.len() == 1 {
+                (None, None)
             } else {
-                Some((values[2].parse::<u32>()? - 1) as usize)
+                // Texture coordinates that were not given are ignored, as
+                // they are for files without any.
+                let uv = values[1]
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|uv| (1..=uv_count).contains(uv))
+                    .map(|uv| uv - 1);
+
+                (uv, Some((values[2].parse::<u32>()? - 1) as usize))
             };
 
-            Ok((vertex as usize, normal))
+            Ok(Corner { vertex: vertex as usize, uv, normal })
         };
 
-        let (vertex1, normal1) = get_vertex_normal(items[1])?;
+        let corners = items[1..]
+            .iter()
+            .map(|item| get_corner(item))
+            .collect::<Result<Vec<_>>>()?;
 
-        for index in 2..(items.len() - 1) {
-            let (vertex2, normal2) = get_vertex_normal(items[index])?;
-            let (vertex3, normal3) = get_vertex_normal(items[index + 1])?;
+        let is_smooth = corners[0].normal.is_some();
 
-            let is_smooth = if normal1.is_none() {
-                false
-            } else {
-                if normal2.is_none() || normal3.is_none() {
-                    bail!(
-                        "\
+        if is_smooth && corners.iter().any(|corner| corner.normal.is_none()) {
+            bail!(
+                "\
 If one vertex normal is specified, all faces must also provide vertex normals."
-                    )
-                }
+            )
+        }
 
-                true
-            };
+        let polygon =
+            corners.iter().map(|corner| corner.vertex).collect::<Vec<_>>();
 
-            if is_smooth {
-                group.push(
-                    Object::triangle_builder(
-                        self.vertices[vertex1],
-                        self.vertices[vertex2],
-                        self.vertices[vertex3],
-                        // We have already checked these are all Some().
-                        self.normals[normal1.unwrap()],
-                        self.normals[normal2.unwrap()],
-                        self.normals[normal3.unwrap()],
-                    )
-                    .build(),
-                );
+        for triangle in Self::triangulate(vertices, &polygon) {
+            let face_normals = if is_smooth {
+                // We have already checked these are all Some().
+                Some(triangle.map(|index| corners[index].normal.unwrap()))
             } else {
-                group.push(
-                    Object::flat_triangle_builder(
-                        self.vertices[vertex1],
-                        self.vertices[vertex2],
-                        self.vertices[vertex3],
-                    )
-                    .build(),
-                );
-            }
+                None
+            };
+
+            let uvs = match triangle.map(|index| corners[index].uv) {
+                [Some(uv1), Some(uv2), Some(uv3)] => Some([uv1, uv2, uv3]),
+                _ => None,
+            };
+
+            group.push(Face {
+                vertices: triangle.map(|index| corners[index].vertex),
+                uvs,
+                normals: face_normals,
+                smoothing,
+            });
         }
 
         Ok(())
     }
 
-    fn parse_group<'a>(
-        line: &str,
-        groups: &'a mut HashMap<String, Vec<Object>>,
-    ) -> Result<&'a mut Vec<Object>> {
-        let group_name = line[1..].trim();
+    /// Split a polygon into triangles, returned as indices into `polygon`.
+    /// Convex polygons are split into a fan around the first vertex, concave
+    /// polygons are split by clipping ears after projecting onto the plane the
+    /// polygon mostly lies in. Triangles keep the winding of the polygon.
+    #[must_use]
+    fn triangulate(vertices: &[Point], polygon: &[usize]) -> Vec<[usize; 3]> {
+        let length = polygon.len();
+
+        let fan = |remaining: &[usize]| {
+            (1..remaining.len() - 1)
+                .map(|index| {
+                    [remaining[0], remaining[index], remaining[index + 1]]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let all = (0..length).collect::<Vec<_>>();
+
+        // Faces with unknown vertices are reported when the triangles are
+        // built.
+        if length == 3 || polygon.iter().any(|vertex| *vertex >= vertices.len())
+        {
+            return fan(&all);
+        }
+
+        let points = polygon.iter().map(|vertex| vertices[*vertex]);
+
+        // Newell's method gives a normal that is robust for non planar and
+        // concave polygons.
+        let mut normal = Vector::new(0.0, 0.0, 0.0);
+        for (current, next) in
+            points.clone().zip(points.clone().cycle().skip(1))
+        {
+            normal.x += (current.y - next.y) * (current.z + next.z);
+            normal.y += (current.z - next.z) * (current.x + next.x);
+            normal.z += (current.x - next.x) * (current.y + next.y);
+        }
+
+        let (x, y, z) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+        let (projected, sign) = if x >= y && x >= z {
+            (points.map(|p| (p.y, p.z)).collect::<Vec<_>>(), normal.x)
+        } else if y >= z {
+            (points.map(|p| (p.z, p.x)).collect(), normal.y)
+        } else {
+            (points.map(|p| (p.x, p.y)).collect(), normal.z)
+        };
+
+        let cross = |a: usize, b: usize, c: usize| {
+            let (origin, first, second) =
+                (projected[a], projected[b], projected[c]);
+
+            ((first.0 - origin.0) * (second.1 - origin.1)
+                - (first.1 - origin.1) * (second.0 - origin.0))
+                * sign.signum()
+        };
+
+        let is_convex = (0..length).all(|index| {
+            cross((index + length - 1) % length, index, (index + 1) % length)
+                >= 0.0
+        });
+
+        if is_convex {
+            return fan(&all);
+        }
+
+        let mut remaining = all;
+        let mut triangles = Vec::new();
+
+        while remaining.len() > 3 {
+            let count = remaining.len();
+
+            let ear = (0..count).find(|&index| {
+                let previous = remaining[(index + count - 1) % count];
+                let current = remaining[index];
+                let next = remaining[(index + 1) % count];
+
+                cross(previous, current, next) > 0.0
+                    && remaining.iter().all(|&other| {
+                        other == previous
+                            || other == current
+                            || other == next
+                            || cross(previous, current, other) < 0.0
+                            || cross(current, next, other) < 0.0
+                            || cross(next, previous, other) < 0.0
+                    })
+            });
+
+            // A degenerate polygon may have no ears, fall back to a fan.
+            let Some(index) = ear else {
+                break;
+            };
+
+            triangles.push([
+                remaining[(index + count - 1) % count],
+                remaining[index],
+                remaining[(index + 1) % count],
+            ]);
+            remaining.remove(index);
+        }
+
+        triangles.extend(fan(&remaining));
+
+        triangles
+    }
+
+    fn parse_smoothing(line: &str) -> Result<Option<u32>> {
+        let items = Self::split(line);
+
+        if items.len() != 2 {
+            bail!(
+                "\
+Expected 's' followed by a smoothing group number or 'off'.
+Found {} items.",
+                items.len()
+            );
+        }
+
+        match items[1] {
+            "off" | "0" => Ok(None),
+            group => Ok(Some(group.parse()?)),
+        }
+    }
+
+    #[must_use]
+    fn face_normal(&self, vertices: [usize; 3]) -> Vector {
+        let [vertex1, vertex2, vertex3] = vertices.map(|v| self.vertices[v]);
 
-        if groups.insert(String::from(group_name), Vec::new()).is_some() {
-            bail!("Group {group_name} is repeated.");
+        (vertex3 - vertex1).cross(&(vertex2 - vertex1))
+    }
+
+    /// Sum the face normals, which have a length proportional to the area of
+    /// the face, for every vertex in each smoothing group.
+    #[must_use]
+    fn smoothed_normals(
+        &self,
+        objects: &[ObjObject],
+    ) -> HashMap<(u32, usize), Vector> {
+        let mut normals: HashMap<(u32, usize), Vector> = HashMap::new();
+
+        for face in objects.iter().flat_map(ObjObject::faces) {
+            let (None, Some(smoothing)) = (face.normals, face.smoothing) else {
+                continue;
+            };
+
+            let normal = self.face_normal(face.vertices);
+
+            for vertex in face.vertices {
+                *normals
+                    .entry((smoothing, vertex))
+                    .or_insert(Vector::new(0.0, 0.0, 0.0)) += normal;
+            }
         }
 
-        groups.get_mut(group_name).ok_or_else(|| unreachable!())
+        normals
+    }
+
+    #[must_use]
+    fn build_triangle(
+        &self,
+        face: &Face,
+        smoothed: &HashMap<(u32, usize), Vector>,
+    ) -> Object {
+        let [vertex1, vertex2, vertex3] =
+            face.vertices.map(|vertex| self.vertices[vertex]);
+
+        let normals = if let Some(normals) = face.normals {
+            Some(normals.map(|normal| self.normals[normal]))
+        } else if let Some(smoothing) = face.smoothing {
+            let normals = face.vertices.map(|vertex| {
+                smoothed
+                    .get(&(smoothing, vertex))
+                    .copied()
+                    .unwrap_or_else(|| unreachable!())
+            });
+
+            // Degenerate faces do not contribute a usable normal.
+            if normals.iter().all(|normal| normal.magnitude() > 0.0) {
+                Some(normals.map(|normal| normal.normalise()))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let triangle = if let Some([normal1, normal2, normal3]) = normals {
+            Object::fixed_winding_triangle_builder(
+                vertex1, vertex2, vertex3, normal1, normal2, normal3,
+            )
+            .build()
+        } else {
+            Object::flat_triangle_builder(vertex1, vertex2, vertex3).build()
+        };
+
+        let triangle = if let Some(uvs) = face.uvs {
+            triangle.with_uvs(uvs.map(|uv| self.uvs[uv]))
+        } else {
+            triangle
+        };
+
+        match face.vertices.map(|vertex| self.colours[vertex]) {
+            [Some(colour1), Some(colour2), Some(colour3)] => {
+                triangle.with_vertex_colours([colour1, colour2, colour3])
+            }
+            _ => triangle,
+        }
     }
 
     pub fn into_group(self) -> GroupBuilder {
@@ -235,7 +697,11 @@ If one vertex normal is specified, all faces must also provide vertex normals."
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::math::{float::*, Vector};
+    use crate::{
+        intersection::Intersection,
+        math::{float::*, Ray, Vector},
+        object::shapes::Shapes,
+    };
 
     #[test]
     fn ignoring_unrecognised_lines() {
@@ -256,6 +722,39 @@ mod tests {
         assert_approx_eq!(p.vertices[3], Point::new(1.0, 1.0, 0.0));
     }
 
+    #[test]
+    fn parsing_vertex_colours() {
+        let p = ObjParser::parse("src/object/tests/colours.obj").unwrap();
+
+        assert_eq!(p.vertices.len(), 4);
+        assert_approx_eq!(p.vertices[1], Point::new(-1.0, 0.0, 0.0));
+
+        assert_approx_eq!(p.colours[0].unwrap(), Colour::red());
+        assert_approx_eq!(p.colours[1].unwrap(), Colour::green());
+        assert_approx_eq!(p.colours[2].unwrap(), Colour::blue());
+        assert!(p.colours[3].is_none());
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+        let c = &g.objects;
+
+        let colour = |object: &Object| {
+            object.vertex_colour(&Intersection::new_with_u_v(
+                object,
+                1.0,
+                1.0 / 3.0,
+                1.0 / 3.0,
+            ))
+        };
+
+        assert_approx_eq!(
+            colour(&c[0]).unwrap(),
+            Colour::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)
+        );
+
+        // Faces with any uncoloured vertex use the material instead.
+        assert!(colour(&c[1]).is_none());
+    }
+
     #[test]
     fn parsing_invalid_vertices() {
         let p = ObjParser::parse("src/object/tests/too_many_vertices.obj");
@@ -265,7 +764,8 @@ mod tests {
         assert_eq!(
             e.to_string(),
             "\
-Expected 'v' followed by 3 space separated numbers for a vertex.
+Expected 'v' followed by 3 space separated numbers for a vertex and optionally
+3 more for its colour.
 Found 5 items."
         );
 
@@ -364,6 +864,73 @@ Found 3 items."
         );
     }
 
+    #[test]
+    fn triangulating_quads() {
+        let p = ObjParser::parse("src/object/tests/quad.obj").unwrap();
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+        let c = &g.objects;
+
+        assert_eq!(c.len(), 2);
+
+        assert_approx_eq!(
+            c[0],
+            &Object::fixed_winding_triangle_builder(
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Vector::z_axis(),
+                Vector::x_axis(),
+                Vector::y_axis(),
+            )
+            .build()
+        );
+        assert_approx_eq!(
+            c[1],
+            &Object::fixed_winding_triangle_builder(
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+                Vector::z_axis(),
+                Vector::y_axis(),
+                -Vector::x_axis(),
+            )
+            .build()
+        );
+    }
+
+    #[test]
+    fn triangulating_concave_polygons() {
+        let v = [
+            Point::new(2.0, 1.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        ];
+
+        let t = ObjParser::triangulate(&v, &[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(t.len(), 4);
+
+        // Every triangle should keep the winding of the polygon and together
+        // they should cover its area exactly.
+        let areas = t
+            .iter()
+            .map(|[a, b, c]| (v[*b] - v[*a]).cross(&(v[*c] - v[*a])).z / 2.0)
+            .collect::<Vec<_>>();
+
+        assert!(areas.iter().all(|area| *area > 0.0));
+        assert_approx_eq!(areas.iter().sum::<f64>(), 3.0);
+
+        let p = ObjParser::parse("src/object/tests/concave.obj").unwrap();
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+
+        assert_eq!(g.objects.len(), 4);
+    }
+
     #[test]
     fn triangles_in_groups() {
         let o = ObjParser::parse("src/object/tests/triangles.obj")
@@ -416,6 +983,89 @@ Found 3 items."
         assert_eq!(e.to_string(), "Group FirstGroup is repeated.");
     }
 
+    #[test]
+    fn objects_in_nested_groups() {
+        let o = ObjParser::parse("src/object/tests/objects.obj")
+            .unwrap()
+            .into_group()
+            .build();
+
+        let Object::Group(g) = o else { unreachable!() };
+        let c = &g.objects;
+
+        assert_eq!(c.len(), 3);
+
+        let Object::Group(g) = &c[0] else { unreachable!() };
+        assert_eq!(g.objects.len(), 1);
+        assert!(matches!(g.objects[0], Object::Shape(_)));
+
+        let Object::Group(g) = &c[1] else { unreachable!() };
+        assert_eq!(g.objects.len(), 3);
+        assert!(g.objects.iter().all(|o| matches!(o, Object::Shape(_))));
+
+        assert_approx_eq!(
+            g.objects[0],
+            &Object::flat_triangle_builder(
+                Point::new(-1.0, 1.0, 0.0),
+                Point::new(-1.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0)
+            )
+            .build()
+        );
+        assert_approx_eq!(
+            g.objects[2],
+            &Object::flat_triangle_builder(
+                Point::new(-1.0, 1.0, 0.0),
+                Point::new(-1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0)
+            )
+            .build()
+        );
+
+        let Object::Group(g) = &c[2] else { unreachable!() };
+        let c = &g.objects;
+
+        assert_eq!(c.len(), 3);
+        assert!(matches!(c[0], Object::Shape(_)));
+
+        let Object::Group(g) = &c[1] else { unreachable!() };
+        assert_eq!(g.objects.len(), 2);
+
+        let Object::Group(g) = &c[2] else { unreachable!() };
+        assert_eq!(g.objects.len(), 1);
+    }
+
+    #[test]
+    fn building_triangles_in_parallel_keeps_their_order() {
+        for file in ["objects.obj", "smoothing.obj", "triangulating.obj"] {
+            let filename = format!("src/object/tests/{file}");
+
+            let parallel = ObjParser::parse(&filename).unwrap();
+            let sequential = ObjParser::parse_all(
+                &filename,
+                UpAxis::Y,
+                Handedness::Right,
+                false,
+            )
+            .unwrap();
+
+            assert_eq!(parallel.groups.len(), sequential.groups.len());
+
+            for (lhs, rhs) in parallel.groups.iter().zip(&sequential.groups) {
+                assert_approx_eq!(lhs, rhs);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_objects() {
+        let p = ObjParser::parse("src/object/tests/invalid_objects.obj");
+
+        let e = p.unwrap_err();
+
+        assert_eq!(e.to_string(), "Object Zebra is repeated.");
+    }
+
     #[test]
     fn parse_vertex_normal() {
         let p = ObjParser::parse("src/object/tests/normals.obj").unwrap();
@@ -459,7 +1109,7 @@ Found 6 items."
 
         assert_eq!(c.len(), 2);
 
-        let t = Object::triangle_builder(
+        let t = Object::fixed_winding_triangle_builder(
             Point::new(0.0, 1.0, 0.0),
             Point::new(-1.0, 0.0, 0.0),
             Point::new(1.0, 0.0, 0.0),
@@ -473,6 +1123,234 @@ Found 6 items."
         assert_approx_eq!(c[1], &t);
     }
 
+    #[test]
+    fn parsing_texture_coordinates() {
+        let p = ObjParser::parse("src/object/tests/uvs.obj").unwrap();
+
+        assert_eq!(p.uvs.len(), 3);
+        assert_approx_eq!(p.uvs[1].0, 1.0);
+        assert_approx_eq!(p.uvs[2].1, 1.0);
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+        let Object::Shape(s) = &g.objects[0] else { unreachable!() };
+        let Shapes::Triangle(t) = &s.shape else { unreachable!() };
+
+        let (tangent, bitangent) = t.tangents().unwrap();
+        assert_approx_eq!(tangent, Vector::x_axis());
+        assert_approx_eq!(bitangent, Vector::y_axis());
+
+        // Faces without texture coordinates have no tangents.
+        let Object::Shape(s) = &g.objects[1] else { unreachable!() };
+        let Shapes::Triangle(t) = &s.shape else { unreachable!() };
+
+        assert!(t.tangents().is_none());
+
+        let p = ObjParser::parse("src/object/tests/invalid_uvs.obj");
+
+        assert_eq!(
+            p.unwrap_err().to_string(),
+            "\
+Expected 'vt' followed by 2 space separated numbers for texture coordinates and
+optionally a third which is ignored.
+Found 2 items."
+        );
+    }
+
+    #[test]
+    fn parsing_a_z_up_file() {
+        let p = ObjParser::parse("src/object/tests/z_up.obj").unwrap();
+
+        assert_approx_eq!(p.vertices[0], Point::new(0.0, 0.0, 1.0));
+
+        let p = ObjParser::parse_with(
+            "src/object/tests/z_up.obj",
+            UpAxis::Z,
+            Handedness::Right,
+        )
+        .unwrap();
+
+        assert_approx_eq!(p.vertices[0], Point::new(0.0, 1.0, 0.0));
+        assert_approx_eq!(p.vertices[1], Point::new(-1.0, 0.0, 0.0));
+        assert_approx_eq!(p.normals[0], Vector::y_axis());
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+
+        assert_approx_eq!(
+            g.objects[0],
+            &Object::fixed_winding_triangle_builder(
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(-1.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Vector::y_axis(),
+                -Vector::x_axis(),
+                Vector::x_axis(),
+            )
+            .build()
+        );
+    }
+
+    #[test]
+    fn parsing_a_left_handed_file() {
+        let p = ObjParser::parse_with(
+            "src/object/tests/asymmetric.obj",
+            UpAxis::Y,
+            Handedness::Left,
+        )
+        .unwrap();
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+
+        // Each triangle is mirrored along z and its winding reversed so its
+        // normals still point out of the mesh.
+        let mirrored = Object::flat_triangle_builder(
+            Point::new(0.0, 0.0, -1.0),
+            Point::new(0.0, 1.0, -3.0),
+            Point::new(1.0, 0.0, -2.0),
+        )
+        .build();
+
+        assert_approx_eq!(g.objects[0], &mirrored);
+        assert_approx_eq!(
+            g.objects[1],
+            &Object::fixed_winding_triangle_builder(
+                Point::new(0.0, 0.0, -1.0),
+                Point::new(2.0, 2.0, 0.0),
+                Point::new(1.0, 0.0, -2.0),
+                -Vector::z_axis(),
+                Vector::y_axis(),
+                Vector::x_axis(),
+            )
+            .build()
+        );
+
+        let p = ObjParser::parse("src/object/tests/asymmetric.obj").unwrap();
+        let Object::Group(right) = &p.groups[0] else { unreachable!() };
+
+        assert_approx_ne!(right.objects[0], &mirrored);
+
+        // The face normal of the mirrored triangle is the mirror of the
+        // original face normal.
+        let ray = |z: f64| {
+            Ray::new(
+                Point::new(0.2, 0.2, z),
+                Vector::new(0.0, 0.0, -z.signum()),
+            )
+        };
+        let normal = |object: &Object, ray: &Ray| {
+            let l = object.intersect(ray).unwrap();
+
+            l[0].object.normal_at(&ray.position(l[0].t), &l[0])
+        };
+
+        let original = normal(&right.objects[0], &ray(10.0));
+        let flipped = normal(&g.objects[0], &ray(-10.0));
+
+        assert_approx_eq!(
+            flipped,
+            Vector::new(original.x, original.y, -original.z)
+        );
+    }
+
+    #[test]
+    fn parsing_smoothing_groups() {
+        let p = ObjParser::parse("src/object/tests/smoothing.obj").unwrap();
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+        let c = &g.objects;
+
+        assert_eq!(c.len(), 3);
+
+        // The normals at shared vertices are the area weighted average of the
+        // normals of the faces around them.
+        let shared = Vector::new(1.0, 1.0, -3.0).normalise();
+
+        assert_approx_eq!(
+            c[0],
+            &Object::fixed_winding_triangle_builder(
+                p.vertices[0],
+                p.vertices[1],
+                p.vertices[2],
+                shared,
+                -Vector::z_axis(),
+                shared,
+            )
+            .build()
+        );
+        assert_approx_eq!(
+            c[1],
+            &Object::fixed_winding_triangle_builder(
+                p.vertices[0],
+                p.vertices[3],
+                p.vertices[2],
+                shared,
+                Vector::new(1.0, 1.0, -1.0).normalise(),
+                shared,
+            )
+            .build()
+        );
+        assert_approx_eq!(
+            c[2],
+            &Object::flat_triangle_builder(
+                p.vertices[1],
+                p.vertices[2],
+                p.vertices[3],
+            )
+            .build()
+        );
+    }
+
+    #[test]
+    fn explicit_normals_take_priority_over_smoothing_groups() {
+        let p =
+            ObjParser::parse("src/object/tests/smoothing_normals.obj").unwrap();
+
+        let Object::Group(g) = &p.groups[0] else { unreachable!() };
+        let c = &g.objects;
+
+        assert_eq!(c.len(), 2);
+
+        assert_approx_eq!(
+            c[0],
+            &Object::fixed_winding_triangle_builder(
+                p.vertices[0],
+                p.vertices[1],
+                p.vertices[2],
+                p.normals[0],
+                p.normals[1],
+                p.normals[2],
+            )
+            .build()
+        );
+        assert_approx_eq!(
+            c[1],
+            &Object::fixed_winding_triangle_builder(
+                p.vertices[0],
+                p.vertices[3],
+                p.vertices[2],
+                p.normals[0],
+                p.normals[1],
+                p.normals[2],
+            )
+            .build()
+        );
+    }
+
+    #[test]
+    fn parsing_invalid_smoothing_groups() {
+        assert_eq!(
+            ObjParser::parse_smoothing("s 1 2").unwrap_err().to_string(),
+            "\
+Expected 's' followed by a smoothing group number or 'off'.
+Found 3 items."
+        );
+        assert_eq!(
+            ObjParser::parse_smoothing("s on").unwrap_err().to_string(),
+            "invalid digit found in string"
+        );
+        assert_eq!(ObjParser::parse_smoothing("s 0").unwrap(), None);
+        assert_eq!(ObjParser::parse_smoothing("s 4").unwrap(), Some(4));
+    }
+
     #[test]
     #[should_panic(
         expected = "index out of bounds: the len is 2 but the index is 2"
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// `Shading` picks the normal used for triangles, smooth triangles interpolate
+/// their vertex normals while flat shading always uses the face normal giving
+/// a faceted look.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shading {
+    #[default]
+    Smooth,
+    Flat,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::from_str;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_shading() {
+        assert_eq!(from_str::<Shading>("smooth").unwrap(), Shading::Smooth);
+        assert_eq!(from_str::<Shading>("flat").unwrap(), Shading::Flat);
+        assert_eq!(Shading::default(), Shading::Smooth);
+    }
+}
@@ -1,8 +1,11 @@
+use std::{collections::HashSet, sync::Arc};
+
 use typed_builder::{Optional, TypedBuilder};
 
 use super::{
     shapes::{Intersectable, Shapes},
-    Bounded, BoundingBox, Includes, Object, Updatable,
+    Bounded, BoundingBox, EstimateMemory, Includes, Object, Shading, Summarise,
+    Updatable,
 };
 use crate::{
     intersection::{Intersection, List},
@@ -10,11 +13,11 @@ use crate::{
         float::{approx_eq, impl_approx_eq},
         Point, Ray, Transformable, Transformation, Vector,
     },
-    Material,
+    Material, Summary,
 };
 
 #[allow(clippy::module_name_repetitions)]
-pub(super) type ShapeBuilder = _ShapeBuilder<((), (), (), (Shapes,))>;
+pub(super) type ShapeBuilder = _ShapeBuilder<((), (), (), (), (), (Shapes,))>;
 
 /// A `Shape` is a simple geometric shape, fixed around the origin.
 #[derive(Clone, Debug, TypedBuilder)]
@@ -26,12 +29,28 @@ pub struct Shape {
     pub(super) transformation: Transformation,
     #[builder(default = Transformation::new(), setter(skip))]
     inverse_transformation: Transformation,
-    #[builder(default = Material::default())]
-    pub(super) material: Material,
+    /// Materials are reference counted so that objects sharing one, such as
+    /// the children of a group, do not each hold their own copy.
+    #[builder(default = Arc::new(Material::default()), setter(into))]
+    pub(super) material: Arc<Material>,
     #[builder(default = true)]
     pub(super) casts_shadow: bool,
+    /// Planes given as a point and a normal in object space, only the parts of
+    /// the shape on the side of every plane that its normal points towards are
+    /// kept.
+    #[builder(default = Vec::new())]
+    clip_planes: Vec<(Point, Vector)>,
+    /// A motion path as `(time, Transformation)` pairs sorted by time. The
+    /// interpolated keyframe is applied before `transformation`, the shape is
+    /// posed at the first keyframe until `at_time` is used to move it.
+    #[builder(default = Vec::new())]
+    keyframes: Vec<(f64, Transformation)>,
+    /// The transformation without any keyframe applied, only used when there
+    /// are keyframes.
+    #[builder(default = Transformation::new(), setter(skip))]
+    base_transformation: Transformation,
     #[allow(clippy::struct_field_names)]
-    shape: Shapes,
+    pub(super) shape: Shapes,
 }
 
 impl Shape {
@@ -51,9 +70,36 @@ impl Shape {
         ray: &Ray,
         object: &'a Object,
     ) -> Option<List<'a>> {
-        let ray = self.to_object_space(ray);
+        let object_ray = self.to_object_space(ray);
+
+        let mut t_list = self.shape.intersect(&object_ray)?;
+
+        if !self.clip_planes.is_empty() {
+            t_list.retain(|t_values| {
+                self.is_kept(&object_ray.position(t_values.t))
+            });
+        }
+
+        if self.material.alpha_map.is_some() {
+            t_list.retain(|t_values| {
+                self.material.is_opaque(object, &ray.position(t_values.t))
+            });
+        }
 
-        self.shape.intersect(&ray).map(|t_list| t_list.into_list(object))
+        if t_list.is_empty() {
+            return None;
+        }
+
+        Some(t_list.into_list(object))
+    }
+
+    /// Check if a point in object space lies on the kept side of all the clip
+    /// planes.
+    #[must_use]
+    fn is_kept(&self, point: &Point) -> bool {
+        self.clip_planes
+            .iter()
+            .all(|(origin, normal)| (*point - *origin).dot(normal) >= 0.0)
     }
 
     #[must_use]
@@ -64,25 +110,83 @@ impl Shape {
     ) -> Vector {
         let object_point = self.to_object_space(point);
 
-        let object_normal = self.shape.normal_at(&object_point, intersection);
+        let mut object_normal =
+            self.shape.normal_at(&object_point, intersection);
+
+        if let Some(normal_map) = &self.material.normal_map {
+            let colour = normal_map.pattern_at(intersection.object, point);
+
+            let tangent_normal = Vector::new(
+                colour.red * 2.0 - 1.0,
+                colour.green * 2.0 - 1.0,
+                colour.blue * 2.0 - 1.0,
+            );
 
+            if let Some(normal) = self
+                .shape
+                .tangent_to_object(&tangent_normal, &object_normal.normalise())
+            {
+                object_normal = normal;
+            }
+        }
+
+        // The inverse transpose keeps normals facing outwards even when the
+        // transformation mirrors the shape, so no flip is needed.
         self.to_world_space(&object_normal).normalise()
     }
+
+    /// A point and normal in world space describing the plane the shape lies
+    /// in, `None` if the shape is not flat.
+    #[must_use]
+    pub fn flat_surface(&self) -> Option<(Point, Vector)> {
+        let (point, normal) = self.shape.flat_surface()?;
+
+        Some((
+            point.apply(&self.transformation),
+            self.to_world_space(&normal).normalise(),
+        ))
+    }
+
+    /// Pose the shape at `time` along its keyframes, shapes without keyframes
+    /// are unchanged.
+    #[must_use]
+    pub fn at_time(&self, time: f64) -> Self {
+        let mut shape = self.clone();
+
+        if !self.keyframes.is_empty() {
+            shape.pose(time);
+        }
+
+        shape
+    }
+
+    fn pose(&mut self, time: f64) {
+        self.transformation =
+            Transformation::from_keyframes(&self.keyframes, time)
+                .extend(&self.base_transformation);
+        self.inverse_transformation = self.transformation.invert();
+    }
 }
 
 impl Updatable for Shape {
     fn update_transformation(&mut self, transformation: &Transformation) {
         self.transformation = self.transformation.extend(transformation);
         self.inverse_transformation = self.transformation.invert();
+        self.base_transformation =
+            self.base_transformation.extend(transformation);
     }
 
-    fn replace_material(&mut self, material: &Material) {
-        self.material = material.clone();
+    fn share_material(&mut self, material: &Arc<Material>) {
+        self.material = Arc::clone(material);
     }
 
     fn update_casts_shadow(&mut self, casts_shadow: bool) {
         self.casts_shadow = casts_shadow;
     }
+
+    fn update_shading(&mut self, shading: Shading) {
+        self.shape = self.shape.clone().with_shading(shading);
+    }
 }
 
 impl Bounded for Shape {
@@ -105,13 +209,39 @@ impl Includes for Shape {
     }
 }
 
+impl EstimateMemory for Shape {
+    fn estimate_memory(
+        &self,
+        materials: &mut HashSet<*const Material>,
+    ) -> usize {
+        let material = if materials.insert(Arc::as_ptr(&self.material)) {
+            size_of::<Material>()
+        } else {
+            0
+        };
+
+        size_of::<Object>()
+            + self.clip_planes.capacity() * size_of::<(Point, Vector)>()
+            + self.keyframes.capacity() * size_of::<(f64, Transformation)>()
+            + material
+    }
+}
+
+impl Summarise for Shape {
+    fn summarise(&self, summary: &mut Summary) {
+        summary.add_shape(self.shape.kind());
+    }
+}
+
 impl_approx_eq!(&Shape { ref shape, transformation, ref material });
 
-impl<T, M, S> _ShapeBuilder<(T, M, S, (Shapes,))>
+impl<T, M, S, C, K> _ShapeBuilder<(T, M, S, C, K, (Shapes,))>
 where
     T: Optional<Transformation>,
-    M: Optional<Material>,
+    M: Optional<Arc<Material>>,
     S: Optional<bool>,
+    C: Optional<Vec<(Point, Vector)>>,
+    K: Optional<Vec<(f64, Transformation)>>,
 {
     #[must_use]
     pub fn build(self) -> Object {
@@ -119,6 +249,13 @@ where
 
         shape.inverse_transformation = shape.transformation.invert();
 
+        if !shape.keyframes.is_empty() {
+            shape.keyframes.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+            shape.base_transformation = shape.transformation;
+
+            shape.pose(shape.keyframes[0].0);
+        }
+
         shape.into()
     }
 }
@@ -128,12 +265,14 @@ mod tests {
     use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
 
     use paste::paste;
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
 
     use super::*;
     use crate::{
         math::{float::*, Angle},
         object::shapes::test::Test,
-        Colour, Object,
+        AntiAliasing, Camera, Colour, Light, Object, Output, World,
     };
 
     #[test]
@@ -145,9 +284,9 @@ mod tests {
         macro_rules! test_object {
             ($shape:ident($($args:expr $(,)?)*)) => {{
                 paste! {
-                    let s = Shapes::[<new_ $shape:lower>]($($args,)*);
+                    let s = Shapes::[<new_ $shape:snake>]($($args,)*);
 
-                    let o = Object::[<$shape:lower _builder>]($($args,)*)
+                    let o = Object::[<$shape:snake _builder>]($($args,)*)
                         .transformation(t)
                         .material(m.clone())
                         .casts_shadow(false)
@@ -161,7 +300,7 @@ mod tests {
                     assert!(!o.casts_shadow);
                     assert_approx_eq!(o.shape, &s);
 
-                    let o = Object::[<$shape:lower _builder>]($($args,)*)
+                    let o = Object::[<$shape:snake _builder>]($($args,)*)
                         .build();
 
                     let Object::Shape(o) = o else { unreachable!() };
@@ -181,10 +320,82 @@ mod tests {
         test_object!(Cube());
         test_object!(Cylinder(1.0, 2.0, false));
         test_object!(Plane());
+        test_object!(RoundedBox(0.2));
         test_object!(Sphere());
         test_object!(Test());
     }
 
+    #[test]
+    fn intersecting_a_clipped_sphere() {
+        let o = Object::sphere_builder()
+            .clip_planes(vec![(Point::origin(), Vector::z_axis())])
+            .build();
+
+        let l = o
+            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 6.0);
+
+        let l = o
+            .intersect(&Ray::new(Point::new(0.0, 0.0, 5.0), -Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 4.0);
+
+        assert!(o
+            .intersect(&Ray::new(Point::new(-5.0, 0.0, -0.5), Vector::x_axis()))
+            .is_none());
+        assert!(o
+            .intersect(&Ray::new(Point::new(-5.0, 0.0, 0.5), Vector::x_axis()))
+            .is_some());
+    }
+
+    #[test]
+    fn a_clipped_sphere_renders_one_hemisphere() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .clip_planes(vec![(Point::origin(), Vector::z_axis())])
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(0.0, 10.0, 0.0),
+            Colour::white(),
+        ));
+
+        // Looking down on the sphere with z pointing up the image.
+        let c = Camera::new(
+            11,
+            11,
+            Angle(PI / 6.0),
+            Transformation::view_transformation(
+                Point::new(0.0, 5.0, 0.0),
+                Point::origin(),
+                Vector::z_axis(),
+            ),
+        );
+
+        let i = c
+            .render(
+                &w,
+                5,
+                true,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0),
+            )
+            .unwrap();
+
+        for x in 3..8 {
+            assert_approx_ne!(i.get_pixel(x, 2), Colour::black());
+            assert_approx_eq!(i.get_pixel(x, 8), Colour::black());
+        }
+    }
+
     #[test]
     fn intersecting_a_transformed_object_with_a_ray() {
         let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
@@ -314,6 +525,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn perturbing_the_normal_of_a_triangle_with_a_normal_map() {
+        let m = Material::builder()
+            .normal_map(Some(Colour::new(1.0, 0.5, 1.0).into()))
+            .build();
+
+        let o = Object::flat_triangle_builder(
+            Point::origin(),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        )
+        .material(m)
+        .build();
+
+        let p = Point::new(0.25, 0.25, 0.0);
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.25, 0.25);
+
+        // Without texture coordinates there is no tangent space to use.
+        assert_approx_eq!(o.normal_at(&p, &i), -Vector::z_axis());
+
+        let o = o.with_uvs([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.25, 0.25);
+
+        assert_approx_eq!(
+            o.normal_at(&p, &i),
+            Vector::new(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn normals_on_mirrored_shapes_point_outwards() {
+        for transformation in [
+            Transformation::new().scale(-1.0, 1.0, 1.0),
+            Transformation::new()
+                .scale(-2.0, 0.5, 1.0)
+                .translate(1.0, 2.0, 3.0),
+            Transformation::new()
+                .rotate_y(Angle::from_degrees(30.0))
+                .scale(1.0, -1.0, -3.0),
+        ] {
+            let centre = Point::origin().apply(&transformation);
+
+            for o in [
+                Object::sphere_builder().transformation(transformation).build(),
+                Object::cube_builder().transformation(transformation).build(),
+            ] {
+                for direction in [
+                    Vector::new(1.0, 0.2, 0.3),
+                    Vector::new(-0.4, 1.0, 0.1),
+                    Vector::new(0.3, -0.2, -1.0),
+                ] {
+                    // Cast a ray from outside the shape towards its centre,
+                    // the normal where it enters must face back along the ray.
+                    let direction = direction.normalise();
+                    let r = Ray::new(centre + direction * 20.0, -direction);
+
+                    let l = o.intersect(&r).unwrap();
+                    let h = l.hit().unwrap();
+                    let n = o.normal_at(&r.position(h.t), &h);
+
+                    assert!(n.dot(&r.direction) < 0.0);
+                    assert!(n.dot(&(r.position(h.t) - centre)) > 0.0);
+                }
+            }
+        }
+    }
+
     #[test]
     fn the_bounding_box_of_an_object() {
         let o = Object::sphere_builder()
@@ -333,6 +611,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn objects_can_share_a_material() {
+        let m =
+            Arc::new(Material::builder().pattern(Colour::red().into()).build());
+
+        let o1 = Object::sphere_builder().material(Arc::clone(&m)).build();
+        let o2 = Object::cube_builder().material(Arc::clone(&m)).build();
+
+        assert!(std::ptr::eq(o1.material(), o2.material()));
+        assert_eq!(Arc::strong_count(&m), 3);
+
+        let g = Object::group_builder()
+            .set_objects(vec![
+                Object::sphere_builder().build(),
+                Object::cube_builder().build(),
+            ])
+            .material(Material::glass())
+            .build();
+
+        let Object::Group(g) = g else { unreachable!() };
+        let [o3, o4] = g.objects.as_slice() else { unreachable!() };
+
+        assert!(std::ptr::eq(o3.material(), o4.material()));
+
+        let render = |o: Object| {
+            let mut w = World::new();
+
+            w.add_object(o);
+            w.add_light(Light::new_point(
+                Point::new(-10.0, 10.0, -10.0),
+                Colour::white(),
+            ));
+
+            Camera::new(
+                11,
+                11,
+                Angle(PI / 3.0),
+                Transformation::view_transformation(
+                    Point::new(0.0, 0.0, -5.0),
+                    Point::origin(),
+                    Vector::y_axis(),
+                ),
+            )
+            .render(
+                &w,
+                5,
+                true,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0),
+            )
+            .unwrap()
+        };
+
+        let i1 = render(o1);
+        let i2 =
+            render(Object::sphere_builder().material((*m).clone()).build());
+
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_approx_eq!(i1.get_pixel(x, y), i2.get_pixel(x, y));
+            }
+        }
+        assert_approx_ne!(i1.get_pixel(5, 5), Colour::black());
+    }
+
     #[test]
     fn test_updating_a_shape() {
         let mut o = Object::sphere_builder().build();
@@ -381,4 +725,44 @@ mod tests {
 
         assert_approx_ne!(o1, &o3);
     }
+
+    #[test]
+    fn posing_a_shape_along_its_keyframes() {
+        let o = Object::sphere_builder()
+            .transformation(Transformation::new().translate(0.0, 1.0, 0.0))
+            .keyframes(vec![
+                (1.0, Transformation::new().translate(4.0, 0.0, 0.0)),
+                (0.0, Transformation::new()),
+            ])
+            .build();
+
+        let position =
+            |object: &Object| Point::origin().apply(&object.transformation());
+
+        // Shapes start at the first keyframe.
+        assert_approx_eq!(position(&o), Point::new(0.0, 1.0, 0.0));
+
+        let p = position(&o.at_time(0.5));
+
+        assert!(p.x > 0.0 && p.x < 4.0);
+        assert_approx_eq!(p, Point::new(2.0, 1.0, 0.0));
+
+        assert_approx_eq!(position(&o.at_time(2.0)), Point::new(4.0, 1.0, 0.0));
+
+        let g = Object::group_builder()
+            .add_object(o)
+            .transformation(Transformation::new().translate(0.0, 0.0, 3.0))
+            .build();
+
+        let g = g.at_time(0.75);
+
+        assert_approx_eq!(position(g.shapes()[0]), Point::new(3.0, 1.0, 3.0));
+        assert_approx_eq!(
+            g.bounding_box(),
+            BoundingBox::new(
+                Point::new(2.0, 0.0, 2.0),
+                Point::new(4.0, 2.0, 4.0)
+            )
+        );
+    }
 }
@@ -1,50 +1,126 @@
 use std::f64::EPSILON;
 
-use derive_new::new;
-
-use super::{Bounded, BoundingBox, Intersectable};
+use super::{wedge::Wedge, Bounded, BoundingBox, Intersectable};
 use crate::{
     intersection::{Intersection, TList, TValues},
     math::{
         float::{approx_eq, approx_ne, impl_approx_eq},
-        Point, Ray, Vector,
+        Angle, Point, Ray, Vector,
     },
 };
 
 // A `Cone` is a double napped cone centred on the origin and extending in both
 // directions, its extend is given by minimum and maximum. Closed indicates if
-// the ends are capped.
-#[derive(Clone, Copy, Debug, new)]
+// the ends are capped. The cone can be limited to a wedge of angles around the
+// y axis.
+#[derive(Clone, Copy, Debug)]
 pub struct Cone {
     minimum: f64,
     maximum: f64,
-    closed: bool,
+    cap_minimum: bool,
+    cap_maximum: bool,
+    wedge: Wedge,
 }
 
 impl Cone {
+    /// Create a cone between `minimum` and `maximum` in y, equal values give
+    /// a flat disc at that height that is solid if either end is capped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `minimum` is greater than `maximum`.
+    #[must_use]
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Self {
+        assert!(
+            minimum <= maximum,
+            "The minimum of a cone can not be greater than its maximum"
+        );
+
+        Self {
+            minimum,
+            maximum,
+            cap_minimum: closed,
+            cap_maximum: closed,
+            wedge: Wedge::full(),
+        }
+    }
+
+    /// Set whether each end of the cone is capped, closing only one end
+    /// gives a cone that is open at the other.
+    #[must_use]
+    pub fn with_caps(mut self, minimum: bool, maximum: bool) -> Self {
+        self.cap_minimum = minimum;
+        self.cap_maximum = maximum;
+
+        self
+    }
+
+    /// A cone is only closed, and so has faces across a wedge, when both
+    /// ends are capped.
+    #[must_use]
+    const fn is_closed(&self) -> bool {
+        self.cap_minimum && self.cap_maximum
+    }
+
+    /// A cone with no height is a flat disc.
+    #[must_use]
+    fn is_disc(&self) -> bool {
+        approx_eq!(self.minimum, self.maximum)
+    }
+
+    /// Limit the cone to the angles between `minimum` and `maximum` measured
+    /// from the x axis towards the z axis. If the cone is closed the flat faces
+    /// at either end of the wedge are also added.
+    #[must_use]
+    pub fn with_wedge(mut self, minimum: Angle, maximum: Angle) -> Self {
+        self.wedge = Wedge::new(minimum, maximum);
+
+        self
+    }
+
     #[must_use]
     fn intersect_caps(&self, ray: &Ray, mut list: TList) -> Option<TList> {
         let check_cap = |t: f64, r: f64| {
-            let x = ray.origin.x + t * ray.direction.x;
-            let z = ray.origin.z + t * ray.direction.z;
+            let point = ray.position(t);
 
-            x.powi(2) + z.powi(2) <= r.powi(2)
+            point.x.powi(2) + point.z.powi(2) <= r.powi(2)
+                && self.wedge.contains(&point)
         };
 
-        if self.closed && approx_ne!(ray.direction.y, 0.0) {
+        if approx_ne!(ray.direction.y, 0.0) && self.is_disc() {
+            // Both caps of a disc lie in the same plane so it is only hit
+            // once.
             let t = (self.minimum - ray.origin.y) / ray.direction.y;
 
-            if check_cap(t, self.minimum) {
+            if (self.cap_minimum || self.cap_maximum)
+                && check_cap(t, self.minimum)
+            {
+                list.push(TValues::new(t));
+            }
+        } else if approx_ne!(ray.direction.y, 0.0) {
+            let t = (self.minimum - ray.origin.y) / ray.direction.y;
+
+            if self.cap_minimum && check_cap(t, self.minimum) {
                 list.push(TValues::new(t));
             }
 
             let t = (self.maximum - ray.origin.y) / ray.direction.y;
 
-            if check_cap(t, self.maximum) {
+            if self.cap_maximum && check_cap(t, self.maximum) {
                 list.push(TValues::new(t));
             }
         }
 
+        if self.is_closed() {
+            self.wedge.intersect_faces(
+                ray,
+                self.minimum,
+                self.maximum,
+                f64::abs,
+                &mut list,
+            );
+        }
+
         if list.is_empty() {
             return None;
         };
@@ -69,8 +145,10 @@ impl Intersectable for Cone {
         let mut list = TList::new();
 
         if approx_eq!(a, 0.0) {
-            if approx_ne!(b, 0.0) {
-                list.push(TValues::new(-c / (2.0 * b)));
+            let t = -c / (2.0 * b);
+
+            if approx_ne!(b, 0.0) && self.wedge.contains(&ray.position(t)) {
+                list.push(TValues::new(t));
             }
         } else {
             let discriminant = b.powi(2) - 4.0 * a * c;
@@ -86,12 +164,18 @@ impl Intersectable for Cone {
             let t1 = (-b + discriminant) / a;
 
             let y0 = ray.origin.y + t0 * ray.direction.y;
-            if self.minimum < y0 && y0 < self.maximum {
+            if self.minimum < y0
+                && y0 < self.maximum
+                && self.wedge.contains(&ray.position(t0))
+            {
                 list.push(TValues::new(t0));
             }
 
             let y1 = ray.origin.y + t1 * ray.direction.y;
-            if self.minimum < y1 && y1 < self.maximum {
+            if self.minimum < y1
+                && y1 < self.maximum
+                && self.wedge.contains(&ray.position(t1))
+            {
                 list.push(TValues::new(t1));
             }
         }
@@ -101,6 +185,10 @@ impl Intersectable for Cone {
 
     #[must_use]
     fn normal_at(&self, point: &Point, _intersection: &Intersection) -> Vector {
+        if self.is_disc() {
+            return Vector::y_axis();
+        }
+
         let distance = point.x.powi(2) + point.z.powi(2);
 
         if distance < 1.0 && point.y >= self.maximum - EPSILON {
@@ -109,6 +197,15 @@ impl Intersectable for Cone {
             return -Vector::y_axis();
         }
 
+        if self.is_closed() {
+            if let Some(normal) = self
+                .wedge
+                .face_normal(point, (distance.sqrt() - point.y.abs()).abs())
+            {
+                return normal;
+            }
+        }
+
         let mut y = distance.sqrt();
         if point.y > 0.0 {
             y = -y;
@@ -128,12 +225,20 @@ impl Bounded for Cone {
     }
 }
 
-impl_approx_eq!(&Cone { eq closed, minimum, maximum });
+impl_approx_eq!(
+    &Cone {
+        eq cap_minimum,
+        eq cap_maximum,
+        minimum,
+        maximum,
+        wedge
+    }
+);
 
 #[cfg(test)]
 mod tests {
     use std::f64::{
-        consts::{FRAC_1_SQRT_2, SQRT_2},
+        consts::{FRAC_1_SQRT_2, FRAC_PI_2, SQRT_2},
         INFINITY,
     };
 
@@ -221,6 +326,41 @@ mod tests {
         assert_approx_eq!(i[3].t, 0.5);
     }
 
+    #[test]
+    fn a_cone_with_no_height_is_a_disc() {
+        let c = Cone::new(-2.0, -2.0, true);
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 0.0);
+
+        let r = Ray::new(Point::new(1.5, 0.0, 0.0), -Vector::y_axis());
+        let hits = c.intersect(&r).unwrap();
+
+        assert_eq!(hits.len(), 1);
+        assert_approx_eq!(hits[0].t, 2.0);
+        assert_approx_eq!(
+            c.normal_at(&r.position(hits[0].t), &i),
+            Vector::y_axis()
+        );
+
+        assert!(c
+            .intersect(&Ray::new(Point::new(2.5, 0.0, 0.0), -Vector::y_axis()))
+            .is_none());
+
+        // A disc at the tip of the cone has no area.
+        assert!(Cone::new(0.0, 0.0, true)
+            .intersect(&Ray::new(Point::new(0.5, 1.0, 0.0), -Vector::y_axis()))
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The minimum of a cone can not be greater than its maximum"
+    )]
+    fn a_cone_with_its_minimum_above_its_maximum() {
+        let _ = Cone::new(1.0, -1.0, false);
+    }
+
     #[test]
     fn computing_the_normal_vector_on_a_cone() {
         let c = Cone::new(-1.5, 1.5, true);
@@ -263,6 +403,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_wedge_of_a_cone_misses_rays_aimed_at_the_removed_side() {
+        let c =
+            Cone::new(-1.0, 1.0, true).with_wedge(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert!(c
+            .intersect(&Ray::new(
+                Point::new(-0.25, 0.5, -5.0),
+                Vector::z_axis()
+            ))
+            .is_none());
+
+        let l = c
+            .intersect(&Ray::new(Point::new(0.25, 0.5, -5.0), Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 2);
+        assert_approx_eq!(l[0].t, 5.0 + 0.1875_f64.sqrt());
+        assert_approx_eq!(l[1].t, 5.0);
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 5.0);
+
+        assert_approx_eq!(
+            c.normal_at(&Point::new(0.25, 0.5, 0.0), &i),
+            -Vector::z_axis()
+        );
+    }
+
     #[test]
     fn comparing_cones() {
         let c1 = Cone::new(0.0, 1.0, true);
@@ -0,0 +1,174 @@
+use std::{fmt::Debug, sync::Arc};
+
+use super::{Bounded, BoundingBox, Intersectable};
+use crate::{
+    intersection::{Intersection, TList},
+    math::{Point, Ray, Vector},
+};
+
+/// The `CustomShape` trait allows shapes to be defined outside of this crate.
+/// Implementors provide intersections in object space by returning a `TList`,
+/// normals in object space and a bounding box; transformations, materials and
+/// grouping are all handled by the `Object` that wraps the shape. It is
+/// implemented automatically for anything that meets the requirements.
+pub trait CustomShape: Intersectable + Bounded + Debug + Send + Sync {}
+
+#[rustfmt::skip]
+impl<T> CustomShape for T
+where
+    T: Intersectable + Bounded + Debug + Send + Sync,
+{}
+
+/// A `Custom` wraps a user provided `CustomShape` so it can be stored and
+/// rendered alongside the built in shapes.
+#[derive(Clone, Debug)]
+pub struct Custom(Arc<dyn CustomShape>);
+
+impl Custom {
+    #[must_use]
+    pub fn new<S: CustomShape + 'static>(shape: S) -> Self {
+        Self(Arc::new(shape))
+    }
+
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Intersectable for Custom {
+    fn intersect(&self, ray: &Ray) -> Option<TList> {
+        self.0.intersect(ray)
+    }
+
+    fn normal_at(&self, point: &Point, intersection: &Intersection) -> Vector {
+        self.0.normal_at(point, intersection)
+    }
+}
+
+impl Bounded for Custom {
+    fn bounding_box(&self) -> BoundingBox {
+        self.0.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{
+        intersection::TValues,
+        math::{float::*, Transformation},
+        Colour, Light, Material, Object, World,
+    };
+
+    /// A unit square lying in the xy plane, facing down the negative z axis.
+    #[derive(Debug)]
+    struct Square;
+
+    impl Intersectable for Square {
+        fn intersect(&self, ray: &Ray) -> Option<TList> {
+            if approx_eq!(ray.direction.z, 0.0) {
+                return None;
+            }
+
+            let t = -ray.origin.z / ray.direction.z;
+            let point = ray.position(t);
+
+            if point.x.abs() > 1.0 || point.y.abs() > 1.0 {
+                return None;
+            }
+
+            Some(TList::from(TValues::new_with_u_v(t, point.x, point.y)))
+        }
+
+        fn normal_at(
+            &self,
+            _point: &Point,
+            _intersection: &Intersection,
+        ) -> Vector {
+            -Vector::z_axis()
+        }
+    }
+
+    impl Bounded for Square {
+        fn bounding_box(&self) -> BoundingBox {
+            BoundingBox::new(
+                Point::new(-1.0, -1.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+            )
+        }
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn intersecting_a_custom_shape() {
+        let c = Custom::new(Square);
+
+        let r = Ray::new(Point::new(0.5, 0.5, -5.0), Vector::z_axis());
+        let l = c.intersect(&r).unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 5.0);
+
+        let r = Ray::new(Point::new(1.5, 0.5, -5.0), Vector::z_axis());
+        assert!(c.intersect(&r).is_none());
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 5.0);
+        assert_approx_eq!(c.normal_at(&Point::origin(), &i), -Vector::z_axis());
+
+        assert_approx_eq!(
+            c.bounding_box(),
+            BoundingBox::new(
+                Point::new(-1.0, -1.0, 0.0),
+                Point::new(1.0, 1.0, 0.0)
+            )
+        );
+    }
+
+    #[test]
+    fn comparing_custom_shapes() {
+        let c1 = Custom::new(Square);
+        let c2 = c1.clone();
+        let c3 = Custom::new(Square);
+
+        assert!(c1.ptr_eq(&c2));
+        assert!(!c1.ptr_eq(&c3));
+    }
+
+    #[test]
+    fn rendering_a_custom_shape_in_a_world() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.0, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(
+            Object::custom_builder(Square)
+                .transformation(Transformation::new().translate(0.0, 0.0, 1.0))
+                .material(
+                    Material::builder().pattern(Colour::red().into()).build(),
+                )
+                .build(),
+        );
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let l = w.objects[0].intersect(&r).unwrap();
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 6.0);
+
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(0);
+        let c = w.colour_at(&r, 5, &mut rng);
+
+        assert_approx_ne!(c, Colour::black());
+        assert!(c.red > c.green);
+
+        let r = Ray::new(Point::new(2.0, 0.0, -5.0), Vector::z_axis());
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng), Colour::black());
+    }
+}
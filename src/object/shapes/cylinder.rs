@@ -1,50 +1,143 @@
-use std::f64::EPSILON;
+use std::f64::{consts::TAU, EPSILON};
 
-use derive_new::new;
-
-use super::{Bounded, BoundingBox, Intersectable};
+use super::{wedge::Wedge, Bounded, BoundingBox, Intersectable};
 use crate::{
     intersection::{Intersection, TList, TValues},
     math::{
         float::{approx_eq, approx_ne, impl_approx_eq},
-        Point, Ray, Vector,
+        Angle, Point, Ray, Vector,
     },
 };
 
 // A `Cylinder` is an cylinder of radius 1 centred on the y axis which extends
-// from minimum to maximum. Closed indicates if the cylinder is capped on both
-// ends.
-#[derive(Clone, Copy, Debug, new)]
+// from minimum to maximum. Each end can be capped separately. The cylinder can
+// be limited to a wedge of angles around the y axis.
+#[derive(Clone, Copy, Debug)]
 pub struct Cylinder {
     minimum: f64,
     maximum: f64,
-    closed: bool,
+    cap_minimum: bool,
+    cap_maximum: bool,
+    wedge: Wedge,
 }
 
 impl Cylinder {
+    /// Create a cylinder between `minimum` and `maximum` in y, equal values
+    /// give a flat disc at that height that is solid if either end is capped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `minimum` is greater than `maximum`.
+    #[must_use]
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Self {
+        assert!(
+            minimum <= maximum,
+            "The minimum of a cylinder can not be greater than its maximum"
+        );
+
+        Self {
+            minimum,
+            maximum,
+            cap_minimum: closed,
+            cap_maximum: closed,
+            wedge: Wedge::full(),
+        }
+    }
+
+    /// Set whether each end of the cylinder is capped, closing only one end
+    /// gives a cylinder that is open at the other.
+    #[must_use]
+    pub fn with_caps(mut self, minimum: bool, maximum: bool) -> Self {
+        self.cap_minimum = minimum;
+        self.cap_maximum = maximum;
+
+        self
+    }
+
+    /// A cylinder is only closed, and so has faces across a wedge, when both
+    /// ends are capped.
+    #[must_use]
+    const fn is_closed(&self) -> bool {
+        self.cap_minimum && self.cap_maximum
+    }
+
+    /// A cylinder with no height is a flat disc.
+    #[must_use]
+    fn is_disc(&self) -> bool {
+        approx_eq!(self.minimum, self.maximum)
+    }
+
+    /// Limit the cylinder to the angles between `minimum` and `maximum`
+    /// measured from the x axis towards the z axis. If the cylinder is closed
+    /// the flat faces at either end of the wedge are also added.
+    #[must_use]
+    pub fn with_wedge(mut self, minimum: Angle, maximum: Angle) -> Self {
+        self.wedge = Wedge::new(minimum, maximum);
+
+        self
+    }
+
+    /// The u and v values of a point on the cylinder. On the sides u increases
+    /// around the y axis and v repeats every unit along it, the caps use a
+    /// planar mapping of x and z across the unit disc.
+    #[must_use]
+    pub fn u_v_at(&self, point: &Point) -> (f64, f64) {
+        let distance = point.x.powi(2) + point.z.powi(2);
+
+        if self.is_disc()
+            || distance < 1.0
+                && (point.y >= self.maximum - f64::EPSILON
+                    || point.y <= self.minimum + f64::EPSILON)
+        {
+            return (point.x.mul_add(0.5, 0.5), point.z.mul_add(0.5, 0.5));
+        }
+
+        let theta = point.x.atan2(point.z);
+
+        (1.0 - (theta / TAU + 0.5), point.y.rem_euclid(1.0))
+    }
+
     #[must_use]
     fn intersect_caps(&self, ray: &Ray, mut list: TList) -> Option<TList> {
         let check_cap = |t: f64| {
-            let x = ray.origin.x + t * ray.direction.x;
-            let z = ray.origin.z + t * ray.direction.z;
+            let point = ray.position(t);
 
-            x.powi(2) + z.powi(2) <= 1.0
+            point.x.powi(2) + point.z.powi(2) <= 1.0
+                && self.wedge.contains(&point)
         };
 
-        if self.closed && approx_ne!(ray.direction.y, 0.0) {
+        if approx_ne!(ray.direction.y, 0.0) && self.is_disc() {
+            // Both caps of a disc lie in the same plane so it is only hit
+            // once.
+            let t = (self.minimum - ray.origin.y) / ray.direction.y;
+
+            if (self.cap_minimum || self.cap_maximum) && check_cap(t) {
+                list.push(TValues::new(t));
+            }
+        } else if approx_ne!(ray.direction.y, 0.0) {
             let t = (self.minimum - ray.origin.y) / ray.direction.y;
 
-            if check_cap(t) {
+            if self.cap_minimum && check_cap(t) {
                 list.push(TValues::new(t));
             }
 
             let t = (self.maximum - ray.origin.y) / ray.direction.y;
 
-            if check_cap(t) {
+            if self.cap_maximum && check_cap(t) {
                 list.push(TValues::new(t));
             }
         }
 
+        if self.is_closed() {
+            self.wedge.intersect_faces(
+                ray,
+                self.minimum,
+                self.maximum,
+                |_| 1.0,
+                &mut list,
+            );
+        }
+
         if list.is_empty() {
             return None;
         };
@@ -82,12 +175,18 @@ impl Intersectable for Cylinder {
         let t1 = (-b + discriminant) / a;
 
         let y0 = ray.origin.y + t0 * ray.direction.y;
-        if self.minimum < y0 && y0 < self.maximum {
+        if self.minimum < y0
+            && y0 < self.maximum
+            && self.wedge.contains(&ray.position(t0))
+        {
             list.push(TValues::new(t0));
         }
 
         let y1 = ray.origin.y + t1 * ray.direction.y;
-        if self.minimum < y1 && y1 < self.maximum {
+        if self.minimum < y1
+            && y1 < self.maximum
+            && self.wedge.contains(&ray.position(t1))
+        {
             list.push(TValues::new(t1));
         }
 
@@ -96,6 +195,10 @@ impl Intersectable for Cylinder {
 
     #[must_use]
     fn normal_at(&self, point: &Point, _intersection: &Intersection) -> Vector {
+        if self.is_disc() {
+            return Vector::y_axis();
+        }
+
         let distance = point.x.powi(2) + point.z.powi(2);
 
         if distance < 1.0 && point.y >= self.maximum - EPSILON {
@@ -104,6 +207,14 @@ impl Intersectable for Cylinder {
             return -Vector::y_axis();
         }
 
+        if self.is_closed() {
+            if let Some(normal) =
+                self.wedge.face_normal(point, (distance.sqrt() - 1.0).abs())
+            {
+                return normal;
+            }
+        }
+
         Vector::new(point.x, 0.0, point.z)
     }
 }
@@ -117,14 +228,31 @@ impl Bounded for Cylinder {
     }
 }
 
-impl_approx_eq!(&Cylinder { eq closed, minimum, maximum });
+impl_approx_eq!(
+    &Cylinder {
+        eq cap_minimum,
+        eq cap_maximum,
+        minimum,
+        maximum,
+        wedge
+    }
+);
 
 #[cfg(test)]
 mod tests {
-    use std::f64::INFINITY;
+    use std::f64::{
+        consts::{FRAC_1_SQRT_2, FRAC_PI_2},
+        INFINITY,
+    };
+
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
 
     use super::*;
-    use crate::{math::float::*, Object};
+    use crate::{
+        math::{float::*, Transformation},
+        AntiAliasing, Camera, Colour, Light, Object, Output, World,
+    };
 
     #[test]
     fn a_ray_misses_a_cylinder() {
@@ -247,6 +375,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_cylinder_capped_only_on_top_is_open_at_the_bottom() {
+        let c = Cylinder::new(1.0, 2.0, false).with_caps(false, true);
+
+        let i = c
+            .intersect(&Ray::new(Point::new(0.0, 1.5, 0.0), -Vector::y_axis()))
+            .unwrap();
+
+        assert_eq!(i.len(), 1);
+        assert_approx_eq!(i[0].t, -0.5);
+
+        let i = c
+            .intersect(&Ray::new(Point::new(0.0, 1.5, 0.0), Vector::y_axis()))
+            .unwrap();
+
+        assert_eq!(i.len(), 1);
+        assert_approx_eq!(i[0].t, 0.5);
+
+        assert_approx_ne!(&c, &Cylinder::new(1.0, 2.0, true));
+        assert_approx_ne!(&c, &Cylinder::new(1.0, 2.0, false));
+    }
+
+    #[test]
+    fn a_cylinder_with_no_height_is_a_disc() {
+        let c = Cylinder::new(1.0, 1.0, true);
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 0.0);
+
+        for direction in [Vector::y_axis(), -Vector::y_axis()] {
+            let r = Ray::new(
+                Point::new(0.5, 1.0, 0.0) - direction * 2.0,
+                direction,
+            );
+
+            let hits = c.intersect(&r).unwrap();
+
+            assert_eq!(hits.len(), 1);
+            assert_approx_eq!(hits[0].t, 2.0);
+            assert_approx_eq!(
+                c.normal_at(&r.position(hits[0].t), &i),
+                Vector::y_axis()
+            );
+        }
+
+        // Either cap is enough for a disc but rays in its plane never hit it.
+        let r = Ray::new(Point::new(0.5, 3.0, 0.0), -Vector::y_axis());
+
+        assert_eq!(c.with_caps(false, true).intersect(&r).unwrap().len(), 1);
+        assert_eq!(c.with_caps(true, false).intersect(&r).unwrap().len(), 1);
+        assert!(c.with_caps(false, false).intersect(&r).is_none());
+        assert!(c
+            .intersect(&Ray::new(Point::new(1.5, 3.0, 0.0), -Vector::y_axis()))
+            .is_none());
+        assert!(c
+            .intersect(&Ray::new(Point::new(-5.0, 1.0, 0.0), Vector::x_axis()))
+            .is_none());
+    }
+
+    #[test]
+    fn rendering_a_cylinder_with_no_height() {
+        let mut w = World::new();
+        w.add_object(Object::cylinder_builder(0.0, 0.0, true).build());
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+
+        let render = |from| {
+            Camera::new(
+                11,
+                11,
+                Angle(FRAC_PI_2),
+                Transformation::view_transformation(
+                    from,
+                    Point::origin(),
+                    Vector::y_axis(),
+                ),
+            )
+            .render(
+                &w,
+                5,
+                true,
+                AntiAliasing::Edges,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0),
+            )
+            .unwrap()
+        };
+
+        for from in [Point::new(0.0, 0.0, -5.0), Point::new(0.0, 3.0, -3.0)] {
+            assert!(render(from).pixels().all(Colour::is_finite));
+        }
+
+        assert_approx_ne!(
+            render(Point::new(0.0, 3.0, -3.0)).get_pixel(5, 5),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The minimum of a cylinder can not be greater than its maximum"
+    )]
+    fn a_cylinder_with_its_minimum_above_its_maximum() {
+        let _ = Cylinder::new(2.0, 1.0, true);
+    }
+
     #[test]
     fn normal_vector_on_a_cylinder() {
         let c = Cylinder::new(-INFINITY, INFINITY, false);
@@ -318,6 +554,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_u_and_v_values_of_a_point_on_a_cylinder() {
+        let c = Cylinder::new(-1.0, 1.0, true);
+
+        let (u, v) = c.u_v_at(&Point::new(1.0, 0.25, 0.0));
+        assert_approx_eq!(u, 0.25);
+        assert_approx_eq!(v, 0.25);
+
+        let (u, v) = c.u_v_at(&Point::new(0.5, 1.0, 0.0));
+        assert_approx_eq!(u, 0.75);
+        assert_approx_eq!(v, 0.5);
+    }
+
+    #[test]
+    fn a_wedge_of_a_cylinder_misses_rays_aimed_at_the_removed_side() {
+        let c = Cylinder::new(-1.0, 1.0, false)
+            .with_wedge(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert!(c
+            .intersect(&Ray::new(Point::new(-0.5, 0.0, -5.0), Vector::z_axis()))
+            .is_none());
+        assert!(c
+            .intersect(&Ray::new(Point::new(5.0, 0.0, -0.5), -Vector::x_axis()))
+            .is_none());
+
+        let l = c
+            .intersect(&Ray::new(Point::new(0.5, 0.0, -5.0), Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 5.0 + 0.75_f64.sqrt());
+    }
+
+    #[test]
+    fn intersecting_the_faces_of_a_closed_cylinder_wedge() {
+        let c = Cylinder::new(-1.0, 1.0, true)
+            .with_wedge(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert!(c
+            .intersect(&Ray::new(Point::new(-0.5, 0.0, -5.0), Vector::z_axis()))
+            .is_none());
+
+        let l = c
+            .intersect(&Ray::new(Point::new(0.5, 0.0, -5.0), Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 2);
+        assert_approx_eq!(l[0].t, 5.0 + 0.75_f64.sqrt());
+        assert_approx_eq!(l[1].t, 5.0);
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 5.0);
+
+        assert_approx_eq!(
+            c.normal_at(&Point::new(0.5, 0.0, 0.0), &i),
+            -Vector::z_axis()
+        );
+        assert_approx_eq!(
+            c.normal_at(&Point::new(0.0, 0.5, 0.5), &i),
+            -Vector::x_axis()
+        );
+        assert_approx_eq!(
+            c.normal_at(&Point::new(FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2), &i),
+            Vector::new(FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2)
+        );
+        assert_approx_eq!(
+            c.normal_at(&Point::new(0.5, 1.0, 0.5), &i),
+            Vector::y_axis()
+        );
+    }
+
     #[test]
     fn comparing_cylinders() {
         let c1 = Cylinder::new(0.0, 1.0, true);
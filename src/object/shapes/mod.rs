@@ -1,28 +1,32 @@
 mod cone;
 mod cube;
+mod custom;
 mod cylinder;
 mod intersectable;
 mod plane;
+mod rounded_box;
 mod sphere;
 #[cfg(test)]
 pub(super) mod test;
 mod triangle;
+mod wedge;
 
 use enum_dispatch::enum_dispatch;
 use float_cmp::{ApproxEq, F64Margin};
 use paste::paste;
 
-pub use self::intersectable::Intersectable;
 #[cfg(test)]
 use self::test::Test;
 use self::{
-    cone::Cone, cube::Cube, cylinder::Cylinder, plane::Plane, sphere::Sphere,
-    triangle::Triangle,
+    cone::Cone, cube::Cube, custom::Custom, cylinder::Cylinder, plane::Plane,
+    rounded_box::RoundedBox, sphere::Sphere, triangle::Triangle,
 };
-use super::{Bounded, BoundingBox};
+pub use self::{custom::CustomShape, intersectable::Intersectable};
+use super::{Bounded, BoundingBox, Shading};
 use crate::{
     intersection::{Intersection, TList},
-    math::{Point, Ray, Vector},
+    math::{Angle, Point, Ray, Vector},
+    Colour,
 };
 
 /// `Shapes` is the list of the various geometries that can be rendered.
@@ -31,8 +35,10 @@ use crate::{
 pub enum Shapes {
     Cone(Cone),
     Cube(Cube),
+    Custom(Custom),
     Cylinder(Cylinder),
     Plane(Plane),
+    RoundedBox(RoundedBox),
     Sphere(Sphere),
     #[cfg(test)]
     Test(Test),
@@ -43,7 +49,7 @@ macro_rules! add_new_fn {
     ($shape:ident($($args:ident : $ty:ty $(,)?)*)) => {
         paste! {
             #[must_use]
-            pub fn [<new_ $shape:lower>]($($args:$ty,)*) -> Shapes {
+            pub fn [<new_ $shape:snake>]($($args:$ty,)*) -> Shapes {
                 Self::$shape($shape::new($($args,)*))
             }
         }
@@ -52,9 +58,46 @@ macro_rules! add_new_fn {
 
 impl Shapes {
     add_new_fn!(Cone(minimum: f64, maximum: f64, closed: bool));
+
+    /// Create a cone limited to a wedge of angles around the y axis, see
+    /// `Cone::with_wedge`.
+    #[must_use]
+    pub fn new_cone_wedge(
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+        from: Angle,
+        to: Angle,
+    ) -> Self {
+        Self::Cone(Cone::new(minimum, maximum, closed).with_wedge(from, to))
+    }
+
     add_new_fn!(Cube());
+
+    #[must_use]
+    pub fn new_custom<S: CustomShape + 'static>(shape: S) -> Self {
+        Self::Custom(Custom::new(shape))
+    }
+
     add_new_fn!(Cylinder(minimum: f64, maximum: f64, closed: bool));
+
+    /// Create a cylinder limited to a wedge of angles around the y axis, see
+    /// `Cylinder::with_wedge`.
+    #[must_use]
+    pub fn new_cylinder_wedge(
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+        from: Angle,
+        to: Angle,
+    ) -> Self {
+        Self::Cylinder(
+            Cylinder::new(minimum, maximum, closed).with_wedge(from, to),
+        )
+    }
+
     add_new_fn!(Plane());
+    add_new_fn!(RoundedBox(radius: f64));
     add_new_fn!(Sphere());
     #[cfg(test)]
     add_new_fn!(Test());
@@ -67,6 +110,154 @@ impl Shapes {
         normal3: Vector,
     ));
 
+    #[must_use]
+    pub fn new_fixed_winding_triangle(
+        point1: Point,
+        point2: Point,
+        point3: Point,
+        normal1: Vector,
+        normal2: Vector,
+        normal3: Vector,
+    ) -> Self {
+        Self::Triangle(
+            Triangle::new(point1, point2, point3, normal1, normal2, normal3)
+                .with_fixed_winding(),
+        )
+    }
+
+    /// A short lower case name for the kind of shape, used when summarising
+    /// scenes.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Cone(_) => "cone",
+            Self::Cube(_) => "cube",
+            Self::Custom(_) => "custom",
+            Self::Cylinder(_) => "cylinder",
+            Self::Plane(_) => "plane",
+            Self::RoundedBox(_) => "rounded-box",
+            Self::Sphere(_) => "sphere",
+            #[cfg(test)]
+            Self::Test(_) => "test",
+            Self::Triangle(_) => "triangle",
+        }
+    }
+
+    /// Give a triangle a colour at each vertex, see `Triangle::with_colours`.
+    /// Other shapes have no vertices and are returned unchanged.
+    #[must_use]
+    pub fn with_vertex_colours(self, colours: [Colour; 3]) -> Self {
+        match self {
+            Self::Triangle(triangle) => {
+                Self::Triangle(triangle.with_colours(colours))
+            }
+            _ => self,
+        }
+    }
+
+    /// Give a triangle texture coordinates at each vertex, see
+    /// `Triangle::with_uvs`. Other shapes are returned unchanged.
+    #[must_use]
+    pub fn with_uvs(self, [uv1, uv2, uv3]: [(f64, f64); 3]) -> Self {
+        match self {
+            Self::Triangle(triangle) => {
+                Self::Triangle(triangle.with_uvs(uv1, uv2, uv3))
+            }
+            _ => self,
+        }
+    }
+
+    /// Set how triangles are shaded, all other shapes are unchanged.
+    #[must_use]
+    pub fn with_shading(self, shading: Shading) -> Self {
+        match self {
+            Self::Triangle(triangle) => {
+                Self::Triangle(triangle.with_shading(shading))
+            }
+            _ => self,
+        }
+    }
+
+    /// Set whether each end of a cone or cylinder is capped, all other shapes
+    /// are unchanged.
+    #[must_use]
+    pub fn with_caps(self, minimum: bool, maximum: bool) -> Self {
+        match self {
+            Self::Cone(cone) => Self::Cone(cone.with_caps(minimum, maximum)),
+            Self::Cylinder(cylinder) => {
+                Self::Cylinder(cylinder.with_caps(minimum, maximum))
+            }
+            _ => self,
+        }
+    }
+
+    /// The vertex colour at the given u and v values for triangles with vertex
+    /// colours, `None` for all other shapes.
+    #[must_use]
+    pub fn vertex_colour(&self, u_v: Option<(f64, f64)>) -> Option<Colour> {
+        match (self, u_v) {
+            (Self::Triangle(triangle), Some((u, v))) => {
+                triangle.colour_at(u, v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Transform a normal in tangent space into object space for triangles
+    /// with texture coordinates, `None` for all other shapes.
+    #[must_use]
+    pub fn tangent_to_object(
+        &self,
+        tangent_normal: &Vector,
+        normal: &Vector,
+    ) -> Option<Vector> {
+        match self {
+            Self::Triangle(triangle) => {
+                triangle.tangent_to_object(tangent_normal, normal)
+            }
+            _ => None,
+        }
+    }
+
+    /// The u and v values of a point in object space on a sphere, cylinder or
+    /// plane, `None` for all other shapes. Triangles give their u and v when
+    /// intersected instead.
+    #[must_use]
+    pub fn u_v_at(&self, point: &Point) -> Option<(f64, f64)> {
+        match self {
+            Self::Cylinder(cylinder) => Some(cylinder.u_v_at(point)),
+            Self::Plane(_) => {
+                Some((point.x.rem_euclid(1.0), point.z.rem_euclid(1.0)))
+            }
+            Self::Sphere(_) => Some(Sphere::u_v_at(point)),
+            _ => None,
+        }
+    }
+
+    /// How close the given u and v values are to the nearest edge of a
+    /// triangle, as the smallest of its three barycentric coordinates. `None`
+    /// for all other shapes.
+    #[must_use]
+    pub fn edge_distance(&self, u_v: Option<(f64, f64)>) -> Option<f64> {
+        match (self, u_v) {
+            (Self::Triangle(_), Some((u, v))) => {
+                Some(u.min(v).min(1.0 - u - v))
+            }
+            _ => None,
+        }
+    }
+
+    /// A point and normal in object space describing the plane a flat shape
+    /// lies in, `None` for shapes that are not flat.
+    #[must_use]
+    pub fn flat_surface(&self) -> Option<(Point, Vector)> {
+        match self {
+            Self::Plane(_) => Some((Point::origin(), Vector::y_axis())),
+            Self::Triangle(triangle) => Some(triangle.plane()),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn new_flat_triangle(
         point1: Point,
@@ -88,9 +279,13 @@ impl ApproxEq for &Shapes {
                 lhs.approx_eq(rhs, margin)
             }
             (Shapes::Cube(_), Shapes::Cube(_)) => true,
+            (Shapes::Custom(lhs), Shapes::Custom(rhs)) => lhs.ptr_eq(rhs),
             (Shapes::Cylinder(lhs), Shapes::Cylinder(rhs)) => {
                 lhs.approx_eq(rhs, margin)
             }
+            (Shapes::RoundedBox(lhs), Shapes::RoundedBox(rhs)) => {
+                lhs.approx_eq(rhs, margin)
+            }
             (Shapes::Sphere(_), Shapes::Sphere(_)) => true,
             (Shapes::Plane(_), Shapes::Plane(_)) => true,
             #[cfg(test)]
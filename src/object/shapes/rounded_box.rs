@@ -0,0 +1,378 @@
+use super::{cube::Cube, Bounded, BoundingBox, Intersectable};
+use crate::{
+    intersection::{Intersection, TList},
+    math::{
+        float::{approx_eq, impl_approx_eq},
+        Point, Ray, Vector,
+    },
+};
+
+/// A `RoundedBox` is a `Cube` of size 2 (-1.0..1.0) on each axis with its edges
+/// and corners rounded off to the given radius. It is the smaller box left by
+/// pulling each face in by the radius, swept by a sphere of that radius, so can
+/// be split into three slabs, a cylinder along each edge and a sphere on each
+/// corner.
+#[derive(Clone, Copy, Debug)]
+pub struct RoundedBox {
+    radius: f64,
+}
+
+impl RoundedBox {
+    /// Create a rounded box, the radius is clamped between 0.0, giving a
+    /// `Cube`, and 1.0, giving a `Sphere`.
+    #[must_use]
+    pub fn new(radius: f64) -> Self {
+        Self { radius: radius.clamp(0.0, 1.0) }
+    }
+
+    /// The half size of the box that is swept by the sphere.
+    #[must_use]
+    fn inner(self) -> f64 {
+        1.0 - self.radius
+    }
+
+    /// The range of t values within the axis aligned box of the given half
+    /// sizes.
+    #[must_use]
+    fn box_range(
+        origin: [f64; 3],
+        direction: [f64; 3],
+        size: [f64; 3],
+    ) -> Option<(f64, f64)> {
+        let mut min = f64::NEG_INFINITY;
+        let mut max = f64::INFINITY;
+
+        for axis in 0..3 {
+            let (axis_min, axis_max) = BoundingBox::check_axis(
+                origin[axis],
+                direction[axis],
+                -size[axis],
+                size[axis],
+            );
+
+            min = min.max(axis_min);
+            max = max.min(axis_max);
+        }
+
+        (min <= max).then_some((min, max))
+    }
+
+    /// Solve a quadratic for the range of t values between its roots.
+    #[must_use]
+    fn quadratic_range(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
+        let discriminant = b.powi(2) - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let discriminant = discriminant.sqrt();
+        let a = 2.0 * a;
+
+        Some(((-b - discriminant) / a, (-b + discriminant) / a))
+    }
+}
+
+impl Intersectable for RoundedBox {
+    fn intersect(&self, ray: &Ray) -> Option<TList> {
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+
+        let inner = self.inner();
+        let radius = self.radius;
+
+        // The rounded box is convex so a ray passes through it at most once,
+        // and each of the pieces it is made from lies inside it. The range
+        // through the whole box is then from the first entry into any piece to
+        // the last exit from any piece.
+        let mut range: Option<(f64, f64)> = None;
+        let mut add = |piece: Option<(f64, f64)>| {
+            if let Some((t1, t2)) = piece {
+                range =
+                    Some(range.map_or((t1, t2), |(min, max)| {
+                        (min.min(t1), max.max(t2))
+                    }));
+            }
+        };
+
+        for axis in 0..3 {
+            let mut size = [inner; 3];
+            size[axis] = 1.0;
+
+            add(Self::box_range(origin, direction, size));
+        }
+
+        if !approx_eq!(radius, 0.0) {
+            let signs = [-1.0, 1.0];
+
+            for x in signs {
+                for y in signs {
+                    for z in signs {
+                        let corner =
+                            Point::new(x * inner, y * inner, z * inner);
+                        let to_ray = ray.origin - corner;
+
+                        add(Self::quadratic_range(
+                            ray.direction.dot(&ray.direction),
+                            2.0 * ray.direction.dot(&to_ray),
+                            to_ray.dot(&to_ray) - radius.powi(2),
+                        ));
+                    }
+                }
+            }
+
+            for axis in 0..3 {
+                let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+
+                let Some((along_min, along_max)) = Self::box_range(
+                    [origin[axis], 0.0, 0.0],
+                    [direction[axis], 0.0, 0.0],
+                    [inner, 1.0, 1.0],
+                ) else {
+                    continue;
+                };
+
+                for sign_first in signs {
+                    for sign_second in signs {
+                        let to_ray_first = origin[first] - sign_first * inner;
+                        let to_ray_second =
+                            origin[second] - sign_second * inner;
+
+                        let a = direction[first].powi(2)
+                            + direction[second].powi(2);
+                        let c = to_ray_first.powi(2) + to_ray_second.powi(2)
+                            - radius.powi(2);
+
+                        // A ray parallel to the edge is either inside the
+                        // cylinder along its whole length or misses it.
+                        let around = if approx_eq!(a, 0.0) {
+                            (c <= 0.0)
+                                .then_some((f64::NEG_INFINITY, f64::INFINITY))
+                        } else {
+                            Self::quadratic_range(
+                                a,
+                                2.0 * (direction[first] * to_ray_first
+                                    + direction[second] * to_ray_second),
+                                c,
+                            )
+                        };
+
+                        add(around.and_then(|(min, max)| {
+                            let min = min.max(along_min);
+                            let max = max.min(along_max);
+
+                            (min <= max).then_some((min, max))
+                        }));
+                    }
+                }
+            }
+        }
+
+        range.map(|(min, max)| TList::from(vec![min, max]))
+    }
+
+    fn normal_at(&self, point: &Point, intersection: &Intersection) -> Vector {
+        if approx_eq!(self.radius, 0.0) {
+            return Cube::new().normal_at(point, intersection);
+        }
+
+        // The normal points away from the nearest point on the inner box.
+        let inner = self.inner();
+
+        Vector::new(
+            point.x - point.x.clamp(-inner, inner),
+            point.y - point.y.clamp(-inner, inner),
+            point.z - point.z.clamp(-inner, inner),
+        )
+    }
+}
+
+impl Bounded for RoundedBox {
+    fn bounding_box(&self) -> BoundingBox {
+        Cube::new().bounding_box()
+    }
+}
+
+impl_approx_eq!(&RoundedBox { radius });
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{math::float::*, Object};
+
+    #[test]
+    fn creating_a_rounded_box() {
+        assert_approx_eq!(RoundedBox::new(0.3).radius, 0.3);
+        assert_approx_eq!(RoundedBox::new(-0.5).radius, 0.0);
+        assert_approx_eq!(RoundedBox::new(2.0).radius, 1.0);
+    }
+
+    #[test]
+    fn a_rounded_box_with_no_radius_is_a_cube() {
+        let rounded = RoundedBox::new(0.0);
+        let cube = Cube::new();
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 0.0);
+
+        let rays = [
+            Ray::new(Point::new(5.0, 0.5, 0.0), -Vector::x_axis()),
+            Ray::new(Point::new(0.5, -5.0, 0.0), Vector::y_axis()),
+            Ray::new(Point::new(0.0, 0.5, 0.0), Vector::z_axis()),
+            Ray::new(
+                Point::new(5.0, 5.0, 5.0),
+                -Vector::new(1.0, 1.0, 1.0).normalise(),
+            ),
+            Ray::new(
+                Point::new(-2.0, 0.0, 0.0),
+                Vector::new(0.267_3, 0.534_5, 0.801_8),
+            ),
+            Ray::new(Point::new(2.0, 2.0, 0.0), -Vector::x_axis()),
+        ];
+
+        for r in rays {
+            match (rounded.intersect(&r), cube.intersect(&r)) {
+                (Some(lhs), Some(rhs)) => {
+                    assert_eq!(lhs.len(), rhs.len());
+                    assert_approx_eq!(lhs[0].t, rhs[0].t);
+                    assert_approx_eq!(lhs[1].t, rhs[1].t);
+
+                    let p = r.position(lhs[0].t);
+
+                    assert_approx_eq!(
+                        rounded.normal_at(&p, &i),
+                        cube.normal_at(&p, &i)
+                    );
+                }
+                (None, None) => {}
+                _ => panic!("Rounded box and cube disagree for {r:?}"),
+            }
+        }
+
+        assert_approx_eq!(rounded.bounding_box(), cube.bounding_box());
+    }
+
+    #[test]
+    fn a_ray_intersects_a_rounded_box() {
+        let b = RoundedBox::new(0.5);
+        let sqrt_3 = 3.0_f64.sqrt();
+
+        let test = |r, t1, t2| {
+            let l = b.intersect(&r).unwrap();
+
+            assert_approx_eq!(l[0].t, t1, epsilon = 0.000_01);
+            assert_approx_eq!(l[1].t, t2, epsilon = 0.000_01);
+        };
+
+        // The flat faces are unchanged.
+        test(Ray::new(Point::new(5.0, 0.2, 0.0), -Vector::x_axis()), 4.0, 6.0);
+        test(Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()), 4.0, 6.0);
+
+        // Across an edge the ray passes through the cylinder along it.
+        test(
+            Ray::new(Point::new(5.0, 0.0, 0.5 + 0.5 * 0.6), -Vector::x_axis()),
+            4.5 - 0.5 * 0.8,
+            5.5 + 0.5 * 0.8,
+        );
+
+        // Along the diagonal the ray hits the corner spheres.
+        let corner = 0.5 * sqrt_3 + 0.5;
+        test(
+            Ray::new(
+                Point::new(5.0, 5.0, 5.0),
+                -Vector::new(1.0, 1.0, 1.0).normalise(),
+            ),
+            5.0 * sqrt_3 - corner,
+            5.0 * sqrt_3 + corner,
+        );
+
+        // A ray that would clip a corner of the cube misses the rounded box.
+        assert!(Cube::new()
+            .intersect(&Ray::new(
+                Point::new(0.95, 5.0, 0.95),
+                -Vector::y_axis()
+            ))
+            .is_some());
+        assert!(b
+            .intersect(&Ray::new(
+                Point::new(0.95, 5.0, 0.95),
+                -Vector::y_axis()
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn the_normal_on_a_rounded_box() {
+        let b = RoundedBox::new(0.5);
+        let sqrt_3 = 3.0_f64.sqrt();
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new(&o, 0.0);
+
+        assert_approx_eq!(
+            b.normal_at(&Point::new(1.0, 0.2, -0.4), &i).normalise(),
+            Vector::x_axis()
+        );
+        assert_approx_eq!(
+            b.normal_at(&Point::new(0.1, -1.0, 0.3), &i).normalise(),
+            -Vector::y_axis()
+        );
+
+        let corner = 0.5 + 0.5 / sqrt_3;
+        assert_approx_eq!(
+            b.normal_at(&Point::new(corner, corner, corner), &i).normalise(),
+            Vector::new(1.0, 1.0, 1.0).normalise()
+        );
+
+        // Moving around an edge the normal turns smoothly from one face to the
+        // next.
+        let steps = 100;
+        let mut previous = Vector::z_axis();
+
+        for step in 0..=steps {
+            let angle = FRAC_PI_2 * f64::from(step) / f64::from(steps);
+
+            let point = Point::new(
+                0.5 + 0.5 * angle.sin(),
+                0.1,
+                0.5 + 0.5 * angle.cos(),
+            );
+
+            let normal = b.normal_at(&point, &i).normalise();
+
+            assert_approx_eq!(
+                normal,
+                Vector::new(angle.sin(), 0.0, angle.cos())
+            );
+            assert!(normal.dot(&previous) > 0.999);
+
+            previous = normal;
+        }
+
+        assert_approx_eq!(previous, Vector::x_axis());
+    }
+
+    #[test]
+    fn the_bounding_box_of_a_rounded_box() {
+        assert_approx_eq!(
+            RoundedBox::new(0.25).bounding_box(),
+            BoundingBox::new(
+                Point::new(-1.0, -1.0, -1.0),
+                Point::new(1.0, 1.0, 1.0)
+            )
+        );
+    }
+
+    #[test]
+    fn comparing_rounded_boxes() {
+        let b1 = RoundedBox::new(0.2);
+        let b2 = RoundedBox::new(0.2);
+        let b3 = RoundedBox::new(0.200_1);
+
+        assert_approx_eq!(&b1, &b2);
+
+        assert_approx_ne!(&b1, &b3);
+    }
+}
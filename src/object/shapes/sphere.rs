@@ -1,3 +1,5 @@
+use std::f64::consts::{PI, TAU};
+
 use derive_new::new;
 
 use super::{Bounded, BoundingBox, Intersectable};
@@ -10,6 +12,18 @@ use crate::{
 #[derive(Clone, Copy, Debug, new)]
 pub struct Sphere;
 
+impl Sphere {
+    /// The spherical u and v values of a point on the sphere, u increases
+    /// around the y axis and v from the south to the north pole.
+    #[must_use]
+    pub fn u_v_at(point: &Point) -> (f64, f64) {
+        let theta = point.x.atan2(point.z);
+        let phi = point.y.clamp(-1.0, 1.0).acos();
+
+        (1.0 - (theta / TAU + 0.5), 1.0 - phi / PI)
+    }
+}
+
 impl Intersectable for Sphere {
     #[must_use]
     fn intersect(&self, ray: &Ray) -> Option<TList> {
@@ -162,6 +176,21 @@ mod tests {
         assert_approx_eq!(n, n.normalise());
     }
 
+    #[test]
+    fn the_u_and_v_values_of_a_point_on_a_sphere() {
+        let test = |p, u, v| {
+            let (pu, pv) = Sphere::u_v_at(&p);
+
+            assert_approx_eq!(pu, u);
+            assert_approx_eq!(pv, v);
+        };
+
+        test(Point::new(0.0, 0.0, -1.0), 0.0, 0.5);
+        test(Point::new(0.0, 0.0, 1.0), 0.5, 0.5);
+        test(Point::new(0.0, 1.0, 0.0), 0.5, 1.0);
+        test(Point::new(0.0, -1.0, 0.0), 0.5, 0.0);
+    }
+
     #[test]
     fn the_bounding_box_of_a_sphere() {
         let s = Sphere::new();
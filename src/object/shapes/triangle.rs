@@ -5,10 +5,16 @@ use crate::{
         float::{approx_eq, impl_approx_eq},
         Point, Ray, Vector,
     },
+    Colour, Shading,
 };
 
-/// A `Triangle` is a simple triangle defined by three vertices.
-#[derive(Clone, Copy, Debug)]
+/// How far (as a cosine) an interpolated vertex normal may point away from the
+/// face normal before it is flipped when fixing winding.
+const WINDING_THRESHOLD: f64 = 0.1;
+
+/// A `Triangle` is a simple triangle defined by three vertices. The optional
+/// per-vertex data is boxed to keep the size of `Shapes` down.
+#[derive(Clone, Debug)]
 pub struct Triangle {
     point1: Point,
     point2: Point,
@@ -18,6 +24,10 @@ pub struct Triangle {
     normal1: Vector,
     normal2: Vector,
     normal3: Vector,
+    fix_winding: bool,
+    tangents: Option<Box<(Vector, Vector)>>,
+    colours: Option<Box<[Colour; 3]>>,
+    shading: Shading,
 }
 
 impl Triangle {
@@ -41,7 +51,63 @@ impl Triangle {
     ) -> Self {
         let (edge1, edge2) = Self::calculate_edges(point1, point2, point3);
 
-        Self { point1, point2, point3, edge1, edge2, normal1, normal2, normal3 }
+        Self {
+            point1,
+            point2,
+            point3,
+            edge1,
+            edge2,
+            normal1,
+            normal2,
+            normal3,
+            fix_winding: false,
+            tangents: None,
+            colours: None,
+            shading: Shading::Smooth,
+        }
+    }
+
+    /// Flip any interpolated normal that points into the opposite hemisphere
+    /// to the face normal. This fixes smooth triangles from files where the
+    /// vertex normals disagree with the winding order of the vertices.
+    #[must_use]
+    pub const fn with_fixed_winding(mut self) -> Self {
+        self.fix_winding = true;
+
+        self
+    }
+
+    /// Set how the triangle is shaded, flat shading ignores the vertex normals
+    /// and uses the face normal everywhere.
+    #[must_use]
+    pub const fn with_shading(mut self, shading: Shading) -> Self {
+        self.shading = shading;
+
+        self
+    }
+
+    /// Set a colour for each vertex, these are blended across the triangle and
+    /// replace the colour of the material's pattern, see `colour_at`.
+    #[must_use]
+    pub fn with_colours(mut self, colours: [Colour; 3]) -> Self {
+        self.colours = Some(Box::new(colours));
+
+        self
+    }
+
+    /// The vertex colours blended at the given u and v values, as used to
+    /// interpolate normals, `None` if the triangle has no vertex colours.
+    #[must_use]
+    pub fn colour_at(&self, u: f64, v: f64) -> Option<Colour> {
+        self.colours.as_deref().map(|[colour1, colour2, colour3]| {
+            *colour2 * u + *colour3 * v + *colour1 * (1.0 - u - v)
+        })
+    }
+
+    /// A point on the triangle and the normal of the plane it lies in.
+    #[must_use]
+    pub fn plane(&self) -> (Point, Vector) {
+        (self.point1, self.edge2.cross(&self.edge1).normalise())
     }
 
     #[must_use]
@@ -59,8 +125,64 @@ impl Triangle {
             normal1: normal,
             normal2: normal,
             normal3: normal,
+            fix_winding: false,
+            tangents: None,
+            colours: None,
+            shading: Shading::Smooth,
         }
     }
+
+    /// Set the texture coordinates of each vertex and from these calculate
+    /// the tangent and bitangent of the triangle. These define the tangent
+    /// space used by normal maps, if the texture coordinates are degenerate no
+    /// tangents are calculated.
+    #[must_use]
+    pub fn with_uvs(
+        mut self,
+        uv1: (f64, f64),
+        uv2: (f64, f64),
+        uv3: (f64, f64),
+    ) -> Self {
+        let (du1, dv1) = (uv2.0 - uv1.0, uv2.1 - uv1.1);
+        let (du2, dv2) = (uv3.0 - uv1.0, uv3.1 - uv1.1);
+
+        let determinant = du1 * dv2 - du2 * dv1;
+
+        self.tangents = if approx_eq!(determinant, 0.0) {
+            None
+        } else {
+            let tangent = (self.edge1 * dv2 - self.edge2 * dv1) / determinant;
+            let bitangent = (self.edge2 * du1 - self.edge1 * du2) / determinant;
+
+            Some(Box::new((tangent.normalise(), bitangent.normalise())))
+        };
+
+        self
+    }
+
+    /// The tangent and bitangent of the triangle if texture coordinates have
+    /// been set.
+    #[must_use]
+    pub fn tangents(&self) -> Option<(Vector, Vector)> {
+        self.tangents.as_deref().copied()
+    }
+
+    /// Transform a normal given in tangent space, such as one read from a
+    /// normal map, into object space using the given surface normal. Returns
+    /// `None` if the triangle has no tangents.
+    #[must_use]
+    pub fn tangent_to_object(
+        &self,
+        tangent_normal: &Vector,
+        normal: &Vector,
+    ) -> Option<Vector> {
+        self.tangents().map(|(tangent, bitangent)| {
+            (tangent * tangent_normal.x
+                + bitangent * tangent_normal.y
+                + *normal * tangent_normal.z)
+                .normalise()
+        })
+    }
 }
 
 impl Intersectable for Triangle {
@@ -100,7 +222,31 @@ impl Intersectable for Triangle {
         // The u and v values will always be set for triangles.
         let Some((u, v)) = intersection.u_v else { unreachable!() };
 
-        self.normal2 * u + self.normal3 * v + self.normal1 * (1.0 - u - v)
+        if self.shading == Shading::Flat {
+            return self.edge2.cross(&self.edge1).normalise();
+        }
+
+        if !self.fix_winding {
+            return self.normal2 * u
+                + self.normal3 * v
+                + self.normal1 * (1.0 - u - v);
+        }
+
+        let face_normal = self.edge2.cross(&self.edge1).normalise();
+
+        let fix = |normal: Vector| {
+            if normal.normalise().dot(&face_normal) < -WINDING_THRESHOLD {
+                -normal
+            } else {
+                normal
+            }
+        };
+
+        // Fix each vertex normal before interpolating, otherwise normals that
+        // disagree can cancel out to nothing part way across the face.
+        fix(fix(self.normal2) * u
+            + fix(self.normal3) * v
+            + fix(self.normal1) * (1.0 - u - v))
     }
 }
 
@@ -110,7 +256,8 @@ impl Bounded for Triangle {
     }
 }
 
-// Edges are derived from the points, so no need to check them.
+// Edges and tangents are derived from the points and texture coordinates, so
+// no need to check them.
 impl_approx_eq!(&Triangle {
     point1,
     point2,
@@ -118,12 +265,19 @@ impl_approx_eq!(&Triangle {
     normal1,
     normal2,
     normal3,
+    eq fix_winding,
+    eq shading,
 });
 
 #[cfg(test)]
 mod tests {
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
     use super::*;
-    use crate::{math::float::*, Object};
+    use crate::{
+        math::float::*, object::Updatable, Colour, Light, Object, World,
+    };
 
     fn create_flat_triangle() -> Triangle {
         Triangle::new_flat(
@@ -344,5 +498,252 @@ mod tests {
 
         assert_approx_ne!(t4, &t3);
         assert_approx_ne!(t3, &t4);
+
+        assert_approx_ne!(t4, &t5.clone().with_fixed_winding());
+    }
+
+    #[test]
+    fn blending_vertex_colours_across_a_triangle() {
+        let t = create_flat_triangle();
+
+        assert!(t.colour_at(0.2, 0.3).is_none());
+
+        let t =
+            t.with_colours([Colour::red(), Colour::green(), Colour::blue()]);
+
+        assert_approx_eq!(t.colour_at(0.0, 0.0).unwrap(), Colour::red());
+        assert_approx_eq!(t.colour_at(1.0, 0.0).unwrap(), Colour::green());
+        assert_approx_eq!(t.colour_at(0.0, 1.0).unwrap(), Colour::blue());
+        assert_approx_eq!(
+            t.colour_at(1.0 / 3.0, 1.0 / 3.0).unwrap(),
+            Colour::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn calculating_the_tangents_of_a_triangle() {
+        let t = Triangle::new_flat(
+            Point::origin(),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        );
+
+        assert!(t.tangents().is_none());
+
+        let t = t.with_uvs((0.0, 0.0), (1.0, 0.0), (0.0, 1.0));
+
+        let (tangent, bitangent) = t.tangents().unwrap();
+        assert_approx_eq!(tangent, Vector::x_axis());
+        assert_approx_eq!(bitangent, Vector::y_axis());
+
+        let t = Triangle::new_flat(
+            Point::origin(),
+            Point::new(2.0, 0.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+        )
+        .with_uvs((1.0, 1.0), (0.0, 1.0), (1.0, 0.0));
+
+        let (tangent, bitangent) = t.tangents().unwrap();
+        assert_approx_eq!(tangent, -Vector::x_axis());
+        assert_approx_eq!(bitangent, -Vector::y_axis());
+
+        let t = t.with_uvs((0.0, 0.0), (1.0, 1.0), (2.0, 2.0));
+        assert!(t.tangents().is_none());
+    }
+
+    #[test]
+    fn transforming_a_normal_from_tangent_space() {
+        let t = Triangle::new_flat(
+            Point::origin(),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        );
+
+        let n = Vector::z_axis();
+
+        assert!(t.tangent_to_object(&Vector::z_axis(), &n).is_none());
+
+        let t = t.with_uvs((0.0, 0.0), (1.0, 0.0), (0.0, 1.0));
+
+        assert_approx_eq!(
+            t.tangent_to_object(&Vector::z_axis(), &n).unwrap(),
+            Vector::z_axis()
+        );
+        assert_approx_eq!(
+            t.tangent_to_object(&Vector::new(1.0, 0.0, 1.0), &n).unwrap(),
+            Vector::new(1.0, 0.0, 1.0).normalise()
+        );
+        assert_approx_eq!(
+            t.tangent_to_object(&Vector::new(0.0, -1.0, 1.0), &n).unwrap(),
+            Vector::new(0.0, -1.0, 1.0).normalise()
+        );
+    }
+
+    #[test]
+    fn fixing_the_winding_of_a_smooth_triangle() {
+        let t = Triangle::new(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Vector::z_axis(),
+            -Vector::z_axis(),
+            -Vector::z_axis(),
+        );
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.25, 0.25);
+
+        assert_approx_eq!(
+            t.normal_at(&Point::origin(), &i),
+            Vector::new(0.0, 0.0, 0.0)
+        );
+
+        let f = t.clone().with_fixed_winding();
+
+        assert_approx_eq!(f.normal_at(&Point::origin(), &i), -Vector::z_axis());
+
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.0, 0.0);
+        assert_approx_eq!(t.normal_at(&Point::origin(), &i), Vector::z_axis());
+        assert_approx_eq!(f.normal_at(&Point::origin(), &i), -Vector::z_axis());
+
+        let t = create_triangle().with_fixed_winding();
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.45, 0.25);
+
+        assert_approx_eq!(
+            t.normal_at(&Point::origin(), &i).normalise(),
+            Vector::new(-0.554_7, 0.832_05, 0.0),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_an_inconsistently_wound_smooth_triangle() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.5, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(
+            Object::fixed_winding_triangle_builder(
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(-1.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Vector::z_axis(),
+                -Vector::z_axis(),
+                -Vector::z_axis(),
+            )
+            .build(),
+        );
+
+        let r = Ray::new(Point::new(0.0, 0.5, -5.0), Vector::z_axis());
+        let c = w.colour_at(&r, 5, &mut Xoshiro256PlusPlus::seed_from_u64(0));
+
+        assert_approx_eq!(c, Colour::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn flat_shading_a_smooth_triangle() {
+        let t = create_triangle();
+
+        let o = Object::test_builder().build();
+        let i = Intersection::new_with_u_v(&o, 1.0, 0.45, 0.25);
+
+        assert_approx_eq!(
+            t.clone()
+                .with_shading(Shading::Flat)
+                .normal_at(&Point::origin(), &i),
+            -Vector::z_axis()
+        );
+        assert_approx_eq!(
+            t.clone()
+                .with_shading(Shading::Smooth)
+                .normal_at(&Point::origin(), &i)
+                .normalise(),
+            Vector::new(-0.554_7, 0.832_05, 0.0),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_a_mesh_with_flat_and_smooth_shading() {
+        let mesh = || {
+            Object::group_builder()
+                .set_objects(vec![Object::triangle_builder(
+                    Point::new(0.0, 1.0, 0.0),
+                    Point::new(-1.0, 0.0, 0.0),
+                    Point::new(1.0, 0.0, 0.0),
+                    Vector::new(0.0, 0.6, -0.8),
+                    Vector::new(-0.6, 0.0, -0.8),
+                    Vector::new(0.6, 0.0, -0.8),
+                )
+                .build()])
+                .build()
+        };
+
+        let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::z_axis());
+
+        let normal = |shading| {
+            let mut o = mesh();
+            o.update_shading(shading);
+
+            let l = o.intersect(&r).unwrap();
+
+            l[0].prepare_computations(&r, &l).normal
+        };
+
+        let smooth = normal(Shading::Smooth);
+        let flat = normal(Shading::Flat);
+
+        assert_approx_eq!(flat, -Vector::z_axis());
+        assert_approx_ne!(smooth, flat);
+        assert_approx_eq!(
+            smooth,
+            Vector::new(-0.12, 0.18, -0.8).normalise(),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_a_triangle_with_vertex_colours() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.0, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(
+            Object::flat_triangle_builder(
+                Point::new(0.0, 1.0, 0.0),
+                Point::new(-1.0, -1.0, 0.0),
+                Point::new(1.0, -1.0, 0.0),
+            )
+            .material(
+                crate::Material::builder()
+                    .pattern(Colour::white().into())
+                    .ambient(1.0)
+                    .diffuse(0.0)
+                    .specular(0.0)
+                    .build(),
+            )
+            .build()
+            .with_vertex_colours([
+                Colour::red(),
+                Colour::green(),
+                Colour::blue(),
+            ]),
+        );
+
+        // The centroid is an equal blend of every vertex colour, replacing the
+        // white of the material.
+        let r = Ray::new(Point::new(0.0, -1.0 / 3.0, -5.0), Vector::z_axis());
+        let c = w.colour_at(&r, 5, &mut Xoshiro256PlusPlus::seed_from_u64(0));
+
+        assert_approx_eq!(c, Colour::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0));
+
+        let r = Ray::new(Point::new(0.0, 0.99, -5.0), Vector::z_axis());
+        let c = w.colour_at(&r, 5, &mut Xoshiro256PlusPlus::seed_from_u64(0));
+
+        assert!(c.red > 0.95 && c.green < 0.05 && c.blue < 0.05);
     }
 }
@@ -0,0 +1,193 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    intersection::{TList, TValues},
+    math::{
+        float::{approx_eq, impl_approx_eq},
+        Angle, Point, Ray, Vector,
+    },
+};
+
+/// A `Wedge` limits a shape revolved around the y axis to the angles between
+/// `minimum` and `maximum`, measured from the x axis towards the z axis. When
+/// the shape is closed the two flat faces of the wedge are also solid.
+#[derive(Clone, Copy, Debug)]
+pub struct Wedge {
+    minimum: f64,
+    maximum: f64,
+}
+
+impl Wedge {
+    #[must_use]
+    pub fn new(minimum: Angle, maximum: Angle) -> Self {
+        Self { minimum: minimum.0, maximum: maximum.0 }
+    }
+
+    #[must_use]
+    pub const fn full() -> Self {
+        Self { minimum: 0.0, maximum: TAU }
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.maximum - self.minimum >= TAU
+    }
+
+    /// Check if the point lies within the angles covered by the wedge.
+    #[must_use]
+    pub fn contains(&self, point: &Point) -> bool {
+        if self.is_full() {
+            return true;
+        }
+
+        let angle = point.z.atan2(point.x);
+
+        (angle - self.minimum).rem_euclid(TAU) <= self.maximum - self.minimum
+    }
+
+    /// Intersect the flat faces at either end of the wedge, `radius` gives the
+    /// radius of the shape at a given y value and the faces extend between
+    /// `minimum` and `maximum` in y.
+    pub fn intersect_faces<F: Fn(f64) -> f64>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        radius: F,
+        list: &mut TList,
+    ) {
+        if self.is_full() {
+            return;
+        }
+
+        for angle in [self.minimum, self.maximum] {
+            let (sin, cos) = angle.sin_cos();
+
+            let denominator = cos * ray.direction.z - sin * ray.direction.x;
+
+            if approx_eq!(denominator, 0.0) {
+                continue;
+            }
+
+            let t = (sin * ray.origin.x - cos * ray.origin.z) / denominator;
+
+            let point = ray.position(t);
+            let distance = point.x * cos + point.z * sin;
+
+            if distance >= 0.0
+                && distance <= radius(point.y)
+                && minimum < point.y
+                && point.y < maximum
+            {
+                list.push(TValues::new(t));
+            }
+        }
+    }
+
+    /// Find the outward normal of the face of the wedge nearest to the point,
+    /// if it is closer than `side_distance`, the distance to the curved side
+    /// of the shape.
+    #[must_use]
+    pub fn face_normal(
+        &self,
+        point: &Point,
+        side_distance: f64,
+    ) -> Option<Vector> {
+        if self.is_full() {
+            return None;
+        }
+
+        let normal = |angle: f64| Vector::new(-angle.sin(), 0.0, angle.cos());
+
+        [
+            (-normal(self.minimum), self.minimum),
+            (normal(self.maximum), self.maximum),
+        ]
+        .into_iter()
+        .filter(|(_, angle)| {
+            point.x * angle.cos() + point.z * angle.sin() >= 0.0
+        })
+        .map(|(normal, _)| {
+            (normal, (*point - Point::origin()).dot(&normal).abs())
+        })
+        .filter(|(_, distance)| *distance < side_distance)
+        .min_by(|(_, lhs), (_, rhs)| {
+            lhs.partial_cmp(rhs).unwrap_or_else(|| unreachable!())
+        })
+        .map(|(normal, _)| normal)
+    }
+}
+
+impl_approx_eq!(Wedge { minimum, maximum });
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::math::float::*;
+
+    #[test]
+    fn creating_a_wedge() {
+        let w = Wedge::new(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert_approx_eq!(w.minimum, 0.0);
+        assert_approx_eq!(w.maximum, FRAC_PI_2);
+        assert!(!w.is_full());
+
+        assert!(Wedge::full().is_full());
+    }
+
+    #[test]
+    fn checking_if_a_wedge_contains_a_point() {
+        let w = Wedge::new(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert!(w.contains(&Point::new(1.0, 0.0, 1.0)));
+        assert!(w.contains(&Point::new(1.0, 5.0, 0.0)));
+        assert!(!w.contains(&Point::new(-1.0, 0.0, 1.0)));
+        assert!(!w.contains(&Point::new(1.0, 0.0, -0.1)));
+
+        let w = Wedge::new(Angle(-FRAC_PI_2), Angle(FRAC_PI_2));
+
+        assert!(w.contains(&Point::new(1.0, 0.0, -1.0)));
+        assert!(!w.contains(&Point::new(-1.0, 0.0, 0.0)));
+
+        assert!(Wedge::full().contains(&Point::new(-1.0, 0.0, -1.0)));
+    }
+
+    #[test]
+    fn the_normal_on_the_face_of_a_wedge() {
+        let w = Wedge::new(Angle(0.0), Angle(PI));
+
+        assert_approx_eq!(
+            w.face_normal(&Point::new(0.5, 0.0, 0.0), 0.5).unwrap(),
+            -Vector::z_axis()
+        );
+        assert_approx_eq!(
+            w.face_normal(&Point::new(-0.5, 0.0, 0.0), 0.5).unwrap(),
+            -Vector::z_axis()
+        );
+        assert!(w.face_normal(&Point::new(0.0, 0.0, 0.9), 0.1).is_none());
+
+        let w = Wedge::new(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert_approx_eq!(
+            w.face_normal(&Point::new(0.0, 1.0, 0.5), 0.5).unwrap(),
+            -Vector::x_axis()
+        );
+        assert!(Wedge::full()
+            .face_normal(&Point::new(0.5, 0.0, 0.0), 0.5)
+            .is_none());
+    }
+
+    #[test]
+    fn comparing_wedges() {
+        let w1 = Wedge::new(Angle(0.0), Angle(PI));
+        let w2 = Wedge::new(Angle(0.0), Angle(PI));
+        let w3 = Wedge::new(Angle(0.0), Angle(FRAC_PI_2));
+
+        assert_approx_eq!(w1, w2);
+
+        assert_approx_ne!(w1, w3);
+    }
+}
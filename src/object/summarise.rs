@@ -0,0 +1,9 @@
+use enum_dispatch::enum_dispatch;
+
+use crate::Summary;
+
+/// A trait that adds the contents of an `Object` to a `Summary`.
+#[enum_dispatch(Object)]
+pub trait Summarise {
+    fn summarise(&self, summary: &mut Summary);
+}
@@ -0,0 +1,52 @@
+use std::f64::consts::FRAC_PI_2;
+
+use serde::Deserialize;
+
+use crate::math::{Angle, Transformation};
+
+/// `UpAxis` is the axis that points up in an imported mesh, meshes are rotated
+/// on import so that up is along y.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
+
+impl UpAxis {
+    /// The transformation that turns a mesh with this up axis to be y up.
+    #[must_use]
+    pub fn transformation(self) -> Transformation {
+        match self {
+            Self::Y => Transformation::new(),
+            Self::Z => Transformation::new().rotate_x(Angle(-FRAC_PI_2)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::from_str;
+
+    use super::*;
+    use crate::math::{float::*, Point, Transformable};
+
+    #[test]
+    fn turning_an_up_axis_to_y() {
+        let p = Point::new(1.0, 2.0, 3.0);
+
+        assert_approx_eq!(p.apply(&UpAxis::Y.transformation()), p);
+        assert_approx_eq!(
+            p.apply(&UpAxis::Z.transformation()),
+            Point::new(1.0, 3.0, -2.0)
+        );
+    }
+
+    #[test]
+    fn deserialize_up_axis() {
+        assert_eq!(from_str::<UpAxis>("y").unwrap(), UpAxis::Y);
+        assert_eq!(from_str::<UpAxis>("z").unwrap(), UpAxis::Z);
+        assert_eq!(UpAxis::default(), UpAxis::Y);
+    }
+}
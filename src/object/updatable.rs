@@ -1,13 +1,22 @@
+use std::sync::Arc;
+
 use enum_dispatch::enum_dispatch;
 
+use super::Shading;
 use crate::{math::Transformation, Material};
 
 /// A trait that `Object` should implement to add another `Transformation` to
-/// themselves or replace a `Material`. These should recursively be applied if
-/// needed.
+/// themselves, replace a `Material` or change how they are shaded. These should
+/// recursively be applied if needed.
 #[enum_dispatch(Object)]
 pub trait Updatable {
     fn update_transformation(&mut self, transformation: &Transformation);
-    fn replace_material(&mut self, material: &Material);
+    fn replace_material(&mut self, material: &Material) {
+        self.share_material(&Arc::new(material.clone()));
+    }
+    /// Replace the `Material` with one that is shared with other objects.
+    fn share_material(&mut self, material: &Arc<Material>);
     fn update_casts_shadow(&mut self, casts_shadow: bool);
+    /// Override the `Shading` of any triangles, other shapes are unchanged.
+    fn update_shading(&mut self, shading: Shading);
 }
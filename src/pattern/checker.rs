@@ -3,18 +3,16 @@ use crate::{math::Point, Colour};
 
 impl_pattern!(
     /// A `Checker` pattern produces a checker board pattern where no square
-    /// touches another of the same `Colour`.
-    Checker
+    /// touches another of the same `Colour`. With more than two patterns the
+    /// squares cycle through them in order.
+    multi Checker
 );
 
 impl PatternAt for Checker {
     #[must_use]
     fn pattern_at(&self, point: &Point) -> Colour {
-        if (point.x.floor() + point.y.floor() + point.z.floor()) % 2.0 == 0.0 {
-            return self.a.sub_pattern_at(point);
-        }
-
-        self.b.sub_pattern_at(point)
+        self.pattern_for(point.x.floor() + point.y.floor() + point.z.floor())
+            .sub_pattern_at(point)
     }
 }
 
@@ -0,0 +1,201 @@
+use std::path::Path;
+
+use anyhow::Result;
+use float_cmp::{ApproxEq, F64Margin};
+
+use super::image::Image;
+use crate::{math::Vector, Colour};
+
+/// A `CubeMap` surrounds a scene with six images, one for each face of a cube
+/// centred on the origin, and is sampled by the direction of rays that leave
+/// the scene without hitting anything. Faces are given in the order +x, -x,
+/// +y, -y, +z, -z and are laid out so that neighbouring faces meet along their
+/// edges.
+#[derive(Clone, Debug)]
+pub struct CubeMap {
+    faces: [Image; 6],
+}
+
+impl CubeMap {
+    #[must_use]
+    fn new(faces: [Image; 6]) -> Self {
+        Self { faces }
+    }
+
+    /// Create a cube map where each face is a single colour.
+    #[cfg(test)]
+    #[must_use]
+    pub fn from_colours(colours: [Colour; 6]) -> Self {
+        Self::new(colours.map(|colour| Image::new(1, 1, vec![colour], true)))
+    }
+
+    /// Load a cube map from six sRGB encoded images given in the order +x,
+    /// -x, +y, -y, +z, -z.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if any of the images can not be loaded.
+    pub fn load<P: AsRef<Path>>(filenames: [P; 6]) -> Result<Self> {
+        let [px, nx, py, ny, pz, nz] = filenames;
+
+        Ok(Self::new([
+            Image::load(px, false)?,
+            Image::load(nx, false)?,
+            Image::load(py, false)?,
+            Image::load(ny, false)?,
+            Image::load(pz, false)?,
+            Image::load(nz, false)?,
+        ]))
+    }
+
+    /// Find the colour of the cube map seen in the given direction.
+    #[must_use]
+    pub fn colour_at(&self, direction: &Vector) -> Colour {
+        let (face, u, v) = Self::face_u_v(direction);
+
+        self.faces[face].texel_at(u, v)
+    }
+
+    /// Select the face of the cube that the direction points at from its
+    /// largest component and find the u and v on that face. Ties go to x and
+    /// then y so the edges between faces are always consistent.
+    #[must_use]
+    fn face_u_v(direction: &Vector) -> (usize, f64, f64) {
+        let (x, y, z) = (direction.x, direction.y, direction.z);
+        let (abs_x, abs_y, abs_z) = (x.abs(), y.abs(), z.abs());
+
+        let (face, across, up, size) = if abs_x >= abs_y && abs_x >= abs_z {
+            if x > 0.0 {
+                (0, -z, y, abs_x)
+            } else {
+                (1, z, y, abs_x)
+            }
+        } else if abs_y >= abs_z {
+            if y > 0.0 {
+                (2, x, -z, abs_y)
+            } else {
+                (3, x, z, abs_y)
+            }
+        } else if z > 0.0 {
+            (4, x, y, abs_z)
+        } else {
+            (5, -x, y, abs_z)
+        };
+
+        let convert =
+            |value: f64| (value / size).mul_add(0.5, 0.5).clamp(0.0, 1.0);
+
+        (face, convert(across), convert(up))
+    }
+}
+
+impl ApproxEq for &CubeMap {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+
+        self.faces
+            .iter()
+            .zip(other.faces.iter())
+            .all(|(lhs, rhs)| lhs.approx_eq(rhs, margin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::float::*;
+
+    fn cube_map() -> CubeMap {
+        let face = |colour| Image::new(1, 1, vec![colour], true);
+
+        CubeMap::new([
+            Image::new(
+                3,
+                1,
+                vec![Colour::red(), Colour::white(), Colour::blue()],
+                true,
+            ),
+            face(Colour::green()),
+            face(Colour::yellow()),
+            face(Colour::purple()),
+            face(Colour::cyan()),
+            Image::new(
+                3,
+                1,
+                vec![Colour::red(), Colour::black(), Colour::green()],
+                true,
+            ),
+        ])
+    }
+
+    #[test]
+    fn selecting_the_face_of_a_cube_map() {
+        let c = cube_map();
+
+        assert_approx_eq!(c.colour_at(&Vector::x_axis()), Colour::white());
+        assert_approx_eq!(c.colour_at(&-Vector::x_axis()), Colour::green());
+        assert_approx_eq!(c.colour_at(&Vector::y_axis()), Colour::yellow());
+        assert_approx_eq!(c.colour_at(&-Vector::y_axis()), Colour::purple());
+        assert_approx_eq!(c.colour_at(&Vector::z_axis()), Colour::cyan());
+        assert_approx_eq!(c.colour_at(&-Vector::z_axis()), Colour::black());
+
+        assert_approx_eq!(
+            c.colour_at(&Vector::new(2.0, 0.5, -0.3)),
+            Colour::white()
+        );
+    }
+
+    #[test]
+    fn the_u_and_v_of_a_cube_map_face() {
+        let (f, u, v) = CubeMap::face_u_v(&Vector::new(2.0, 1.0, -1.0));
+
+        assert_eq!(f, 0);
+        assert_approx_eq!(u, 0.75);
+        assert_approx_eq!(v, 0.75);
+
+        let (f, u, v) = CubeMap::face_u_v(&Vector::new(0.5, -4.0, 2.0));
+
+        assert_eq!(f, 3);
+        assert_approx_eq!(u, 0.562_5);
+        assert_approx_eq!(v, 0.75);
+    }
+
+    #[test]
+    fn neighbouring_faces_meet_at_their_edges() {
+        let c = cube_map();
+
+        // The -z edge of the +x face meets the +x edge of the -z face.
+        assert_approx_eq!(
+            c.colour_at(&Vector::new(1.0, 0.0, -0.999)),
+            Colour::blue()
+        );
+        assert_approx_eq!(
+            c.colour_at(&Vector::new(1.0, 0.0, -1.0)),
+            Colour::blue()
+        );
+        assert_approx_eq!(
+            c.colour_at(&Vector::new(0.999, 0.0, -1.0)),
+            Colour::red()
+        );
+
+        assert_approx_eq!(
+            c.colour_at(&Vector::new(1.0, 0.0, 0.999)),
+            Colour::red()
+        );
+    }
+
+    #[test]
+    fn loading_a_missing_cube_map() {
+        assert!(CubeMap::load([
+            "missing-px.png",
+            "missing-nx.png",
+            "missing-py.png",
+            "missing-ny.png",
+            "missing-pz.png",
+            "missing-nz.png",
+        ])
+        .is_err());
+    }
+}
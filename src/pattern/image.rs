@@ -0,0 +1,262 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{bail, Result};
+use float_cmp::{ApproxEq, F64Margin};
+
+use super::PatternAt;
+use crate::{math::Point, Canvas, Colour};
+
+/// An `Image` pattern wraps a texture around an object using a planar mapping
+/// in x and z, repeating every unit. Texels are stored in linear space; images
+/// are normally sRGB encoded and are converted when the pattern is created
+/// unless `linear` is set, as it should be for data such as normal maps.
+#[derive(Clone, Debug)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    texels: Arc<[Colour]>,
+}
+
+impl Image {
+    /// Create an image from texels given row by row starting at the top left.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the number of texels does not match the width and height
+    /// or if either dimension is zero.
+    #[must_use]
+    pub fn new(
+        width: u32,
+        height: u32,
+        texels: Vec<Colour>,
+        linear: bool,
+    ) -> Self {
+        assert!(width > 0 && height > 0, "Image must not be empty");
+        assert_eq!(
+            texels.len(),
+            width as usize * height as usize,
+            "Image texels do not match its dimensions"
+        );
+
+        let texels = if linear {
+            texels
+        } else {
+            texels.iter().map(Colour::to_linear).collect()
+        };
+
+        Self { width, height, texels: texels.into() }
+    }
+
+    /// Load an image from a file.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if the file can not be read or decoded or if the
+    /// image is empty.
+    pub fn load<P: AsRef<Path>>(filename: P, linear: bool) -> Result<Self> {
+        let image = image::open(filename)?.into_rgb8();
+
+        if image.width() == 0 || image.height() == 0 {
+            bail!("Image must not be empty");
+        }
+
+        let texels = image
+            .pixels()
+            .map(|pixel| {
+                let [red, green, blue] = pixel.0;
+
+                Colour::new(
+                    f64::from(red) / 255.0,
+                    f64::from(green) / 255.0,
+                    f64::from(blue) / 255.0,
+                )
+            })
+            .collect();
+
+        Ok(Self::new(image.width(), image.height(), texels, linear))
+    }
+
+    /// Load the alpha channel of an image from a file as a grey image, fully
+    /// opaque texels are white and fully transparent ones black. Alpha is
+    /// never sRGB encoded so no conversion is done. Images without an alpha
+    /// channel are fully opaque.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if the file can not be read or decoded or if the
+    /// image is empty.
+    pub fn load_alpha<P: AsRef<Path>>(filename: P) -> Result<Self> {
+        let image = image::open(filename)?.into_rgba8();
+
+        if image.width() == 0 || image.height() == 0 {
+            bail!("Image must not be empty");
+        }
+
+        let texels = image
+            .pixels()
+            .map(|pixel| {
+                let alpha = f64::from(pixel.0[3]) / 255.0;
+
+                Colour::new(alpha, alpha, alpha)
+            })
+            .collect();
+
+        Ok(Self::new(image.width(), image.height(), texels, true))
+    }
+
+    /// Turn the image into a `Canvas` holding its linear texels.
+    #[must_use]
+    pub fn into_canvas(self) -> Canvas {
+        Canvas::with_vec(self.width, self.height, self.texels.to_vec())
+    }
+
+    #[must_use]
+    pub(super) fn texel_at(&self, u: f64, v: f64) -> Colour {
+        // Both u and v are in the range 0..1 so the conversion is always valid.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let index = |value: f64, size: u32| {
+            (value * f64::from(size - 1)).round() as usize
+        };
+
+        let x = index(u, self.width);
+        let y = index(1.0 - v, self.height);
+
+        self.texels[y * self.width as usize + x]
+    }
+}
+
+impl PatternAt for Image {
+    fn pattern_at(&self, point: &Point) -> Colour {
+        self.texel_at(point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+    }
+}
+
+impl ApproxEq for &Image {
+    type Margin = F64Margin;
+
+    fn approx_eq<M: Into<Self::Margin>>(self, other: Self, margin: M) -> bool {
+        let margin = margin.into();
+
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .texels
+                .iter()
+                .zip(other.texels.iter())
+                .all(|(lhs, rhs)| lhs.approx_eq(*rhs, margin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::float::*;
+
+    fn grey_image(linear: bool) -> Image {
+        Image::new(
+            2,
+            2,
+            vec![
+                Colour::new(0.5, 0.5, 0.5),
+                Colour::white(),
+                Colour::black(),
+                Colour::red(),
+            ],
+            linear,
+        )
+    }
+
+    #[test]
+    fn creating_an_image() {
+        let i = grey_image(true);
+
+        assert_eq!(i.width, 2);
+        assert_eq!(i.height, 2);
+        assert_eq!(i.texels.len(), 4);
+        assert_approx_eq!(i.texels[0], Colour::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn srgb_images_are_converted_to_linear_space() {
+        let i = grey_image(false);
+
+        let expected = ((0.5 + 0.055) / 1.055_f64).powf(2.4);
+
+        assert_approx_eq!(
+            i.texels[0],
+            Colour::new(expected, expected, expected)
+        );
+        assert_approx_eq!(i.texels[1], Colour::white());
+        assert_approx_eq!(i.texels[2], Colour::black());
+        assert_approx_eq!(i.texels[3], Colour::red());
+
+        assert_approx_ne!(i.texels[0], grey_image(true).texels[0]);
+    }
+
+    #[test]
+    fn an_image_pattern_maps_texels_in_x_and_z() {
+        let i = grey_image(true);
+
+        assert_approx_eq!(
+            i.pattern_at(&Point::new(0.1, 0.0, 0.9)),
+            Colour::new(0.5, 0.5, 0.5)
+        );
+        assert_approx_eq!(
+            i.pattern_at(&Point::new(0.9, 5.0, 0.9)),
+            Colour::white()
+        );
+        assert_approx_eq!(
+            i.pattern_at(&Point::new(0.1, 0.0, 0.1)),
+            Colour::black()
+        );
+        assert_approx_eq!(
+            i.pattern_at(&Point::new(-0.1, 0.0, -0.9)),
+            Colour::red()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Image texels do not match its dimensions")]
+    fn creating_an_image_with_the_wrong_number_of_texels() {
+        let _ = Image::new(2, 1, vec![Colour::white()], true);
+    }
+
+    #[test]
+    fn loading_a_missing_image() {
+        assert!(Image::load("missing-image.png", false).is_err());
+    }
+
+    #[test]
+    fn loading_the_alpha_channel_of_an_image() {
+        let i = Image::load_alpha("src/scene/tests/assets/cutout.png").unwrap();
+
+        assert_eq!(i.width, 2);
+        assert_eq!(i.height, 2);
+        assert_approx_eq!(i.texels[0], Colour::white());
+        assert_approx_eq!(i.texels[1], Colour::black());
+        assert_approx_eq!(i.texels[2], Colour::white());
+        assert_approx_eq!(i.texels[3], Colour::white());
+
+        let i =
+            Image::load_alpha("src/scene/tests/assets/texture.png").unwrap();
+
+        assert!(i
+            .texels
+            .iter()
+            .all(|texel| approx_eq!(*texel, Colour::white())));
+
+        assert!(Image::load_alpha("missing-image.png").is_err());
+    }
+
+    #[test]
+    fn comparing_images() {
+        let i1 = grey_image(true);
+        let i2 = grey_image(true);
+        let i3 = grey_image(false);
+
+        assert_approx_eq!(i1, &i2);
+
+        assert_approx_ne!(i1, &i3);
+    }
+}
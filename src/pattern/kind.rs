@@ -3,7 +3,7 @@ use enum_dispatch::enum_dispatch;
 #[cfg(test)]
 use super::Test;
 use super::{
-    util::impl_approx_eq_patterns, Blend, Checker, Gradient, Perturbed,
+    util::impl_approx_eq_patterns, Blend, Checker, Gradient, Image, Perturbed,
     RadialGradient, Ring, Solid, Stripe,
 };
 
@@ -14,6 +14,7 @@ pub enum Kind {
     Blend(Blend),
     Checker(Checker),
     Gradient(Gradient),
+    Image(Image),
     Perturbed(Perturbed),
     RadialGradient(RadialGradient),
     Ring(Ring),
@@ -27,6 +28,7 @@ impl_approx_eq_patterns! {
     Blend,
     Checker,
     Gradient,
+    Image,
     Perturbed,
     RadialGradient,
     Ring,
@@ -1,6 +1,8 @@
 mod blend;
 mod checker;
+mod cube_map;
 mod gradient;
+mod image;
 mod kind;
 mod pattern_at;
 mod perturbed;
@@ -12,12 +14,17 @@ mod stripe;
 mod test;
 mod util;
 
+use std::path::Path;
+
+use anyhow::Result;
 use paste::paste;
 use rand::prelude::*;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use serde::{de::Error, Deserialize, Deserializer};
 use typed_builder::{Optional, TypedBuilder};
 
+pub use self::cube_map::CubeMap;
+pub(crate) use self::image::Image;
 #[cfg(test)]
 use self::test::Test;
 use self::{
@@ -41,6 +48,11 @@ pub struct Pattern {
     #[builder(default = Transformation::new(), setter(skip))]
     inverse_transformation: Transformation,
     kind: Kind,
+    /// Sample the pattern at points in world space rather than in the space
+    /// of the object being shaded, so it stays fixed as objects move. Only
+    /// used when the pattern is applied directly to a `Material`.
+    #[builder(default = false)]
+    world_space: bool,
 }
 
 /// The `add_kind_fn` macro adds a _builder function for the given `Kind`.
@@ -52,7 +64,7 @@ macro_rules! add_kind_fn {
         paste! {
             pub fn [<$kind:snake _builder>](
                 $($arg: $ty),*
-            ) -> PatternBuilder<((), (Kind,))> {
+            ) -> PatternBuilder<((), (Kind,), ())> {
                 Self::_builder().kind(Kind::$kind($kind::new($($arg),*)))
             }
         }
@@ -67,20 +79,77 @@ impl Pattern {
     add_kind_fn!(Ring);
     add_kind_fn!(Stripe);
     add_kind_fn!(Solid(colour: Colour));
+    add_kind_fn!(Image(
+        width: u32,
+        height: u32,
+        texels: Vec<Colour>,
+        linear: bool
+    ));
     #[cfg(test)]
     add_kind_fn!(Test());
 
+    /// Create a stripe pattern that cycles through several patterns.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if fewer than two patterns are given.
+    pub fn stripe_multi_builder(
+        patterns: Vec<Self>,
+    ) -> PatternBuilder<((), (Kind,), ())> {
+        Self::_builder().kind(Kind::Stripe(Stripe::new_multi(patterns)))
+    }
+
+    /// Create a checker pattern that cycles through several patterns.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if fewer than two patterns are given.
+    pub fn checker_multi_builder(
+        patterns: Vec<Self>,
+    ) -> PatternBuilder<((), (Kind,), ())> {
+        Self::_builder().kind(Kind::Checker(Checker::new_multi(patterns)))
+    }
+
     pub fn perturbed_builder<R: Rng>(
         scale: f64,
         pattern: Self,
         rng: &mut R,
-    ) -> PatternBuilder<((), (Kind,))> {
+    ) -> PatternBuilder<((), (Kind,), ())> {
         Self::_builder()
             .kind(Kind::Perturbed(Perturbed::new(scale, pattern, rng)))
     }
 
+    /// Create an image pattern from a file, see `Image` for details on how
+    /// `linear` is used.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if the image can not be loaded.
+    pub fn load_image_builder<P: AsRef<Path>>(
+        filename: P,
+        linear: bool,
+    ) -> Result<PatternBuilder<((), (Kind,), ())>> {
+        Ok(Self::_builder().kind(Kind::Image(Image::load(filename, linear)?)))
+    }
+
+    /// Create an image pattern from the alpha channel of a file, see `Image`
+    /// for details.
+    ///
+    /// # Errors
+    ///
+    /// Will return errors if the image can not be loaded.
+    pub fn load_alpha_builder<P: AsRef<Path>>(
+        filename: P,
+    ) -> Result<PatternBuilder<((), (Kind,), ())>> {
+        Ok(Self::_builder().kind(Kind::Image(Image::load_alpha(filename)?)))
+    }
+
     #[must_use]
     pub fn pattern_at(&self, object: &Object, point: &Point) -> Colour {
+        if self.world_space {
+            return self.sub_pattern_at(point);
+        }
+
         let object_point = object.to_object_space(point);
 
         self.sub_pattern_at(&object_point)
@@ -104,10 +173,17 @@ impl From<Colour> for Pattern {
 }
 
 impl_approx_eq!(
-    &Pattern { ref kind, transformation, inverse_transformation }
+    &Pattern {
+        ref kind,
+        transformation,
+        inverse_transformation,
+        eq world_space
+    }
 );
 
-impl<T: Optional<Transformation>> PatternBuilder<(T, (Kind,))> {
+impl<T: Optional<Transformation>, W: Optional<bool>>
+    PatternBuilder<(T, (Kind,), W)>
+{
     #[must_use]
     pub fn build(self) -> Pattern {
         let mut pattern = self._build();
@@ -119,6 +195,7 @@ impl<T: Optional<Transformation>> PatternBuilder<(T, (Kind,))> {
 }
 
 impl<'de> Deserialize<'de> for Pattern {
+    #[allow(clippy::too_many_lines)]
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -138,18 +215,38 @@ impl<'de> Deserialize<'de> for Pattern {
                 a: ColourPattern,
                 b: ColourPattern,
                 transform: Option<Transformation>,
+                #[serde(rename = "world-space", default)]
+                world_space: bool,
+            },
+            Multi {
+                kind: String,
+                colors: Vec<ColourPattern>,
+                transform: Option<Transformation>,
+                #[serde(rename = "world-space", default)]
+                world_space: bool,
+            },
+            Image {
+                image: String,
+                linear: Option<bool>,
+                #[serde(default)]
+                alpha: bool,
+                transform: Option<Transformation>,
+                #[serde(rename = "world-space", default)]
+                world_space: bool,
             },
             Perturbed {
                 scale: f64,
                 pattern: Pattern,
                 seed: u64,
                 transform: Option<Transformation>,
+                #[serde(rename = "world-space", default)]
+                world_space: bool,
             },
         }
 
         let pattern = PatternData::deserialize(deserializer)?;
 
-        let build = |pattern: PatternBuilder<((), (Kind,))>, transform| {
+        let build = |pattern: PatternBuilder<((), (Kind,), ())>, transform| {
             if let Some(transformation) = transform {
                 Ok(pattern.transformation(transformation).build())
             } else {
@@ -162,48 +259,100 @@ impl<'de> Deserialize<'de> for Pattern {
             ColourPattern::Pattern(pattern) => pattern,
         };
 
-        match pattern {
-            PatternData::Pattern { kind, a, b, transform } => match &*kind {
-                "blend" => build(
-                    Self::blend_builder(get_pattern(a), get_pattern(b)),
-                    transform,
-                ),
-                "checker" => build(
-                    Self::checker_builder(get_pattern(a), get_pattern(b)),
-                    transform,
-                ),
-                "gradient" => build(
-                    Self::gradient_builder(get_pattern(a), get_pattern(b)),
-                    transform,
-                ),
-                "radial-gradient" => build(
-                    Self::radial_gradient_builder(
-                        get_pattern(a),
-                        get_pattern(b),
+        let (PatternData::Pattern { world_space, .. }
+        | PatternData::Multi { world_space, .. }
+        | PatternData::Image { world_space, .. }
+        | PatternData::Perturbed { world_space, .. }) = pattern;
+
+        let mut pattern: Self = match pattern {
+            PatternData::Pattern { kind, a, b, transform, .. } => {
+                match &*kind {
+                    "blend" => build(
+                        Self::blend_builder(get_pattern(a), get_pattern(b)),
+                        transform,
                     ),
-                    transform,
-                ),
-                "ring" => build(
-                    Self::ring_builder(get_pattern(a), get_pattern(b)),
-                    transform,
-                ),
-                "stripe" => build(
-                    Self::stripe_builder(get_pattern(a), get_pattern(b)),
-                    transform,
-                ),
-                _ => Err(Error::custom(format!("Unknown pattern '{kind}'"))),
-            },
-            PatternData::Perturbed { scale, pattern, seed, transform } => {
-                build(
-                    Self::perturbed_builder(
-                        scale,
-                        pattern,
-                        &mut Xoshiro256PlusPlus::seed_from_u64(seed),
+                    "checker" => build(
+                        Self::checker_builder(get_pattern(a), get_pattern(b)),
+                        transform,
                     ),
+                    "gradient" => build(
+                        Self::gradient_builder(get_pattern(a), get_pattern(b)),
+                        transform,
+                    ),
+                    "radial-gradient" => build(
+                        Self::radial_gradient_builder(
+                            get_pattern(a),
+                            get_pattern(b),
+                        ),
+                        transform,
+                    ),
+                    "ring" => build(
+                        Self::ring_builder(get_pattern(a), get_pattern(b)),
+                        transform,
+                    ),
+                    "stripe" => build(
+                        Self::stripe_builder(get_pattern(a), get_pattern(b)),
+                        transform,
+                    ),
+                    _ => {
+                        Err(Error::custom(format!("Unknown pattern '{kind}'")))
+                    }
+                }
+            }
+            PatternData::Multi { kind, colors, transform, .. } => {
+                if colors.len() < 2 {
+                    return Err(Error::custom(
+                        "A pattern needs at least two colors",
+                    ));
+                }
+
+                let patterns = colors.into_iter().map(get_pattern).collect();
+
+                match &*kind {
+                    "checker" => {
+                        build(Self::checker_multi_builder(patterns), transform)
+                    }
+                    "stripe" => {
+                        build(Self::stripe_multi_builder(patterns), transform)
+                    }
+                    _ => Err(Error::custom(format!(
+                        "Pattern '{kind}' does not support a list of colors"
+                    ))),
+                }
+            }
+            PatternData::Image { image, linear, alpha, transform, .. } => {
+                build(
+                    if alpha {
+                        Self::load_alpha_builder(&image)
+                    } else {
+                        Self::load_image_builder(
+                            &image,
+                            linear.unwrap_or(false),
+                        )
+                    }
+                    .map_err(|error| {
+                        Error::custom(format!(
+                            "Unable to load image '{image}': {error}"
+                        ))
+                    })?,
                     transform,
                 )
             }
-        }
+            PatternData::Perturbed {
+                scale, pattern, seed, transform, ..
+            } => build(
+                Self::perturbed_builder(
+                    scale,
+                    pattern,
+                    &mut Xoshiro256PlusPlus::seed_from_u64(seed),
+                ),
+                transform,
+            ),
+        }?;
+
+        pattern.world_space = world_space;
+
+        Ok(pattern)
     }
 }
 
@@ -259,6 +408,11 @@ mod tests {
         let w = Colour::white();
 
         test_pattern!(Solid(w));
+
+        let v = vec![w, w];
+        let (x, y, l) = (2, 1, false);
+
+        test_pattern!(Image(x, y, v, l));
         test_pattern!(Test());
 
         let mut r = Xoroshiro128PlusPlus::seed_from_u64(251);
@@ -318,6 +472,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_world_space_pattern_ignores_the_object_transformation() {
+        let o1 = Object::test_builder().build();
+        let o2 = Object::test_builder()
+            .transformation(
+                Transformation::new()
+                    .scale(2.0, 2.0, 2.0)
+                    .translate(0.5, 0.0, 0.0),
+            )
+            .build();
+
+        let p = Pattern::checker_builder(
+            Colour::white().into(),
+            Colour::black().into(),
+        )
+        .world_space(true)
+        .build();
+
+        for point in [
+            Point::new(0.5, 0.5, 0.5),
+            Point::new(1.5, 0.5, 0.5),
+            Point::new(1.5, 1.5, -0.5),
+        ] {
+            assert_approx_eq!(
+                p.pattern_at(&o1, &point),
+                p.pattern_at(&o2, &point)
+            );
+        }
+
+        assert_approx_eq!(
+            p.pattern_at(&o2, &Point::new(1.5, 0.5, 0.5)),
+            Colour::black()
+        );
+
+        let p = Pattern::checker_builder(
+            Colour::white().into(),
+            Colour::black().into(),
+        )
+        .build();
+
+        assert_approx_ne!(
+            p.pattern_at(&o1, &Point::new(1.5, 0.5, 0.5)),
+            p.pattern_at(&o2, &Point::new(1.5, 0.5, 0.5))
+        );
+    }
+
+    #[test]
+    fn parse_world_space_pattern() {
+        let p: Pattern = from_str(
+            "\
+kind: checker
+a: [1, 1, 1]
+b: [0, 0, 0]
+world-space: true",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            p,
+            &crate::Pattern::checker_builder(
+                Colour::white().into(),
+                Colour::black().into()
+            )
+            .world_space(true)
+            .build()
+        );
+
+        assert_approx_ne!(
+            p,
+            &crate::Pattern::checker_builder(
+                Colour::white().into(),
+                Colour::black().into()
+            )
+            .build()
+        );
+    }
+
     #[test]
     fn a_stripe_pattern_with_an_object_transformation() {
         let o = Object::test_builder()
@@ -521,6 +752,72 @@ b: [0, 0, 1]",
         );
     }
 
+    #[test]
+    fn parse_multi_colour_patterns() {
+        let p: Pattern = from_str(
+            "\
+kind: checker
+colors:
+    - [1, 0, 0]
+    - [0, 1, 0]
+    - [0, 0, 1]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            p,
+            &crate::Pattern::checker_multi_builder(vec![
+                Colour::red().into(),
+                Colour::green().into(),
+                Colour::blue().into()
+            ])
+            .build()
+        );
+
+        let p: Pattern = from_str(
+            "\
+kind: stripe
+colors:
+    - [1, 0, 0]
+    - [0, 1, 0]",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            p,
+            &crate::Pattern::stripe_builder(
+                Colour::red().into(),
+                Colour::green().into()
+            )
+            .build()
+        );
+
+        assert_eq!(
+            from_str::<Pattern>(
+                "\
+kind: ring
+colors:
+    - [1, 0, 0]
+    - [0, 1, 0]",
+            )
+            .unwrap_err()
+            .to_string(),
+            "Pattern 'ring' does not support a list of colors"
+        );
+
+        assert_eq!(
+            from_str::<Pattern>(
+                "\
+kind: stripe
+colors:
+    - [1, 0, 0]",
+            )
+            .unwrap_err()
+            .to_string(),
+            "A pattern needs at least two colors"
+        );
+    }
+
     #[test]
     fn deserialize_perturbed_pattern() {
         let p: Pattern = from_str(
@@ -560,6 +857,34 @@ transform:
         );
     }
 
+    #[test]
+    fn deserialize_alpha_image_pattern() {
+        let p: Pattern = from_str(
+            "\
+image: src/scene/tests/assets/cutout.png
+alpha: true",
+        )
+        .unwrap();
+
+        assert_approx_eq!(
+            p,
+            &Pattern::load_alpha_builder("src/scene/tests/assets/cutout.png")
+                .unwrap()
+                .build()
+        );
+    }
+
+    #[test]
+    fn deserialize_missing_image_pattern() {
+        assert_eq!(
+            from_str::<Pattern>("image: missing-image.png")
+                .unwrap_err()
+                .to_string(),
+            "Unable to load image 'missing-image.png': No such file or \
+directory (os error 2)"
+        );
+    }
+
     #[test]
     fn deserialize_invalid_pattern() {
         assert_eq!(
@@ -3,8 +3,8 @@ use enum_dispatch::enum_dispatch;
 #[cfg(test)]
 use super::Test;
 use super::{
-    Blend, Checker, Gradient, Kind, Perturbed, RadialGradient, Ring, Solid,
-    Stripe,
+    Blend, Checker, Gradient, Image, Kind, Perturbed, RadialGradient, Ring,
+    Solid, Stripe,
 };
 use crate::{math::Point, Colour};
 
@@ -1,17 +1,16 @@
-use libnoise::{Generator, Simplex, Source};
 use rand::prelude::*;
 
 use super::{Pattern, PatternAt};
 use crate::{
-    math::{float::impl_approx_eq, Point},
+    math::{float::impl_approx_eq, Noise, Point},
     Colour,
 };
 
-/// A `Perturbed` pattern uses `Simplex` noise to perturb or move the position of
-/// each point in x and z.
+/// A `Perturbed` pattern uses `Noise` to perturb or move the position of each
+/// point in x and z.
 #[derive(Clone, Debug)]
 pub struct Perturbed {
-    noise: Box<Simplex<2>>,
+    noise: Box<Noise>,
     scale: f64,
     pattern: Box<Pattern>,
 }
@@ -19,7 +18,7 @@ pub struct Perturbed {
 impl Perturbed {
     #[must_use]
     pub fn new<R: Rng>(scale: f64, pattern: Pattern, rng: &mut R) -> Self {
-        let noise = Source::simplex(rng.gen());
+        let noise = Noise::new(rng.gen());
 
         Self { noise: Box::new(noise), scale, pattern: Box::new(pattern) }
     }
@@ -28,7 +27,7 @@ impl Perturbed {
 impl PatternAt for Perturbed {
     #[must_use]
     fn pattern_at(&self, point: &Point) -> Colour {
-        let value = self.noise.sample([point.x, point.z]) * self.scale;
+        let value = self.noise.sample(point) * self.scale;
 
         self.pattern.sub_pattern_at(&Point::new(
             point.x + value,
@@ -47,7 +46,7 @@ mod tests {
     use rand_xoshiro::Xoroshiro128PlusPlus;
 
     use super::*;
-    use crate::math::float::*;
+    use crate::math::{float::*, noise};
 
     // It is difficult to actually test that values are perturbed by inspecting
     // the f64 outputs so testing is just left up to rendering and seeing if it
@@ -75,6 +74,23 @@ mod tests {
         assert_approx_eq!(p.pattern_at(&Point::origin()), Colour::red());
     }
 
+    #[test]
+    fn a_perturbed_pattern_uses_the_public_noise() {
+        let mut r = Xoroshiro128PlusPlus::seed_from_u64(4);
+
+        let p = Perturbed::new(0.5, Colour::red().into(), &mut r);
+
+        let seed = Xoroshiro128PlusPlus::seed_from_u64(4).gen();
+
+        for point in [
+            Point::origin(),
+            Point::new(0.5, 1.0, -2.5),
+            Point::new(-13.1, 0.0, 7.9),
+        ] {
+            assert_approx_eq!(p.noise.sample(&point), noise(&point, seed));
+        }
+    }
+
     #[test]
     fn comparing_perturbed_patterns() {
         let mut r = Xoroshiro128PlusPlus::seed_from_u64(3);
@@ -3,17 +3,13 @@ use crate::{math::Point, Colour};
 
 impl_pattern!(
     /// A `Stripe` pattern alternates between two different `Colour`s as the x
-    /// value changes.
-    Stripe
+    /// value changes, or cycles through several in order.
+    multi Stripe
 );
 
 impl PatternAt for Stripe {
     fn pattern_at(&self, point: &Point) -> Colour {
-        if point.x.floor() % 2.0 == 0.0 {
-            return self.a.sub_pattern_at(point);
-        }
-
-        self.b.sub_pattern_at(point)
+        self.pattern_for(point.x.floor()).sub_pattern_at(point)
     }
 }
 
@@ -88,5 +84,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_three_colour_stripe_pattern_cycles_in_x() {
+        let s = Stripe::new_multi(vec![
+            Colour::red().into(),
+            Colour::green().into(),
+            Colour::blue().into(),
+        ]);
+
+        assert_approx_eq!(s.pattern_at(&Point::origin()), Colour::red());
+
+        assert_approx_eq!(
+            s.pattern_at(&Point::new(1.0, 0.0, 0.0)),
+            Colour::green()
+        );
+
+        assert_approx_eq!(
+            s.pattern_at(&Point::new(2.0, 0.0, 0.0)),
+            Colour::blue()
+        );
+
+        assert_approx_eq!(
+            s.pattern_at(&Point::new(3.0, 0.0, 0.0)),
+            Colour::red()
+        );
+
+        assert_approx_eq!(
+            s.pattern_at(&Point::new(-0.5, 0.0, 0.0)),
+            Colour::blue()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "At least two patterns are needed")]
+    fn a_stripe_pattern_needs_two_patterns() {
+        let _ = Stripe::new_multi(vec![Colour::red().into()]);
+    }
+
     add_kind_tests!(Stripe);
 }
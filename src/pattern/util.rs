@@ -23,6 +23,84 @@ macro_rules! impl_pattern {
 
         crate::math::float::impl_approx_eq!(&$pattern { ref a, ref b });
     };
+    ($(#[$outer:meta])* multi $pattern:ident) => {
+        $(#[$outer])*
+        #[derive(Clone, Debug)]
+        pub struct $pattern {
+            a: Box<crate::Pattern>,
+            b: Box<crate::Pattern>,
+            rest: Vec<crate::Pattern>,
+        }
+
+        impl $pattern {
+            #[must_use]
+            pub fn new(a: crate::Pattern, b: crate::Pattern) -> Self {
+                Self { a: Box::new(a), b: Box::new(b), rest: Vec::new() }
+            }
+
+            /// Create a pattern that cycles through the given patterns in
+            /// order.
+            ///
+            /// # Panics
+            ///
+            /// Will panic if fewer than two patterns are given.
+            #[must_use]
+            pub fn new_multi(patterns: Vec<crate::Pattern>) -> Self {
+                assert!(
+                    patterns.len() >= 2,
+                    "At least two patterns are needed"
+                );
+
+                let mut patterns = patterns.into_iter();
+
+                let a = patterns.next().unwrap_or_else(|| unreachable!());
+                let b = patterns.next().unwrap_or_else(|| unreachable!());
+
+                Self {
+                    a: Box::new(a),
+                    b: Box::new(b),
+                    rest: patterns.collect(),
+                }
+            }
+
+            /// Pick the pattern for a given integer step, cycling through the
+            /// patterns in order.
+            #[must_use]
+            fn pattern_for(&self, step: f64) -> &crate::Pattern {
+                // The count is small so the conversions are always valid.
+                #[allow(clippy::cast_possible_truncation)]
+                #[allow(clippy::cast_precision_loss)]
+                #[allow(clippy::cast_sign_loss)]
+                let index =
+                    step.rem_euclid((self.rest.len() + 2) as f64) as usize;
+
+                match index {
+                    0 => &self.a,
+                    1 => &self.b,
+                    _ => &self.rest[index - 2],
+                }
+            }
+        }
+
+        impl float_cmp::ApproxEq for &$pattern {
+            type Margin = float_cmp::F64Margin;
+
+            fn approx_eq<M: Into<Self::Margin>>(
+                self, other: Self, margin: M
+            ) -> bool {
+                let margin = margin.into();
+
+                self.a.approx_eq(&other.a, margin)
+                    && self.b.approx_eq(&other.b, margin)
+                    && self.rest.len() == other.rest.len()
+                    && self
+                        .rest
+                        .iter()
+                        .zip(&other.rest)
+                        .all(|(lhs, rhs)| lhs.approx_eq(rhs, margin))
+            }
+        }
+    };
 }
 pub(super) use impl_pattern;
 
@@ -34,9 +34,21 @@ impl Define {
             if data.shapes.insert(self.define, add).is_some() {
                 bail!(err("Shape"));
             };
+        } else if from_value::<HashValue>(self.value.clone()).is_ok_and(
+            |hash_map| {
+                // Only patterns have these keys, materials hold their pattern
+                // under the pattern key.
+                ["kind", "image", "scale"]
+                    .iter()
+                    .any(|key| hash_map.contains_key(*key))
+            },
+        ) {
+            if data.patterns.insert(self.define, self.value).is_some() {
+                bail!(err("Pattern"));
+            }
         } else if from_value::<HashValue>(self.value.clone()).is_ok() {
             let material = if let Some(extend) = self.extend {
-                if let Some(define) = data.materials.get(&extend) {
+                if let Some(define) = data.material(&extend) {
                     define.clone().update(self.value)?
                 } else {
                     bail!(
@@ -170,6 +182,46 @@ value:
         );
     }
 
+    #[test]
+    fn parse_define_pattern() {
+        let d: Define = from_str(
+            "\
+define: foo
+value:
+    kind: checker
+    a: [1, 1, 1]
+    b: [0, 0, 0]",
+        )
+        .unwrap();
+
+        let mut da = Data::new();
+
+        d.clone().parse(&mut da).unwrap();
+
+        assert_eq!(da.patterns.len(), 1);
+        assert!(da.materials.is_empty());
+
+        assert_eq!(
+            d.parse(&mut da).unwrap_err().to_string(),
+            "Pattern 'foo' already defined"
+        );
+
+        // A material using a pattern is still a material.
+        let d: Define = from_str(
+            "\
+define: bar
+value:
+    pattern: foo
+    ambient: 0.5",
+        )
+        .unwrap();
+
+        d.parse(&mut da).unwrap();
+
+        assert_eq!(da.patterns.len(), 1);
+        assert_eq!(da.materials.len(), 1);
+    }
+
     #[test]
     fn define_transformation() {
         let d: Define = from_str(
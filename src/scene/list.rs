@@ -1,15 +1,26 @@
+use std::io::Write;
+
 use anyhow::{bail, Result};
 use rand::prelude::*;
 use serde::Deserialize;
 
-use super::{Add, Data, Define};
+use super::{Add, Data, Define, RenderSettings};
+use crate::Output;
+
+/// The `Settings` struct holds the deserialized render settings for the scene.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Settings {
+    settings: RenderSettings,
+}
 
-/// An `Element` is either a deserialized definition or some object to add.
+/// An `Element` is either a deserialized definition, some object to add or the
+/// render settings.
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
 pub enum Element {
     Add(Add),
     Define(Define),
+    Settings(Settings),
 }
 
 /// A `List` is the list of all elements that were deserialized.
@@ -17,11 +28,25 @@ pub enum Element {
 pub struct List(Vec<Element>);
 
 impl List {
-    pub fn parse<R: Rng>(self, data: &mut Data, rng: &mut R) -> Result<()> {
+    /// Parse all the elements into `data`, writing a warning to `output` for
+    /// any defines that are never referenced.
+    pub fn parse<O: Write, R: Rng>(
+        self,
+        data: &mut Data,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<()> {
         for element in self.0 {
             match element {
                 Element::Add(add) => add.parse(data, rng)?,
                 Element::Define(define) => define.parse(data)?,
+                Element::Settings(settings) => {
+                    if data.settings.is_some() {
+                        bail!("Settings can only be specified once")
+                    }
+
+                    data.settings = Some(settings.settings);
+                }
             }
         }
 
@@ -33,6 +58,10 @@ impl List {
             bail!("No objects were defined")
         }
 
+        for warning in data.unreferenced() {
+            writeln!(output, "Warning: {warning}")?;
+        }
+
         Ok(())
     }
 }
@@ -74,12 +103,68 @@ mod tests {
 
         let mut d = Data::new();
 
-        l.parse(&mut d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+        l.parse(
+            &mut d,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut Xoshiro256PlusPlus::seed_from_u64(0),
+        )
+        .unwrap();
 
         assert!(d.camera.is_some());
         assert_eq!(d.lights.len(), 2);
     }
 
+    #[test]
+    fn unreferenced_defines_are_warned_about() {
+        let parse = |yaml: &str| {
+            let l: List = from_str(yaml).unwrap();
+
+            let mut o = Vec::new();
+
+            l.parse(
+                &mut Data::new(),
+                &mut Output::new(&mut o),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0),
+            )
+            .unwrap();
+
+            String::from_utf8(o).unwrap()
+        };
+
+        let scene = |material| {
+            format!(
+                "\
+- define: shiny
+  value:
+      specular: 0.9
+- define: dull
+  value:
+      diffuse: 0.2
+- add: light
+  at: [-10, 10, -10]
+  intensity: [1, 1, 1]
+- add: camera
+  width: 100
+  height: 100
+  field-of-view: 1.0
+  from: [0, 0, 0]
+  to: [0, 0, 5]
+  up: [0, 1, 0]
+- add: sphere
+  material: shiny
+- add: cube
+  material: {material}"
+            )
+        };
+
+        assert_eq!(
+            parse(&scene("{ ambient: 0.5 }")),
+            "Warning: Material 'dull' is never used\n"
+        );
+
+        assert_eq!(parse(&scene("dull")), "");
+    }
+
     #[test]
     fn parse_no_camera() {
         let l: List = from_str(
@@ -103,13 +188,42 @@ mod tests {
         let mut d = Data::new();
 
         assert_eq!(
-            l.parse(&mut d, &mut Xoshiro256PlusPlus::seed_from_u64(0))
-                .unwrap_err()
-                .to_string(),
+            l.parse(
+                &mut d,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .to_string(),
             "A camera must be defined"
         );
     }
 
+    #[test]
+    fn parse_repeated_settings() {
+        let l: List = from_str(
+            "\
+- settings:
+    depth: 2
+- settings:
+    samples: 4",
+        )
+        .unwrap();
+
+        let mut d = Data::new();
+
+        assert_eq!(
+            l.parse(
+                &mut d,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .to_string(),
+            "Settings can only be specified once"
+        );
+    }
+
     #[test]
     fn parse_no_lights() {
         let l: List = from_str(
@@ -130,9 +244,13 @@ mod tests {
         let mut d = Data::new();
 
         assert_eq!(
-            l.parse(&mut d, &mut Xoshiro256PlusPlus::seed_from_u64(0))
-                .unwrap_err()
-                .to_string(),
+            l.parse(
+                &mut d,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .to_string(),
             "No lights were defined"
         );
     }
@@ -167,9 +285,13 @@ mod tests {
         let mut d = Data::new();
 
         assert_eq!(
-            l.parse(&mut d, &mut Xoshiro256PlusPlus::seed_from_u64(0))
-                .unwrap_err()
-                .to_string(),
+            l.parse(
+                &mut d,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(0)
+            )
+            .unwrap_err()
+            .to_string(),
             "No objects were defined"
         );
     }
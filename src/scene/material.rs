@@ -23,33 +23,81 @@ impl Material {
 
         let mut hash_map: HashValue = from_value(value)?;
 
-        if let Some(pattern) = hash_map.remove("pattern") {
-            let mut pattern_hash_map: HashValue = from_value(pattern)?;
+        for key in ["pattern", "alpha_map", "normal_map"] {
+            if let Some(pattern) = hash_map.remove(key) {
+                let mut pattern = Self::get_pattern(pattern, data)?;
 
-            pattern_hash_map
-                .insert(String::from("seed"), to_value(rng.gen::<u64>())?);
+                Self::resolve_images(&mut pattern, data);
 
-            if let Some(transform) = pattern_hash_map.remove("transform") {
-                let transformations: TransformationList =
-                    from_value(transform)?;
+                let mut pattern_hash_map: HashValue = from_value(pattern)?;
 
-                pattern_hash_map.insert(
-                    String::from("transform"),
-                    to_value(transformations.collect(data)?)?,
-                );
-            }
+                pattern_hash_map
+                    .insert(String::from("seed"), to_value(rng.gen::<u64>())?);
+
+                if let Some(transform) = pattern_hash_map.remove("transform") {
+                    let transformations: TransformationList =
+                        from_value(transform)?;
+
+                    pattern_hash_map.insert(
+                        String::from("transform"),
+                        to_value(transformations.collect(data)?)?,
+                    );
+                }
 
-            hash_map
-                .insert(String::from("pattern"), to_value(pattern_hash_map)?);
+                hash_map.insert(String::from(key), to_value(pattern_hash_map)?);
+            }
         }
 
         Ok(from_value(to_value(hash_map)?)?)
     }
 
+    /// Resolve the paths of any image patterns, including those nested
+    /// anywhere within other patterns such as in a list of `colors`, against
+    /// the scene's directory.
+    fn resolve_images(pattern: &mut Value, data: &Data) {
+        match pattern {
+            Value::Mapping(mapping) => {
+                for (key, value) in mapping.iter_mut() {
+                    match value {
+                        Value::String(image)
+                            if key.as_str() == Some("image") =>
+                        {
+                            *image = data
+                                .resolve(&*image)
+                                .to_string_lossy()
+                                .into_owned();
+                        }
+                        _ => Self::resolve_images(value, data),
+                    }
+                }
+            }
+            Value::Sequence(sequence) => {
+                for value in sequence {
+                    Self::resolve_images(value, data);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Look up a pattern given by name, patterns given in full are unchanged.
+    fn get_pattern(pattern: Value, data: &Data) -> Result<Value> {
+        match pattern {
+            Value::String(name) => {
+                if let Some(pattern) = data.pattern(&name) {
+                    Ok(pattern.clone())
+                } else {
+                    bail!("Reference to pattern '{name}' that was not defined");
+                }
+            }
+            _ => Ok(pattern),
+        }
+    }
+
     fn get_value(self, data: &Data) -> Result<Value> {
         match self {
             Self::Name(name) => {
-                if let Some(material) = data.materials.get(&name) {
+                if let Some(material) = data.material(&name) {
                     material.clone().get_value(data)
                 } else {
                     bail!(
@@ -77,6 +125,8 @@ impl Material {
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
     use rand_xoshiro::Xoshiro256PlusPlus;
     use serde_yaml::from_str;
 
@@ -146,6 +196,50 @@ diffuse: 0.0",
         );
     }
 
+    #[test]
+    fn parse_material_pattern_reference() {
+        let mut d = Data::new();
+        d.patterns.insert(
+            String::from("foo"),
+            from_str(
+                "\
+kind: stripe
+a: [1, 1, 1]
+b: [0, 0, 0]",
+            )
+            .unwrap(),
+        );
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let m: Material = from_str(
+            "\
+pattern: foo
+alpha_map: foo
+normal_map: foo",
+        )
+        .unwrap();
+
+        let m = m.parse(&d, &mut r).unwrap();
+
+        let p = Pattern::stripe_builder(
+            Colour::white().into(),
+            Colour::black().into(),
+        )
+        .build();
+
+        assert_approx_eq!(m.pattern, &p);
+        assert_approx_eq!(m.alpha_map.as_ref().unwrap(), &p);
+        assert_approx_eq!(m.normal_map.as_ref().unwrap(), &p);
+
+        let m: Material = from_str("pattern: bar").unwrap();
+
+        assert_eq!(
+            m.parse(&d, &mut r).unwrap_err().to_string(),
+            "Reference to pattern 'bar' that was not defined"
+        );
+    }
+
     #[test]
     fn parse_material_with_transform_transformation() {
         let m: Material = from_str(
@@ -195,6 +289,90 @@ pattern:
         );
     }
 
+    #[test]
+    fn parse_material_with_alpha_map() {
+        let m: Material = from_str(
+            "\
+color: [1, 0, 0]
+alpha_map:
+    image: cutout.png
+    alpha: true
+    transform:
+        - [scale, 2, 2, 2]",
+        )
+        .unwrap();
+
+        let mut d = Data::new();
+        d.directory = PathBuf::from("src/scene/tests/assets");
+
+        let m = m.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        assert_approx_eq!(
+            m,
+            &crate::Material::builder()
+                .pattern(Colour::red().into())
+                .alpha_map(Some(
+                    Pattern::load_alpha_builder(
+                        "src/scene/tests/assets/cutout.png"
+                    )
+                    .unwrap()
+                    .transformation(Transformation::new().scale(2.0, 2.0, 2.0))
+                    .build()
+                ))
+                .build()
+        );
+    }
+
+    #[test]
+    fn parse_material_with_nested_images() {
+        let m: Material = from_str(
+            "\
+pattern:
+    kind: checker
+    colors:
+        - [1, 0, 0]
+        - kind: stripe
+          a: [0, 1, 0]
+          b:
+              image: texture.png
+        - image: cutout.png",
+        )
+        .unwrap();
+
+        let mut d = Data::new();
+        d.directory = PathBuf::from("src/scene/tests/assets");
+
+        let m = m.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        assert_approx_eq!(
+            m,
+            &crate::Material::builder()
+                .pattern(
+                    Pattern::checker_multi_builder(vec![
+                        Colour::red().into(),
+                        Pattern::stripe_builder(
+                            Colour::green().into(),
+                            Pattern::load_image_builder(
+                                "src/scene/tests/assets/texture.png",
+                                false
+                            )
+                            .unwrap()
+                            .build()
+                        )
+                        .build(),
+                        Pattern::load_image_builder(
+                            "src/scene/tests/assets/cutout.png",
+                            false
+                        )
+                        .unwrap()
+                        .build()
+                    ])
+                    .build()
+                )
+                .build()
+        );
+    }
+
     #[test]
     fn update_material() {
         let m = Material::Data(
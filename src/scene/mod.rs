@@ -2,16 +2,23 @@ mod add;
 mod define;
 mod list;
 mod material;
+mod settings;
 mod shapes;
 mod transformations;
+mod warning;
 
 use std::{
-    collections::HashMap, f64::consts::FRAC_PI_3, fs::File, io::Write,
-    path::Path,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    f64::consts::FRAC_PI_3,
+    fs::{write, File},
+    io::Write,
+    path::{Path, PathBuf},
 };
 
-use anyhow::Result;
+use anyhow::{Context, Error, Result};
 use derive_new::new;
+use image::{ImageBuffer, Rgb};
 use rand::prelude::*;
 use serde_yaml::{from_reader, Value};
 
@@ -19,7 +26,12 @@ use self::{
     add::Add, define::Define, list::List, material::Material,
     transformations::TransformationList,
 };
-use crate::{Camera, Canvas, Light, Object, Output, World};
+pub use self::{settings::RenderSettings, warning::Warning};
+use crate::{
+    math::{float::approx_eq, Point, Vector},
+    AntiAliasing, Bounded, BoundingBox, Camera, Canvas, Colour, Light, Object,
+    Output, Summary, World,
+};
 
 type HashValue = HashMap<String, Value>;
 
@@ -28,22 +40,93 @@ type HashValue = HashMap<String, Value>;
 struct Data {
     shapes: HashMap<String, Add>,
     materials: HashMap<String, Material>,
+    patterns: HashMap<String, Value>,
     transformations: HashMap<String, TransformationList>,
     camera: Option<Camera>,
+    settings: Option<RenderSettings>,
     lights: Vec<Light>,
     objects: Vec<Object>,
+    directory: PathBuf,
+    referenced: RefCell<HashSet<(&'static str, String)>>,
 }
 
 impl Data {
     pub fn new() -> Self {
         Self {
+            directory: PathBuf::new(),
             shapes: HashMap::new(),
             materials: HashMap::new(),
+            patterns: HashMap::new(),
             transformations: HashMap::new(),
             camera: None,
+            settings: None,
             lights: Vec::new(),
             objects: Vec::new(),
+            referenced: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Look up a defined shape, marking it as referenced.
+    pub fn shape(&self, name: &str) -> Option<&Add> {
+        self.reference("Shape", name);
+
+        self.shapes.get(name)
+    }
+
+    /// Look up a defined material, marking it as referenced.
+    pub fn material(&self, name: &str) -> Option<&Material> {
+        self.reference("Material", name);
+
+        self.materials.get(name)
+    }
+
+    /// Look up a defined pattern, marking it as referenced.
+    pub fn pattern(&self, name: &str) -> Option<&Value> {
+        self.reference("Pattern", name);
+
+        self.patterns.get(name)
+    }
+
+    /// Look up defined transformations, marking them as referenced.
+    pub fn transformations(&self, name: &str) -> Option<&TransformationList> {
+        self.reference("Transformations", name);
+
+        self.transformations.get(name)
+    }
+
+    fn reference(&self, kind: &'static str, name: &str) {
+        self.referenced.borrow_mut().insert((kind, name.to_string()));
+    }
+
+    /// Find the names of all defines that were never referenced, sorted so
+    /// that warnings are reported in a consistent order.
+    pub fn unreferenced(&self) -> Vec<String> {
+        let referenced = self.referenced.borrow();
+
+        let mut unreferenced = Vec::new();
+
+        for (kind, names) in [
+            ("Shape", self.shapes.keys().collect::<Vec<_>>()),
+            ("Material", self.materials.keys().collect()),
+            ("Pattern", self.patterns.keys().collect()),
+            ("Transformations", self.transformations.keys().collect()),
+        ] {
+            for name in names {
+                if !referenced.contains(&(kind, name.clone())) {
+                    unreferenced.push(format!("{kind} '{name}' is never used"));
+                }
+            }
         }
+
+        unreferenced.sort();
+
+        unreferenced
+    }
+
+    /// Resolve a path to an asset relative to the directory holding the scene
+    /// file, absolute paths are unchanged.
+    pub fn resolve<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.directory.join(path)
     }
 }
 
@@ -54,24 +137,46 @@ impl Data {
 pub struct Scene {
     camera: Camera,
     world: World,
+    #[new(default)]
+    settings: RenderSettings,
+    #[new(default)]
+    backplate: Option<Canvas>,
 }
 
 impl Scene {
-    /// Load a scene from a Yaml file.
+    /// Load a scene from a Yaml file. Relative paths to textures and OBJ files
+    /// are resolved against the directory containing the scene file. Warnings
+    /// about defines that are never used are written to `output`.
     ///
     /// # Errors
     ///
     /// Will return error if there are problems reading the file or parsing the
     /// data.
-    pub fn from_file<P, R>(filename: P, scale: f64, rng: &mut R) -> Result<Self>
+    pub fn from_file<P, O, R>(
+        filename: P,
+        scale: f64,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Self>
     where
         P: AsRef<Path>,
+        O: Write,
         R: Rng,
     {
-        let list: List = from_reader(File::open(filename)?)?;
+        let filename = filename.as_ref();
+
+        let list: List =
+            from_reader(File::open(filename).with_context(|| {
+                format!("Unable to open scene file '{}'", filename.display())
+            })?)?;
 
         let mut data = Data::new();
-        list.parse(&mut data, rng)?;
+        data.directory =
+            filename.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+
+        list.parse(&mut data, output, rng).with_context(|| {
+            format!("Unable to load scene file '{}'", filename.display())
+        })?;
 
         // We have already checked that camera is Some when parsing list.
         let Some(mut camera) = data.camera else { unreachable!() };
@@ -80,23 +185,387 @@ impl Scene {
         let mut world = World::new();
         world.lights = data.lights;
         world.objects = data.objects;
+        world.prepare_for_camera(&camera);
+
+        Ok(Self {
+            camera,
+            world,
+            settings: data.settings.unwrap_or_default(),
+            backplate: None,
+        })
+    }
+
+    /// Check the scene for common mistakes that would spoil a render, such as
+    /// missing lights, objects the camera can not see, transformations that
+    /// are not finite or transparent materials that do not refract. A scene
+    /// always has a camera so that is never reported.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        if self.world.lights().next().is_none() {
+            warnings.push(Warning::NoLights);
+        }
+
+        for (index, object) in self.world.objects.iter().enumerate() {
+            let shapes = object.shapes();
+
+            if shapes.iter().any(|shape| !shape.transformation().is_finite()) {
+                warnings.push(Warning::NonFiniteTransformation(index));
+            } else if !self.camera.can_see(&object.bounding_box()) {
+                warnings.push(Warning::NotVisible(index));
+            }
+
+            if shapes.iter().any(|shape| {
+                let material = shape.material();
+
+                material.transparency > 0.0
+                    && approx_eq!(material.refractive_index, 1.0)
+            }) {
+                warnings.push(Warning::TransparentWithoutRefraction(index));
+            }
+        }
+
+        warnings
+    }
+
+    /// Look for flat faces, planes and triangles, of different objects that
+    /// lie in the same plane and overlap as they will flicker between each
+    /// other. Overlaps are judged from bounding boxes so only obvious cases
+    /// are caught, and as every pair of faces is compared this is kept apart
+    /// from `validate`.
+    #[must_use]
+    pub fn find_coplanar_overlaps(&self) -> Vec<Warning> {
+        let faces = self
+            .world
+            .objects
+            .iter()
+            .map(|object| {
+                object
+                    .shapes()
+                    .into_iter()
+                    .filter_map(|shape| {
+                        shape.flat_surface().map(|(point, normal)| {
+                            (point, normal, shape.bounding_box())
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
 
-        Ok(Self { camera, world })
+        let mut warnings = Vec::new();
+
+        for (first, first_faces) in faces.iter().enumerate() {
+            for (second, second_faces) in
+                faces.iter().enumerate().skip(first + 1)
+            {
+                if first_faces.iter().any(|face| {
+                    second_faces.iter().any(|other| Self::overlap(face, other))
+                }) {
+                    warnings.push(Warning::CoplanarOverlap(first, second));
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Check if two flat faces, given as a point, normal and bounding box, are
+    /// coplanar and overlap.
+    #[must_use]
+    fn overlap(
+        (point, normal, bounds): &(Point, Vector, BoundingBox),
+        (other_point, other_normal, other_bounds): &(
+            Point,
+            Vector,
+            BoundingBox,
+        ),
+    ) -> bool {
+        const EPSILON: f64 = 1e-6;
+
+        if !approx_eq!(normal.dot(other_normal).abs(), 1.0, epsilon = EPSILON)
+            || (*other_point - *point).dot(normal).abs() >= EPSILON
+        {
+            return false;
+        }
+
+        // Planes go on forever so overlap anything lying in them.
+        if !bounds.is_finite() || !other_bounds.is_finite() {
+            return true;
+        }
+
+        let overlap = bounds.overlap(other_bounds);
+        let sizes = [overlap.x, overlap.y, overlap.z];
+
+        // Coplanar faces overlap in a flat box, so it must have some size
+        // along at least two of the axes.
+        sizes.iter().all(|size| *size > -EPSILON)
+            && sizes.iter().filter(|size| **size > EPSILON).count() >= 2
+    }
+
+    #[must_use]
+    pub const fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Replace any of the scene's render settings with those that are set in
+    /// `overrides`.
+    pub fn override_settings(&mut self, overrides: &RenderSettings) {
+        self.settings = self.settings.overridden_by(overrides);
+    }
+
+    /// Set the maximum number of intersections kept for any single ray, `None`
+    /// means there is no limit.
+    pub fn set_max_intersections(&mut self, maximum: Option<usize>) {
+        self.world.set_max_intersections(maximum);
     }
 
-    /// Render a scene to a `Canvas`.
+    /// Turn spectral rendering of dispersive materials on or off, see
+    /// `World::set_spectral`.
+    pub fn set_spectral(&mut self, spectral: bool) {
+        self.world.set_spectral(spectral);
+    }
+
+    /// Set how many lights are sampled at each hit, see
+    /// `World::set_light_samples`.
+    pub fn set_light_samples(&mut self, samples: Option<usize>) {
+        self.world.set_light_samples(samples);
+    }
+
+    /// Turn Russian roulette for reflections and refractions past the depth
+    /// limit on or off, see `World::set_russian_roulette`.
+    pub fn set_russian_roulette(&mut self, enabled: bool) {
+        self.world.set_russian_roulette(enabled);
+    }
+
+    /// Turn ambient occlusion on with a number of samples and a distance, or
+    /// off with `None`, see `World::set_ambient_occlusion`.
+    pub fn set_ambient_occlusion(&mut self, occlusion: Option<(u32, f64)>) {
+        self.world.set_ambient_occlusion(occlusion);
+    }
+
+    /// Load an image, such as a PNG or PPM, to be seen behind the render
+    /// wherever rays miss everything, see `Camera::render_with_backplate`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the image can not be loaded.
+    pub fn load_backplate<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+    ) -> Result<()> {
+        let filename = filename.as_ref();
+
+        self.backplate = Some(Canvas::load(filename).with_context(|| {
+            format!("Unable to load backplate '{}'", filename.display())
+        })?);
+
+        Ok(())
+    }
+
+    pub fn set_backplate(&mut self, backplate: Option<Canvas>) {
+        self.backplate = backplate;
+    }
+
+    /// Set how far along the surface normal shadow rays start.
+    pub fn set_shadow_bias(&mut self, bias: f64) {
+        self.world.set_shadow_bias(bias);
+    }
+
+    /// Pick an exposure in stops from the power of the lights in the scene. A
+    /// white surface lit head on by every light is the brightest pixel we
+    /// expect to see so this brings that to 1.0. Scenes without any light
+    /// keep an exposure of 0.0.
+    #[must_use]
+    pub fn auto_exposure(&self) -> f64 {
+        let power = self.world.total_light_power();
+
+        if power <= 0.0 {
+            return 0.0;
+        }
+
+        -power.log2()
+    }
+
+    /// Render a scene to a `Canvas` using the scene's render settings.
     ///
     /// # Errors
     ///
     /// Returns an error if there are problems writing status messages.
     pub fn render<O: Write, R: Rng>(
         &self,
-        depth: u32,
         single_threaded: bool,
+        anti_aliasing: AntiAliasing,
         output: &mut Output<O>,
         rng: &mut R,
     ) -> Result<Canvas> {
-        self.camera.render(&self.world, depth, single_threaded, output, rng)
+        self.render_with(
+            &self.settings,
+            single_threaded,
+            anti_aliasing,
+            output,
+            rng,
+        )
+    }
+
+    /// Render the scene with `settings` overriding the scene's own settings,
+    /// returning the width, height and tightly packed RGBA bytes of the image
+    /// ready for a browser canvas's `ImageData`. The render is single threaded,
+    /// is not timed and nothing is written to any file or output, see
+    /// `Camera::render_quietly`, so this can be used when compiled to
+    /// WebAssembly.
+    #[must_use]
+    pub fn render_rgba<R: Rng>(
+        &self,
+        settings: &RenderSettings,
+        rng: &mut R,
+    ) -> (u32, u32, Vec<u8>) {
+        let settings = self.settings.overridden_by(settings);
+
+        let mut canvas = self.camera_with(&settings).render_quietly(
+            &self.world,
+            self.backplate.as_ref(),
+            settings.depth(),
+            rng,
+        );
+
+        canvas.apply_exposure(settings.exposure());
+
+        let tonemap = settings.tonemap();
+
+        let bytes = canvas
+            .pixels()
+            .flat_map(|pixel| {
+                let [red, green, blue] = pixel.to_u8_with_mode(tonemap);
+
+                [red, green, blue, u8::MAX]
+            })
+            .collect();
+
+        (self.horizontal_size(), self.vertical_size(), bytes)
+    }
+
+    /// The scene's camera with the samples, render mode and sampling seed from
+    /// `settings`.
+    #[must_use]
+    fn camera_with(&self, settings: &RenderSettings) -> Camera {
+        self.camera
+            .with_samples(settings.samples())
+            .with_render_mode(settings.mode())
+            .with_sampling_seed(settings.sampling_seed())
+    }
+
+    fn render_with<O: Write, R: Rng>(
+        &self,
+        settings: &RenderSettings,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Canvas> {
+        let camera = self.camera_with(settings);
+
+        let mut canvas = match &self.backplate {
+            Some(backplate) => camera.render_with_backplate(
+                &self.world,
+                backplate,
+                settings.depth(),
+                single_threaded,
+                anti_aliasing,
+                output,
+                rng,
+            ),
+            None => camera.render(
+                &self.world,
+                settings.depth(),
+                single_threaded,
+                anti_aliasing,
+                output,
+                rng,
+            ),
+        }?;
+
+        canvas.apply_exposure(settings.exposure());
+
+        Ok(canvas)
+    }
+
+    /// Render the scene as `render` does and write the image to `filename`.
+    /// Files ending in `.ppm` are written as plain PPM, any other extension
+    /// picks an image format supported by the `image` crate. The rendered
+    /// canvas is returned as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are problems writing status messages or if
+    /// the image can not be written to the file.
+    pub fn render_to_file<P: AsRef<Path>, O: Write, R: Rng>(
+        &self,
+        filename: P,
+        single_threaded: bool,
+        anti_aliasing: AntiAliasing,
+        output: &mut Output<O>,
+        rng: &mut R,
+    ) -> Result<Canvas> {
+        let canvas =
+            self.render(single_threaded, anti_aliasing, output, rng)?;
+
+        let filename = filename.as_ref();
+
+        writeln!(output, "Writing to file {}", filename.display())?;
+
+        let tonemap = self.settings.tonemap();
+
+        if filename
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ppm"))
+        {
+            write(filename, canvas.to_ppm_with_mode(tonemap))
+                .map_err(Error::from)
+        } else {
+            #[allow(clippy::cast_possible_truncation)]
+            let image = ImageBuffer::from_fn(
+                self.horizontal_size(),
+                self.vertical_size(),
+                |x, y| {
+                    Rgb(canvas
+                        .get_pixel(x as usize, y as usize)
+                        .to_u8_with_mode(tonemap))
+                },
+            );
+
+            image.save(filename).map_err(Error::from)
+        }
+        .with_context(|| {
+            format!("Unable to write image '{}'", filename.display())
+        })?;
+
+        Ok(canvas)
+    }
+
+    /// Count the shapes, groups and lights in the scene along with the
+    /// resolution of the camera.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        let mut summary = self.world.summary();
+
+        summary.set_resolution(
+            self.camera.horizontal_size(),
+            self.camera.vertical_size(),
+        );
+
+        summary
+    }
+
+    /// An approximate count of the bytes needed to render the scene, made up
+    /// of the object tree, materials, lights and the output canvas. This is
+    /// intended to help size scenes before rendering them.
+    #[must_use]
+    pub fn estimated_memory(&self) -> usize {
+        let pixels = self.camera.horizontal_size() as usize
+            * self.camera.vertical_size() as usize;
+
+        self.world.estimated_memory() + pixels * size_of::<Colour>()
     }
 
     #[must_use]
@@ -289,7 +758,105 @@ impl Scene {
             Colour::new(0.5, 0.5, 0.5),
         ));
 
-        Self { camera, world }
+        Self::new(camera, world)
+    }
+
+    /// Generate a fixed workload for comparing performance, the same
+    /// `complexity` and random number generator state always give the same
+    /// scene. Each step of complexity adds 10 spheres and 50 triangles in front
+    /// of a fixed 320x240 camera.
+    #[must_use]
+    pub fn benchmark_scene<R: Rng>(complexity: u32, rng: &mut R) -> Self {
+        use crate::{
+            math::{Angle, Point, Transformation, Vector},
+            Colour, Material,
+        };
+
+        let camera = Camera::new(
+            320,
+            240,
+            Angle(FRAC_PI_3),
+            Transformation::view_transformation(
+                Point::new(0.0, 5.0, -15.0),
+                Point::new(0.0, 1.0, 10.0),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut world = World::new();
+
+        world.add_object(Object::plane_builder().build());
+
+        let random_material = |rng: &mut R| {
+            Material::builder()
+                .pattern(
+                    Colour::new(
+                        rng.gen_range(0.0..=1.0),
+                        rng.gen_range(0.0..=1.0),
+                        rng.gen_range(0.0..=1.0),
+                    )
+                    .into(),
+                )
+                .reflective(rng.gen_range(0.0..=0.5))
+                .build()
+        };
+
+        let random_point = |rng: &mut R| {
+            Point::new(
+                rng.gen_range(-10.0..=10.0),
+                rng.gen_range(0.5..=5.0),
+                rng.gen_range(0.0..=20.0),
+            )
+        };
+
+        let spheres = (0..complexity * 10)
+            .map(|_| {
+                let centre = random_point(rng);
+                let radius = rng.gen_range(0.2..=0.5);
+
+                Object::sphere_builder()
+                    .transformation(
+                        Transformation::new()
+                            .scale(radius, radius, radius)
+                            .translate(centre.x, centre.y, centre.z),
+                    )
+                    .material(random_material(rng))
+                    .build()
+            })
+            .collect();
+
+        let offset = |rng: &mut R| {
+            Vector::new(
+                rng.gen_range(-0.5..=0.5),
+                rng.gen_range(-0.5..=0.5),
+                rng.gen_range(-0.5..=0.5),
+            )
+        };
+
+        let triangles = (0..complexity * 50)
+            .map(|_| {
+                let centre = random_point(rng);
+
+                Object::flat_triangle_builder(
+                    centre + offset(rng),
+                    centre + offset(rng),
+                    centre + offset(rng),
+                )
+                .material(random_material(rng))
+                .build()
+            })
+            .collect();
+
+        world.add_object(Object::group_builder().set_objects(spheres).build());
+        world
+            .add_object(Object::group_builder().set_objects(triangles).build());
+
+        world.add_light(Light::new_point(
+            Point::new(-10.0, 20.0, -10.0),
+            Colour::white(),
+        ));
+
+        Self::new(camera, world)
     }
 }
 
@@ -301,16 +868,21 @@ mod tests {
 
     use super::*;
     use crate::{
-        math::{float::*, Angle, Point, Transformation, Vector},
-        Colour,
+        math::{float::*, Angle, Transformation},
+        ClampMode, Pattern,
     };
 
     #[test]
     fn from_simple_yaml() {
         let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
 
-        let s = Scene::from_file("src/scene/tests/simple.yaml", 1.0, &mut r)
-            .unwrap();
+        let s = Scene::from_file(
+            "src/scene/tests/simple.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
 
         assert_approx_eq!(
             s.camera,
@@ -332,16 +904,525 @@ mod tests {
             Light::new_point(Point::new(-10.0, 10.0, -10.0), Colour::white())
         );
 
-        s.render(5, true, &mut Output::<Vec<_>>::new_sink(), &mut r).unwrap();
+        assert_eq!(s.settings, RenderSettings::default());
+
+        s.render(
+            true,
+            AntiAliasing::None,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
     }
 
     #[test]
-    fn test_scale() {
+    fn estimating_the_memory_used_by_a_scene() {
+        let scene = |triangles: u32, size: u32| {
+            let mut w = World::new();
+
+            w.add_object(
+                Object::group_builder()
+                    .set_objects(
+                        (0..triangles)
+                            .map(|index| {
+                                let x = f64::from(index);
+
+                                Object::flat_triangle_builder(
+                                    Point::new(x, 0.0, 0.0),
+                                    Point::new(x + 1.0, 0.0, 0.0),
+                                    Point::new(x, 1.0, 0.0),
+                                )
+                                .build()
+                            })
+                            .collect(),
+                    )
+                    .build(),
+            );
+
+            Scene::new(
+                Camera::new(
+                    size,
+                    size,
+                    Angle(FRAC_PI_3),
+                    Transformation::new(),
+                ),
+                w,
+            )
+        };
+
+        let estimates = [0, 1, 10, 100, 1_000]
+            .map(|triangles| scene(triangles, 10).estimated_memory());
+
+        assert!(estimates.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(
+            scene(10, 20).estimated_memory() - scene(10, 10).estimated_memory(),
+            300 * size_of::<Colour>()
+        );
+    }
+
+    #[test]
+    fn picking_an_exposure_from_the_lights() {
+        let scene = |intensity: f64| {
+            let mut w = World::new();
+
+            w.add_light(Light::new_point(
+                Point::new(0.0, 5.0, 0.0),
+                Colour::new(intensity, intensity, intensity),
+            ));
+            w.add_light(Light::new_area(
+                Point::new(-1.0, 5.0, -1.0),
+                Vector::new(2.0, 0.0, 0.0),
+                2,
+                Vector::new(0.0, 0.0, 2.0),
+                2,
+                Colour::new(0.5 * intensity, intensity, 1.5 * intensity),
+            ));
+
+            Scene::new(
+                Camera::new(10, 10, Angle(FRAC_PI_3), Transformation::new()),
+                w,
+            )
+        };
+
+        let exposure = scene(1.0).auto_exposure();
+
+        // The point light gives 1.0 and the area light 1.0 over an area of 4.
+        assert_approx_eq!(exposure, -5.0_f64.log2());
+
+        let brighter = scene(2.0).auto_exposure();
+
+        assert_approx_eq!(brighter.exp2(), exposure.exp2() / 2.0);
+
+        assert_approx_eq!(
+            Scene::new(
+                Camera::new(10, 10, Angle(FRAC_PI_3), Transformation::new()),
+                World::new(),
+            )
+            .auto_exposure(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn rendering_a_scene_to_rgba_bytes() {
+        let mut s = Scene::from_file(
+            "src/scene/tests/simple.yaml",
+            0.1,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut Xoshiro256PlusPlus::seed_from_u64(0),
+        )
+        .unwrap();
+
+        let settings = RenderSettings::builder()
+            .samples(Some(1))
+            .exposure(Some(0.5))
+            .build();
+
+        let (width, height, bytes) =
+            s.render_rgba(&settings, &mut Xoshiro256PlusPlus::seed_from_u64(1));
+
+        assert_eq!(width, s.horizontal_size());
+        assert_eq!(height, s.vertical_size());
+        assert_eq!(bytes.len(), (width * height * 4) as usize);
+
+        s.override_settings(&settings);
+
+        let c = s
+            .render(
+                true,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut Xoshiro256PlusPlus::seed_from_u64(1),
+            )
+            .unwrap();
+
+        for (rgba, pixel) in bytes.chunks_exact(4).zip(c.pixels()) {
+            let [red, green, blue] =
+                pixel.to_u8_with_mode(s.settings().tonemap());
+
+            assert_eq!(rgba, [red, green, blue, 255]);
+        }
+    }
+
+    #[test]
+    fn rendering_a_scene_to_a_file() {
         let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
 
-        let s = Scene::from_file("src/scene/tests/simple.yaml", 2.5, &mut r)
+        let mut s = Scene::from_file(
+            "src/scene/tests/simple.yaml",
+            0.1,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+        s.override_settings(
+            &RenderSettings::builder().samples(Some(1)).build(),
+        );
+
+        let filename = std::env::temp_dir().join(format!(
+            "raytracer-render-to-file-{}.ppm",
+            std::process::id()
+        ));
+
+        let c = s
+            .render_to_file(
+                &filename,
+                true,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut r,
+            )
             .unwrap();
 
+        let loaded = Canvas::load(&filename).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        assert_eq!(loaded.pixels().count(), c.pixels().count());
+
+        for (pixel, expected) in loaded.pixels().zip(c.pixels()) {
+            let [red, green, blue] =
+                expected.to_u8_with_mode(s.settings().tonemap());
+
+            // The file holds 8 bit values which are read back as sRGB.
+            assert_approx_eq!(
+                *pixel,
+                Colour::new(
+                    f64::from(red) / 255.0,
+                    f64::from(green) / 255.0,
+                    f64::from(blue) / 255.0
+                )
+                .to_linear()
+            );
+        }
+
+        assert!(s
+            .render_to_file(
+                "missing-directory/image.png",
+                true,
+                AntiAliasing::None,
+                &mut Output::<Vec<_>>::new_sink(),
+                &mut r,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn summarising_simple_yaml() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let s = Scene::from_file(
+            "src/scene/tests/simple.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap()
+        .summary();
+
+        assert_eq!(s.resolution(), Some((200, 200)));
+        assert_eq!(s.shapes(None), 1);
+        assert_eq!(s.shapes(Some("cube")), 1);
+        assert_eq!(s.lights(Some("point")), 1);
+
+        assert_eq!(
+            s.to_string(),
+            "\
+Resolution: 200 by 200
+Shapes: 1 (cube: 1)
+Groups: 0
+CSGs: 0
+Lights: 1 (point: 1)
+"
+        );
+    }
+
+    #[test]
+    fn scene_settings_are_overridden() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let mut s = Scene::from_file(
+            "src/scene/tests/settings.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+
+        assert_eq!(s.settings().depth(), 3);
+        assert_eq!(s.settings().tonemap(), ClampMode::HuePreserving);
+        assert_eq!(s.settings().samples(), 1);
+
+        s.override_settings(&RenderSettings::default());
+
+        assert_eq!(s.settings().depth(), 3);
+
+        s.override_settings(
+            &RenderSettings::builder().depth(Some(7)).samples(Some(2)).build(),
+        );
+
+        assert_eq!(s.settings().depth(), 7);
+        assert_eq!(s.settings().samples(), 2);
+        assert_eq!(s.settings().tonemap(), ClampMode::HuePreserving);
+
+        s.render(
+            true,
+            AntiAliasing::None,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn validating_a_scene() {
+        let camera = Camera::new(
+            20,
+            20,
+            Angle(FRAC_PI_3),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(Transformation::new().translate(
+                    f64::NAN,
+                    0.0,
+                    0.0,
+                ))
+                .build(),
+        );
+
+        assert_eq!(
+            Scene::new(camera, w).validate(),
+            vec![Warning::NoLights, Warning::NonFiniteTransformation(0)]
+        );
+
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(Object::sphere_builder().build());
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().translate(0.0, 0.0, -10.0),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::group_builder()
+                .add_object(Object::plane_builder().build())
+                .add_object(
+                    Object::sphere_builder()
+                        .material(
+                            crate::Material::builder()
+                                .transparency(0.9)
+                                .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder().material(crate::Material::glass()).build(),
+        );
+
+        assert_eq!(
+            Scene::new(camera, w).validate(),
+            vec![
+                Warning::NotVisible(1),
+                Warning::TransparentWithoutRefraction(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn finding_overlapping_coplanar_faces() {
+        let camera = Camera::new(
+            20,
+            20,
+            Angle(FRAC_PI_3),
+            Transformation::view_transformation(
+                Point::new(0.0, 5.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let triangle = |x: f64, y: f64| {
+            Object::flat_triangle_builder(
+                Point::new(x, y, 0.0),
+                Point::new(x + 2.0, y, 0.0),
+                Point::new(x, y + 2.0, 0.0),
+            )
+            .build()
+        };
+
+        let mut w = World::new();
+
+        w.add_object(Object::plane_builder().build());
+        w.add_object(Object::sphere_builder().build());
+        w.add_object(
+            Object::group_builder()
+                .add_object(Object::sphere_builder().build())
+                .add_object(
+                    Object::plane_builder()
+                        .transformation(
+                            Transformation::new().scale(3.0, 3.0, 3.0),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(triangle(0.0, 0.0));
+        w.add_object(triangle(1.0, 1.0));
+
+        assert_eq!(
+            Scene::new(camera, w).find_coplanar_overlaps(),
+            vec![
+                Warning::CoplanarOverlap(0, 2),
+                Warning::CoplanarOverlap(3, 4)
+            ]
+        );
+
+        let mut w = World::new();
+
+        w.add_object(Object::plane_builder().build());
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, 0.01, 0.0))
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(
+                    Transformation::new().rotate_x(Angle(FRAC_PI_3)),
+                )
+                .build(),
+        );
+        w.add_object(triangle(0.0, 0.0));
+        // Touching along an edge is not an overlap.
+        w.add_object(triangle(2.0, 0.0));
+        w.add_object(triangle(5.0, 5.0));
+
+        assert!(Scene::new(camera, w).find_coplanar_overlaps().is_empty());
+    }
+
+    #[test]
+    fn missing_files_are_named_in_errors() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let e = Scene::from_file(
+            "src/scene/tests/missing-obj.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{e:#}"),
+            "\
+Unable to load scene file 'src/scene/tests/missing-obj.yaml': Unable to open \
+OBJ file 'src/scene/tests/missing.obj': No such file or directory (os error 2)"
+        );
+
+        let e = Scene::from_file(
+            "src/scene/tests/missing.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{e:#}"),
+            "\
+Unable to open scene file 'src/scene/tests/missing.yaml': No such file or \
+directory (os error 2)"
+        );
+    }
+
+    #[test]
+    fn assets_are_relative_to_the_scene_file() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let s = Scene::from_file(
+            "src/scene/tests/assets/textured.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+
+        assert_eq!(s.world.objects.len(), 2);
+
+        assert_approx_eq!(
+            s.world.objects[0].material().pattern,
+            &Pattern::load_image_builder(
+                "src/scene/tests/assets/texture.png",
+                false
+            )
+            .unwrap()
+            .build()
+        );
+        assert_approx_eq!(
+            s.world.objects[1],
+            &Object::from_file("src/scene/tests/simple.obj").unwrap().build()
+        );
+    }
+
+    #[test]
+    fn named_patterns_are_shared_by_materials() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let s = Scene::from_file(
+            "src/scene/tests/patterns.yaml",
+            1.0,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+
+        assert_eq!(s.world.objects.len(), 2);
+
+        let p = Pattern::checker_builder(
+            Colour::red().into(),
+            Colour::blue().into(),
+        )
+        .transformation(Transformation::new().scale(0.5, 0.5, 0.5))
+        .build();
+
+        let plane = s.world.objects[0].material();
+        let sphere = s.world.objects[1].material();
+
+        assert_approx_eq!(plane.pattern, &p);
+        assert_approx_eq!(sphere.pattern, &p);
+        assert_approx_eq!(plane.reflective, 0.5);
+        assert_approx_eq!(sphere.diffuse, 0.5);
+    }
+
+    #[test]
+    fn test_scale() {
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let s = Scene::from_file(
+            "src/scene/tests/simple.yaml",
+            2.5,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+
         assert_eq!(s.horizontal_size(), 500);
         assert_eq!(s.vertical_size(), 500);
     }
@@ -352,6 +1433,44 @@ mod tests {
 
         let s = Scene::generate_random_spheres(0.1, &mut r);
 
-        s.render(5, true, &mut Output::<Vec<_>>::new_sink(), &mut r).unwrap();
+        s.render(
+            true,
+            AntiAliasing::None,
+            &mut Output::<Vec<_>>::new_sink(),
+            &mut r,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn benchmark_scenes_are_reproducible() {
+        let s1 = Scene::benchmark_scene(
+            2,
+            &mut Xoshiro256PlusPlus::seed_from_u64(7),
+        );
+        let s2 = Scene::benchmark_scene(
+            2,
+            &mut Xoshiro256PlusPlus::seed_from_u64(7),
+        );
+
+        let u1 = s1.summary();
+        let u2 = s2.summary();
+
+        assert_eq!(u1.shapes(Some("sphere")), 20);
+        assert_eq!(u1.shapes(Some("triangle")), 100);
+        assert_eq!(u1.shapes(None), u2.shapes(None));
+        assert_eq!(u1.resolution(), Some((320, 240)));
+
+        assert_approx_eq!(s1.camera, s2.camera);
+
+        assert_approx_eq!(s1.world.objects[1], &s2.world.objects[1]);
+        assert_approx_eq!(s1.world.objects[2], &s2.world.objects[2]);
+
+        let s3 = Scene::benchmark_scene(
+            4,
+            &mut Xoshiro256PlusPlus::seed_from_u64(7),
+        );
+
+        assert_eq!(s3.summary().shapes(None), 241);
     }
 }
@@ -0,0 +1,164 @@
+use serde::Deserialize;
+use typed_builder::TypedBuilder;
+
+use crate::{ClampMode, RenderMode};
+
+/// `RenderSettings` holds the options that control how a scene is rendered.
+/// Each setting is optional so that settings from a scene file can be
+/// overridden by those given elsewhere, such as on the command line, with
+/// defaults used for anything that is never set.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, TypedBuilder)]
+#[serde(deny_unknown_fields)]
+pub struct RenderSettings {
+    #[builder(default = None)]
+    depth: Option<u32>,
+    #[builder(default = None)]
+    samples: Option<u32>,
+    #[builder(default = None)]
+    tonemap: Option<ClampMode>,
+    #[builder(default = None)]
+    exposure: Option<f64>,
+    #[builder(default = None)]
+    mode: Option<RenderMode>,
+    #[builder(default = None)]
+    #[serde(rename = "sampling-seed")]
+    sampling_seed: Option<u64>,
+}
+
+impl RenderSettings {
+    /// The default maximum recursion depth for reflections and refractions.
+    pub const DEFAULT_DEPTH: u32 = 5;
+
+    /// Combine two sets of settings, any setting given in `overrides` takes
+    /// precedence over the same setting in `self`.
+    #[must_use]
+    pub fn overridden_by(&self, overrides: &Self) -> Self {
+        Self {
+            depth: overrides.depth.or(self.depth),
+            samples: overrides.samples.or(self.samples),
+            tonemap: overrides.tonemap.or(self.tonemap),
+            exposure: overrides.exposure.or(self.exposure),
+            mode: overrides.mode.or(self.mode),
+            sampling_seed: overrides.sampling_seed.or(self.sampling_seed),
+        }
+    }
+
+    #[must_use]
+    pub fn depth(&self) -> u32 {
+        self.depth.unwrap_or(Self::DEFAULT_DEPTH)
+    }
+
+    /// The number of samples along each axis of a pixel.
+    #[must_use]
+    pub fn samples(&self) -> u32 {
+        self.samples.unwrap_or(1)
+    }
+
+    #[must_use]
+    pub fn tonemap(&self) -> ClampMode {
+        self.tonemap.unwrap_or_default()
+    }
+
+    /// The exposure in stops, each stop doubles the brightness of the image.
+    #[must_use]
+    pub fn exposure(&self) -> f64 {
+        self.exposure.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> RenderMode {
+        self.mode.unwrap_or_default()
+    }
+
+    /// The seed for sampling within pixels, when `None` it is drawn from the
+    /// scene's random number generator.
+    #[must_use]
+    pub const fn sampling_seed(&self) -> Option<u64> {
+        self.sampling_seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::from_str;
+
+    use super::*;
+    use crate::math::float::*;
+
+    #[test]
+    fn creating_render_settings() {
+        let s = RenderSettings::default();
+
+        assert_eq!(s.depth(), 5);
+        assert_eq!(s.samples(), 1);
+        assert_eq!(s.tonemap(), ClampMode::PerChannel);
+        assert_approx_eq!(s.exposure(), 0.0);
+        assert_eq!(s.mode(), RenderMode::Shaded);
+        assert_eq!(s.sampling_seed(), None);
+
+        let s = RenderSettings::builder()
+            .depth(Some(3))
+            .samples(Some(2))
+            .tonemap(Some(ClampMode::HuePreserving))
+            .exposure(Some(-0.5))
+            .build();
+
+        assert_eq!(s.depth(), 3);
+        assert_eq!(s.samples(), 2);
+        assert_eq!(s.tonemap(), ClampMode::HuePreserving);
+        assert_approx_eq!(s.exposure(), -0.5);
+    }
+
+    #[test]
+    fn overriding_render_settings() {
+        let s = RenderSettings::builder()
+            .depth(Some(3))
+            .exposure(Some(1.0))
+            .build();
+
+        let o = s.overridden_by(&RenderSettings::default());
+
+        assert_eq!(o, s);
+
+        let o = s.overridden_by(
+            &RenderSettings::builder().depth(Some(8)).samples(Some(4)).build(),
+        );
+
+        assert_eq!(o.depth(), 8);
+        assert_eq!(o.samples(), 4);
+        assert_eq!(o.tonemap(), ClampMode::PerChannel);
+        assert_approx_eq!(o.exposure(), 1.0);
+    }
+
+    #[test]
+    fn deserialize_render_settings() {
+        let s: RenderSettings = from_str(
+            "\
+depth: 2
+samples: 3
+tonemap: hue-preserving
+exposure: 0.5
+mode: uv
+sampling-seed: 42",
+        )
+        .unwrap();
+
+        assert_eq!(
+            s,
+            RenderSettings::builder()
+                .depth(Some(2))
+                .samples(Some(3))
+                .tonemap(Some(ClampMode::HuePreserving))
+                .exposure(Some(0.5))
+                .mode(Some(RenderMode::Uv))
+                .sampling_seed(Some(42))
+                .build()
+        );
+
+        let s: RenderSettings = from_str("depth: 1").unwrap();
+
+        assert_eq!(s, RenderSettings::builder().depth(Some(1)).build());
+
+        assert!(from_str::<RenderSettings>("gamma: 2.2").is_err());
+    }
+}
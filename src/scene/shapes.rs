@@ -7,7 +7,11 @@ use serde::Deserialize;
 use serde_yaml::{from_value, to_value, Value};
 
 use super::{Add, Data, HashValue, Material, TransformationList};
-use crate::{Object, Operation};
+use crate::{
+    math::{Angle, Point, Vector},
+    object::Updatable,
+    Handedness, Object, Operation, Shading, UpAxis,
+};
 
 macro_rules! create_shape {
     ($name:ident { $($arg:ident: $ty:ty $(,)?)* }) => {
@@ -27,18 +31,40 @@ macro_rules! create_shape {
 create_shape!(Cone {
     min: Option<f64>,
     max: Option<f64>,
-    closed: Option<bool>
+    closed: Option<bool>,
+    cap_min: Option<bool>,
+    cap_max: Option<bool>,
+    wedge: Option<[Angle; 2]>,
+    clip: Option<Vec<(Point, Vector)>>
 });
-create_shape!(Cube {});
+create_shape!(Cube { clip: Option<Vec<(Point, Vector)>> });
 create_shape!(Cylinder {
     min: Option<f64>,
     max: Option<f64>,
-    closed: Option<bool>
+    closed: Option<bool>,
+    cap_min: Option<bool>,
+    cap_max: Option<bool>,
+    wedge: Option<[Angle; 2]>,
+    clip: Option<Vec<(Point, Vector)>>
 });
-create_shape!(Group { children: Vec<Add>, divide: Option<u32> });
-create_shape!(Obj { file: String, divide: Option<u32>});
-create_shape!(Plane {});
-create_shape!(Sphere {});
+create_shape!(Group {
+    children: Vec<Add>,
+    divide: Option<u32>,
+    shading: Option<Shading>
+});
+create_shape!(Obj {
+    file: String,
+    up: Option<UpAxis>,
+    handedness: Option<Handedness>,
+    divide: Option<u32>,
+    shading: Option<Shading>
+});
+create_shape!(Plane { clip: Option<Vec<(Point, Vector)>> });
+create_shape!(RoundedBox {
+    radius: Option<f64>,
+    clip: Option<Vec<(Point, Vector)>>
+});
+create_shape!(Sphere { clip: Option<Vec<(Point, Vector)>> });
 
 /// `CsgShape` is a helper type since the Yaml definition uses a different tag
 /// than when adding objects, and this saves us converting to from a `HashMap`
@@ -105,9 +131,10 @@ macro_rules! impl_parse {
                 rng: &mut R
             ) -> Result<Object> {
                 paste! {
-                    let object = Object::[<$name:lower _builder>](
+                    let object = Object::[<$name:snake _builder>](
                         $(self.$arg.unwrap_or($default),)*
-                    );
+                    )
+                    .clip_planes(self.clip.unwrap_or_default());
 
                     Ok(build_object!(object, self, data, rng))
                 }
@@ -116,10 +143,63 @@ macro_rules! impl_parse {
     };
 }
 
-impl_parse!(Cone { min: NEG_INFINITY, max: INFINITY, closed: false });
+/// Cones and cylinders can optionally be limited to a wedge, both builders
+/// return the same type so we can pick one before building the object. Closed
+/// caps both ends but each end can also be set separately.
+macro_rules! impl_parse_with_wedge {
+    ($name:ident) => {
+        impl $name {
+            pub fn parse<R: Rng>(
+                self,
+                data: &Data,
+                rng: &mut R,
+            ) -> Result<Object> {
+                let minimum = self.min.unwrap_or(NEG_INFINITY);
+                let maximum = self.max.unwrap_or(INFINITY);
+                let closed = self.closed.unwrap_or(false);
+                let cap_min = self.cap_min.unwrap_or(closed);
+                let cap_max = self.cap_max.unwrap_or(closed);
+
+                if minimum.is_nan() || maximum.is_nan() {
+                    bail!(
+                        "{} min {minimum} and max {maximum} must be numbers",
+                        stringify!($name)
+                    );
+                }
+
+                if minimum > maximum {
+                    bail!(
+                        "{} min {minimum} is greater than max {maximum}",
+                        stringify!($name)
+                    );
+                }
+
+                paste! {
+                    let object = match self.wedge {
+                        Some([from, to]) => {
+                            Object::[<$name:lower _wedge_builder>](
+                                minimum, maximum, closed, from, to,
+                            )
+                        }
+                        None => Object::[<$name:lower _builder>](
+                            minimum, maximum, closed,
+                        ),
+                    }
+                    .clip_planes(self.clip.unwrap_or_default());
+                }
+
+                Ok(build_object!(object, self, data, rng)
+                    .with_caps(cap_min, cap_max))
+            }
+        }
+    };
+}
+
+impl_parse_with_wedge!(Cone);
 impl_parse!(Cube {});
-impl_parse!(Cylinder { min: NEG_INFINITY, max: INFINITY, closed: false });
+impl_parse_with_wedge!(Cylinder);
 impl_parse!(Plane {});
+impl_parse!(RoundedBox { radius: 0.1 });
 impl_parse!(Sphere {});
 
 impl Group {
@@ -138,13 +218,21 @@ impl Group {
             object = object.divide(divide);
         };
 
+        if let Some(shading) = self.shading {
+            object.update_shading(shading);
+        }
+
         Ok(object)
     }
 }
 
 impl Obj {
     pub fn parse<R: Rng>(self, data: &Data, rng: &mut R) -> Result<Object> {
-        let group = Object::from_file(self.file)?;
+        let group = Object::from_file_with(
+            data.resolve(self.file),
+            self.up.unwrap_or_default(),
+            self.handedness.unwrap_or_default(),
+        )?;
 
         let mut object = build_object!(group, self, data, rng);
 
@@ -152,6 +240,10 @@ impl Obj {
             object = object.divide(divide);
         };
 
+        if let Some(shading) = self.shading {
+            object.update_shading(shading);
+        }
+
         Ok(object)
     }
 }
@@ -188,9 +280,10 @@ pub fn parse_shape<R: Rng>(
         "group" => map_to_object!("group"),
         "obj" => map_to_object!("obj"),
         "plane" => map_to_object!("plane"),
+        "rounded-box" => map_to_object!("rounded_box"),
         "sphere" => map_to_object!("sphere"),
         _ => {
-            if let Some(define) = data.shapes.get(tag) {
+            if let Some(define) = data.shape(tag) {
                 let mut shape: HashValue = from_value(value)?;
 
                 let define = define.clone();
@@ -232,12 +325,14 @@ pub fn parse_shape<R: Rng>(
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
     use rand_xoshiro::Xoshiro256PlusPlus;
     use serde_yaml::from_str;
 
     use super::*;
     use crate::{
-        math::{float::*, Transformation},
+        math::{float::*, Ray, Transformation},
         Colour,
     };
 
@@ -280,6 +375,147 @@ transform:
         assert_approx_eq!(o, &Object::cube_builder().build());
     }
 
+    #[test]
+    fn parse_rounded_box() {
+        let d = Data::new();
+
+        let b: RoundedBox = from_str("radius: 0.3").unwrap();
+
+        let o = b.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+        assert_approx_eq!(o, &Object::rounded_box_builder(0.3).build());
+
+        let b: RoundedBox = from_str("").unwrap();
+
+        let o = b.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+        assert_approx_eq!(o, &Object::rounded_box_builder(0.1).build());
+    }
+
+    #[test]
+    fn parse_cylinder_wedge() {
+        let c: Cylinder = from_str(
+            "\
+min: 0
+max: 1
+closed: true
+wedge: [0, \"PI / 2\"]",
+        )
+        .unwrap();
+
+        let o = c
+            .parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+            .unwrap();
+        assert_approx_eq!(
+            o,
+            &Object::cylinder_wedge_builder(
+                0.0,
+                1.0,
+                true,
+                Angle(0.0),
+                Angle(FRAC_PI_2)
+            )
+            .build()
+        );
+        assert_approx_ne!(o, &Object::cylinder_builder(0.0, 1.0, true).build());
+    }
+
+    #[test]
+    fn parse_cylinder_caps() {
+        let c: Cylinder = from_str(
+            "\
+min: 0
+max: 1
+cap_max: true",
+        )
+        .unwrap();
+
+        let o = c
+            .parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+            .unwrap();
+        assert_approx_eq!(
+            o,
+            &Object::cylinder_builder(0.0, 1.0, false)
+                .build()
+                .with_caps(false, true)
+        );
+
+        let c: Cylinder = from_str(
+            "\
+min: 0
+max: 1
+closed: true
+cap_min: false",
+        )
+        .unwrap();
+
+        let o = c
+            .parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+            .unwrap();
+        assert_approx_eq!(
+            o,
+            &Object::cylinder_builder(0.0, 1.0, true)
+                .build()
+                .with_caps(false, true)
+        );
+        assert_approx_ne!(o, &Object::cylinder_builder(0.0, 1.0, true).build());
+    }
+
+    #[test]
+    fn parse_cylinder_with_no_height() {
+        let c: Cylinder = from_str(
+            "\
+min: 1
+max: 1
+closed: true",
+        )
+        .unwrap();
+
+        let o = c
+            .parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+            .unwrap();
+        assert_approx_eq!(o, &Object::cylinder_builder(1.0, 1.0, true).build());
+
+        let c: Cylinder = from_str(
+            "\
+min: 2
+max: 1",
+        )
+        .unwrap();
+
+        assert_eq!(
+            c.parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+                .unwrap_err()
+                .to_string(),
+            "Cylinder min 2 is greater than max 1"
+        );
+
+        let c: Cone = from_str("min: 0.5\nmax: -0.5").unwrap();
+
+        assert_eq!(
+            c.parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+                .unwrap_err()
+                .to_string(),
+            "Cone min 0.5 is greater than max -0.5"
+        );
+
+        let c: Cylinder = from_str("min: .nan\nmax: 1").unwrap();
+
+        assert_eq!(
+            c.parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+                .unwrap_err()
+                .to_string(),
+            "Cylinder min NaN and max 1 must be numbers"
+        );
+
+        let c: Cone = from_str("max: .nan").unwrap();
+
+        assert_eq!(
+            c.parse(&Data::new(), &mut Xoshiro256PlusPlus::seed_from_u64(0))
+                .unwrap_err()
+                .to_string(),
+            "Cone min -inf and max NaN must be numbers"
+        );
+    }
+
     #[test]
     fn parse_cylinder() {
         let c: Cylinder = from_str(
@@ -400,6 +636,90 @@ divide: 1",
         );
     }
 
+    #[test]
+    fn parse_z_up_obj() {
+        let o: Obj = from_str(
+            "\
+add: obj
+file: src/object/tests/z_up.obj
+up: z",
+        )
+        .unwrap();
+
+        let d = Data::new();
+
+        let o = o.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        assert_approx_eq!(
+            o,
+            &Object::from_file_with(
+                "src/object/tests/z_up.obj",
+                UpAxis::Z,
+                Handedness::Right
+            )
+            .unwrap()
+            .build()
+        );
+        assert_approx_ne!(
+            o,
+            &Object::from_file("src/object/tests/z_up.obj").unwrap().build()
+        );
+    }
+
+    #[test]
+    fn parse_left_handed_obj() {
+        let o: Obj = from_str(
+            "\
+add: obj
+file: src/object/tests/asymmetric.obj
+handedness: left",
+        )
+        .unwrap();
+
+        let d = Data::new();
+
+        let o = o.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        assert_approx_eq!(
+            o,
+            &Object::from_file_with(
+                "src/object/tests/asymmetric.obj",
+                UpAxis::Y,
+                Handedness::Left
+            )
+            .unwrap()
+            .build()
+        );
+    }
+
+    #[test]
+    fn parse_flat_shaded_obj() {
+        let o: Obj = from_str(
+            "\
+add: obj
+file: src/object/tests/face_normals.obj
+shading: flat",
+        )
+        .unwrap();
+
+        let d = Data::new();
+
+        let o = o.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        let mut flat = Object::from_file("src/object/tests/face_normals.obj")
+            .unwrap()
+            .build();
+        flat.update_shading(Shading::Flat);
+
+        assert_approx_eq!(o, &flat);
+        assert_approx_ne!(
+            o,
+            &Object::from_file("src/object/tests/face_normals.obj")
+                .unwrap()
+                .build()
+        );
+    }
+
     #[test]
     fn parse_plane() {
         let p: Plane = from_str(
@@ -437,6 +757,27 @@ transform:
         assert_approx_eq!(o, &Object::sphere_builder().build());
     }
 
+    #[test]
+    fn parse_clipped_sphere() {
+        let s: Sphere = from_str(
+            "\
+clip:
+    - [[0, 0, 0], [0, 0, 1]]",
+        )
+        .unwrap();
+
+        let d = Data::new();
+
+        let o = s.parse(&d, &mut Xoshiro256PlusPlus::seed_from_u64(0)).unwrap();
+
+        let l = o
+            .intersect(&Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()))
+            .unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 6.0);
+    }
+
     #[test]
     fn parse_csg() {
         let c: Csg = from_str(
@@ -20,8 +20,7 @@ impl TransformationList {
 
         for transformation in self.0 {
             if let Some(define) = transformation.as_str() {
-                if let Some(transformations) = data.transformations.get(define)
-                {
+                if let Some(transformations) = data.transformations(define) {
                     final_transformations
                         .extend(transformations.clone().collect(data)?.0);
                 } else {
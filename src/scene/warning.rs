@@ -0,0 +1,61 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A `Warning` describes a likely mistake in a `Scene` that does not stop it
+/// from being rendered. Objects are referred to by their index in the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warning {
+    NoLights,
+    NotVisible(usize),
+    NonFiniteTransformation(usize),
+    TransparentWithoutRefraction(usize),
+    CoplanarOverlap(usize, usize),
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoLights => write!(f, "The scene has no lights"),
+            Self::NotVisible(index) => {
+                write!(f, "Object {index} is outside the view of the camera")
+            }
+            Self::NonFiniteTransformation(index) => write!(
+                f,
+                "Object {index} has a transformation that is not finite"
+            ),
+            Self::TransparentWithoutRefraction(index) => write!(
+                f,
+                "Object {index} is transparent but has no refractive index set"
+            ),
+            Self::CoplanarOverlap(first, second) => write!(
+                f,
+                "Objects {first} and {second} have overlapping coplanar faces"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displaying_warnings() {
+        assert_eq!(Warning::NoLights.to_string(), "The scene has no lights");
+        assert_eq!(
+            Warning::NotVisible(2).to_string(),
+            "Object 2 is outside the view of the camera"
+        );
+        assert_eq!(
+            Warning::NonFiniteTransformation(0).to_string(),
+            "Object 0 has a transformation that is not finite"
+        );
+        assert_eq!(
+            Warning::TransparentWithoutRefraction(5).to_string(),
+            "Object 5 is transparent but has no refractive index set"
+        );
+        assert_eq!(
+            Warning::CoplanarOverlap(1, 3).to_string(),
+            "Objects 1 and 3 have overlapping coplanar faces"
+        );
+    }
+}
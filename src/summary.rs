@@ -0,0 +1,163 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+/// A `Summary` holds counts of everything contained in a `World` or `Scene`,
+/// it is intended for logging what has been loaded rather than for any further
+/// processing.
+#[derive(Clone, Debug, Default)]
+pub struct Summary {
+    resolution: Option<(u32, u32)>,
+    shapes: BTreeMap<&'static str, usize>,
+    lights: BTreeMap<&'static str, usize>,
+    groups: usize,
+    csgs: usize,
+}
+
+impl Summary {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.resolution = Some((width, height));
+    }
+
+    pub fn add_shape(&mut self, kind: &'static str) {
+        *self.shapes.entry(kind).or_default() += 1;
+    }
+
+    pub fn add_light(&mut self, kind: &'static str) {
+        *self.lights.entry(kind).or_default() += 1;
+    }
+
+    pub fn add_group(&mut self) {
+        self.groups += 1;
+    }
+
+    pub fn add_csg(&mut self) {
+        self.csgs += 1;
+    }
+
+    #[must_use]
+    pub const fn resolution(&self) -> Option<(u32, u32)> {
+        self.resolution
+    }
+
+    /// The number of shapes of the given kind, or of all kinds if `None`.
+    #[must_use]
+    pub fn shapes(&self, kind: Option<&str>) -> usize {
+        Self::count(&self.shapes, kind)
+    }
+
+    /// The number of lights of the given kind, or of all kinds if `None`.
+    #[must_use]
+    pub fn lights(&self, kind: Option<&str>) -> usize {
+        Self::count(&self.lights, kind)
+    }
+
+    #[must_use]
+    pub const fn groups(&self) -> usize {
+        self.groups
+    }
+
+    #[must_use]
+    pub const fn csgs(&self) -> usize {
+        self.csgs
+    }
+
+    fn count(map: &BTreeMap<&'static str, usize>, kind: Option<&str>) -> usize {
+        kind.map_or_else(
+            || map.values().sum(),
+            |kind| map.get(kind).copied().unwrap_or_default(),
+        )
+    }
+
+    fn write_counts(
+        f: &mut Formatter<'_>,
+        name: &str,
+        map: &BTreeMap<&'static str, usize>,
+    ) -> fmt::Result {
+        write!(f, "{name}: {}", Self::count(map, None))?;
+
+        if !map.is_empty() {
+            let counts = map
+                .iter()
+                .map(|(kind, count)| format!("{kind}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(f, " ({counts})")?;
+        }
+
+        writeln!(f)
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some((width, height)) = self.resolution {
+            writeln!(f, "Resolution: {width} by {height}")?;
+        }
+
+        Self::write_counts(f, "Shapes", &self.shapes)?;
+        writeln!(f, "Groups: {}", self.groups)?;
+        writeln!(f, "CSGs: {}", self.csgs)?;
+        Self::write_counts(f, "Lights", &self.lights)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creating_a_summary() {
+        let s = Summary::new();
+
+        assert!(s.resolution().is_none());
+        assert_eq!(s.shapes(None), 0);
+        assert_eq!(s.lights(None), 0);
+        assert_eq!(s.groups(), 0);
+        assert_eq!(s.csgs(), 0);
+    }
+
+    #[test]
+    fn counting_items_in_a_summary() {
+        let mut s = Summary::new();
+
+        s.set_resolution(100, 50);
+        s.add_shape("sphere");
+        s.add_shape("cube");
+        s.add_shape("sphere");
+        s.add_light("point");
+        s.add_group();
+        s.add_csg();
+        s.add_csg();
+
+        assert_eq!(s.resolution(), Some((100, 50)));
+        assert_eq!(s.shapes(None), 3);
+        assert_eq!(s.shapes(Some("sphere")), 2);
+        assert_eq!(s.shapes(Some("cube")), 1);
+        assert_eq!(s.shapes(Some("plane")), 0);
+        assert_eq!(s.lights(None), 1);
+        assert_eq!(s.lights(Some("point")), 1);
+        assert_eq!(s.groups(), 1);
+        assert_eq!(s.csgs(), 2);
+
+        assert_eq!(
+            s.to_string(),
+            "\
+Resolution: 100 by 50
+Shapes: 3 (cube: 1, sphere: 2)
+Groups: 1
+CSGs: 2
+Lights: 1 (point: 1)
+"
+        );
+    }
+}
@@ -1,3 +1,20 @@
+use anyhow::{anyhow, Context, Result};
+use exmex::eval_str;
+use serde_yaml::Value;
+
+/// Parse a value from a scene file as an `f64`, strings are evaluated as simple
+/// expressions so that values such as "PI / 3" or "1 / 3" can be used. When an
+/// expression can not be evaluated the error from exmex is kept as the source.
+pub fn parse_f64(value: &Value) -> Result<f64> {
+    let message = || format!("Unable to parse '{value:?}' as a float");
+
+    match value {
+        Value::Number(number) => number.as_f64().with_context(message),
+        Value::String(string) => eval_str::<f64>(string).with_context(message),
+        _ => Err(anyhow!(message())),
+    }
+}
+
 /// Macro to implement serde Deserialize for a type that can be represented as 3 f64's.
 macro_rules! impl_deserialize_tuple {
     ($ty:ty) => {
@@ -6,11 +23,56 @@ macro_rules! impl_deserialize_tuple {
             where
                 D: serde::Deserializer<'de>,
             {
-                let [a, b, c] = <[f64; 3]>::deserialize(deserializer)?;
+                let [a, b, c] =
+                    <[serde_yaml::Value; 3]>::deserialize(deserializer)?;
 
-                Ok(Self::new(a, b, c))
+                let parse = |value| {
+                    crate::util::parse_f64(value).map_err(|error| {
+                        <D::Error as serde::de::Error>::custom(format!(
+                            "{error:#}"
+                        ))
+                    })
+                };
+
+                Ok(Self::new(parse(&a)?, parse(&b)?, parse(&c)?))
             }
         }
     };
 }
 pub(crate) use impl_deserialize_tuple;
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{PI, TAU};
+
+    use serde_yaml::from_str;
+
+    use super::*;
+    use crate::math::float::*;
+
+    fn parse(yaml: &str) -> Result<f64> {
+        parse_f64(&from_str::<Value>(yaml).unwrap())
+    }
+
+    #[test]
+    fn parsing_floats() {
+        assert_approx_eq!(parse("1.5").unwrap(), 1.5);
+        assert_approx_eq!(parse("-2").unwrap(), -2.0);
+        assert_approx_eq!(parse("2 * PI").unwrap(), TAU);
+        assert_approx_eq!(parse("PI / 3").unwrap(), PI / 3.0);
+        assert_approx_eq!(parse("1/3").unwrap(), 1.0 / 3.0);
+
+        let error = parse("foo").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Unable to parse 'String(\"foo\")' as a float"
+        );
+        assert_eq!(error.chain().count(), 2);
+
+        assert_eq!(
+            parse("[1, 2]").unwrap_err().to_string(),
+            "Unable to parse 'Sequence [Number(1), Number(2)]' as a float"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::Debug;
+
+use crate::{math::Ray, List, Object};
+
+/// An `Intersector` finds where a ray hits the objects of a `World`, allowing
+/// a custom spatial index such as a grid or BVH to be plugged in with
+/// `World::set_intersector`. The returned list does not need to be sorted.
+pub trait Intersector: Debug + Send + Sync {
+    #[must_use]
+    fn intersect<'a>(
+        &self,
+        objects: &'a [Object],
+        ray: &Ray,
+    ) -> Option<List<'a>>;
+}
+
+/// `AllObjects` is the default `Intersector`, every object is tested in turn
+/// relying on the bounding boxes of groups and CSGs to skip work.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllObjects;
+
+impl Intersector for AllObjects {
+    fn intersect<'a>(
+        &self,
+        objects: &'a [Object],
+        ray: &Ray,
+    ) -> Option<List<'a>> {
+        intersect_objects(objects, ray, true)
+    }
+}
+
+/// Intersect the ray with every object, combining their lists.
+#[must_use]
+pub(super) fn intersect_objects<'a, I>(
+    objects: I,
+    ray: &Ray,
+    bounded: bool,
+) -> Option<List<'a>>
+where
+    I: IntoIterator<Item = &'a Object>,
+{
+    let mut list = List::new();
+
+    for obj in objects {
+        if let Some(mut intersects) = obj.intersect_with(ray, bounded) {
+            list.append(&mut *intersects);
+        }
+    }
+
+    if list.is_empty() {
+        return None;
+    }
+
+    Some(list)
+}
@@ -0,0 +1,3593 @@
+mod intersector;
+mod shadow_map;
+
+use std::{
+    collections::HashSet,
+    f64::consts::{PI, TAU},
+    mem::take,
+    ptr,
+    sync::Arc,
+};
+
+use rand::prelude::*;
+
+use self::intersector::intersect_objects;
+pub use self::{
+    intersector::{AllObjects, Intersector},
+    shadow_map::ShadowMap,
+};
+use crate::{
+    intersection::{Computations, Intersection, Limit, List},
+    light::Lightable,
+    math::{float::approx_eq, Point, Ray, Vector},
+    object::{EstimateMemory, Summarise},
+    Bounded, Camera, Colour, CubeMap, Light, Object, Summary,
+};
+
+/// The default distance that shadow rays start above a surface, this matches
+/// the offset used for the origins of reflected rays.
+const DEFAULT_SHADOW_BIAS: f64 = 100_000.0 * f64::EPSILON;
+
+/// The wavelengths in micrometres used for the red, green and blue channels
+/// when rendering spectrally.
+const WAVELENGTHS: [f64; 3] = [0.65, 0.55, 0.45];
+
+/// The highest chance of a secondary ray surviving Russian roulette.
+const MAX_SURVIVAL: f64 = 0.95;
+
+/// A `World` represents all the objects and light sources in a given scene that
+/// we are rendering.
+#[derive(Clone, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct World {
+    pub(super) objects: Vec<Object>,
+    pub(super) lights: Vec<Light>,
+    limit: Option<Limit>,
+    check_colours: bool,
+    shadow_bias: f64,
+    bounding_boxes: bool,
+    environment: Option<CubeMap>,
+    background: Colour,
+    shadow_map: Option<ShadowMap>,
+    spectral: bool,
+    light_samples: Option<usize>,
+    russian_roulette: bool,
+    intersector: Option<Arc<dyn Intersector>>,
+    ambient_occlusion: Option<(u32, f64)>,
+    visible: Option<Vec<bool>>,
+    self_intersection: Option<f64>,
+}
+
+impl World {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            lights: Vec::new(),
+            limit: None,
+            check_colours: false,
+            shadow_bias: DEFAULT_SHADOW_BIAS,
+            bounding_boxes: true,
+            environment: None,
+            background: Colour::black(),
+            shadow_map: None,
+            spectral: false,
+            light_samples: None,
+            russian_roulette: false,
+            intersector: None,
+            ambient_occlusion: None,
+            visible: None,
+            self_intersection: None,
+        }
+    }
+
+    /// Set the maximum number of intersections kept for any single ray, `None`
+    /// (the default) means there is no limit.
+    pub fn set_max_intersections(&mut self, maximum: Option<usize>) {
+        self.limit = maximum.map(Limit::new);
+    }
+
+    #[must_use]
+    pub fn max_intersections(&self) -> Option<usize> {
+        self.limit.as_ref().map(Limit::maximum)
+    }
+
+    /// Set how far along the surface normal shadow rays start, increasing this
+    /// removes self shadowing acne in scenes with large coordinates without
+    /// changing where reflected or refracted rays start.
+    pub fn set_shadow_bias(&mut self, bias: f64) {
+        self.shadow_bias = bias;
+    }
+
+    #[must_use]
+    pub const fn shadow_bias(&self) -> f64 {
+        self.shadow_bias
+    }
+
+    /// Set a distance within which reflected and refracted rays ignore hits
+    /// on the object they left, as rounding can make them hit it again however
+    /// far they are offset. Hits on that object further away, such as inside a
+    /// reflective sphere, are kept. `None` (the default) keeps every hit.
+    pub fn set_self_intersection(&mut self, distance: Option<f64>) {
+        self.self_intersection = distance;
+    }
+
+    /// Surround the world with a cube map that is seen by any ray that does not
+    /// hit an object, without one such rays see the background.
+    pub fn set_environment(&mut self, environment: Option<CubeMap>) {
+        self.environment = environment;
+    }
+
+    /// Set the colour seen by rays that miss every object when there is no
+    /// environment, black by default. A `Camera` may override this.
+    pub fn set_background(&mut self, background: Colour) {
+        self.background = background;
+    }
+
+    #[must_use]
+    pub const fn background(&self) -> Colour {
+        self.background
+    }
+
+    /// Turn the bounding box checks of groups and CSGs on or off, they are on
+    /// by default. Turning them off is slow but useful when debugging as any
+    /// change in the image shows that a bounding box is wrong.
+    pub fn set_bounding_boxes(&mut self, enabled: bool) {
+        self.bounding_boxes = enabled;
+    }
+
+    /// Darken the ambient light at each hit by how much of the hemisphere
+    /// above it is blocked, tracing `samples` rays out to `distance`. Direct
+    /// light from the light sources is left alone as shadows already account
+    /// for it. `None` (the default) turns ambient occlusion off.
+    pub fn set_ambient_occlusion(&mut self, occlusion: Option<(u32, f64)>) {
+        self.ambient_occlusion = occlusion;
+    }
+
+    /// Replace how rays are intersected with the objects in the world, `None`
+    /// (the default) tests every object in turn as `AllObjects` does while
+    /// honouring the intersection limit and bounding box settings.
+    pub fn set_intersector(
+        &mut self,
+        intersector: Option<Arc<dyn Intersector>>,
+    ) {
+        self.intersector = intersector;
+    }
+
+    /// When set, debug builds assert that every shaded colour is finite,
+    /// reporting the ray that produced it, rather than letting NaNs quietly
+    /// spread through the image.
+    pub fn set_check_colours(&mut self, check: bool) {
+        self.check_colours = check;
+    }
+
+    /// When set, rays that hit a transparent material with dispersion are
+    /// split and traced separately at a red, green and blue wavelength so
+    /// that refraction separates white light into colours. This triples the
+    /// cost of any such hit so it is off by default.
+    pub fn set_spectral(&mut self, spectral: bool) {
+        self.spectral = spectral;
+    }
+
+    /// Set how many lights are sampled at each hit, lights are picked at
+    /// random in proportion to how much they are likely to contribute and
+    /// weighted so that on average the result matches sampling every light.
+    /// `None` (the default) samples every light, as does any count no smaller
+    /// than the number of lights.
+    pub fn set_light_samples(&mut self, samples: Option<usize>) {
+        self.light_samples = samples;
+    }
+
+    /// Count the shapes, groups and lights in the world.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::new();
+
+        for object in &self.objects {
+            object.summarise(&mut summary);
+        }
+
+        for light in self.lights() {
+            summary.add_light(light.kind());
+        }
+
+        summary
+    }
+
+    /// An approximate count of the bytes used by the objects and lights in the
+    /// world, see `EstimateMemory`. Shadow maps and environment maps are not
+    /// included.
+    #[must_use]
+    pub fn estimated_memory(&self) -> usize {
+        let mut materials = HashSet::new();
+
+        self.objects
+            .iter()
+            .map(|object| object.estimate_memory(&mut materials))
+            .sum::<usize>()
+            + self.lights.capacity() * size_of::<Light>()
+    }
+
+    /// The summed power of every light in the world, see
+    /// `Lightable::total_power`.
+    #[must_use]
+    pub fn total_light_power(&self) -> f64 {
+        self.lights().map(|light| light.total_power()).sum()
+    }
+
+    /// The number of times an intersection list has been truncated because it
+    /// exceeded the maximum number of intersections.
+    #[must_use]
+    pub fn truncated_intersections(&self) -> usize {
+        self.limit.as_ref().map_or(0, Limit::truncated)
+    }
+
+    /// Reset the count of truncated intersection lists, see
+    /// `truncated_intersections`.
+    pub fn reset_truncated_intersections(&self) {
+        if let Some(limit) = &self.limit {
+            limit.reset();
+        }
+    }
+
+    /// List every intersection of the ray in order as the id of the shape hit,
+    /// the t value and whether the ray is entering or leaving the medium it
+    /// bounds. Shape ids count every shape in the world depth first, see
+    /// `Object::shapes`, and entering and leaving track the containers used to
+    /// find the refractive indices either side of each hit.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if an intersection is with a shape not in the world.
+    #[must_use]
+    pub fn intersections_with_ids(&self, ray: &Ray) -> Vec<(usize, f64, bool)> {
+        let Some(list) = self.intersect_all(ray, false) else {
+            return Vec::new();
+        };
+
+        let shapes =
+            self.objects.iter().flat_map(Object::shapes).collect::<Vec<_>>();
+
+        let mut container = Vec::<&Object>::new();
+
+        list.iter()
+            .map(|intersection| {
+                let id = shapes
+                    .iter()
+                    .position(|shape| ptr::eq(*shape, intersection.object))
+                    .expect("Intersected shape is not in the world");
+
+                let medium = intersection.csg.unwrap_or(intersection.object);
+
+                let entering = if let Some(index) = container
+                    .iter()
+                    .position(|object| approx_eq!(*object, medium))
+                {
+                    container.remove(index);
+                    false
+                } else {
+                    container.push(medium);
+                    true
+                };
+
+                (id, intersection.t, entering)
+            })
+            .collect()
+    }
+
+    pub fn add_object(&mut self, object: Object) {
+        self.objects.push(object);
+        self.objects_changed();
+    }
+
+    /// Clear everything worked out from the objects in the world, the culling
+    /// from `prepare_for_camera` and any shadow map, after they change.
+    fn objects_changed(&mut self) {
+        self.visible = None;
+        self.shadow_map = None;
+    }
+
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    /// Divide the children of all groups in the world into bounding volume
+    /// hierarchies, see `Object::divide`.
+    pub fn divide(&mut self, threshold: u32) {
+        self.objects = take(&mut self.objects)
+            .into_iter()
+            .map(|object| object.divide(threshold))
+            .collect();
+        self.objects_changed();
+    }
+
+    /// Pose every object at `time` along its keyframes, see `Object::at_time`.
+    /// Poses are not relative so this can be called repeatedly to move back
+    /// and forth through time.
+    pub fn set_time(&mut self, time: f64) {
+        self.objects =
+            self.objects.iter().map(|object| object.at_time(time)).collect();
+        self.objects_changed();
+    }
+
+    /// Remove the object at `index`, returning it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    pub fn remove_object(&mut self, index: usize) -> Object {
+        self.objects_changed();
+
+        self.objects.remove(index)
+    }
+
+    /// Replace the object at `index`, returning the old object. Objects hold
+    /// their own bounding boxes so nothing else needs to be updated other than
+    /// clearing any culling from `prepare_for_camera` and any shadow map.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `index` is out of bounds.
+    pub fn replace_object(&mut self, index: usize, object: Object) -> Object {
+        self.objects_changed();
+
+        std::mem::replace(&mut self.objects[index], object)
+    }
+
+    pub fn objects_mut(&mut self) -> &mut [Object] {
+        self.objects_changed();
+
+        &mut self.objects
+    }
+
+    /// Find which objects the camera can see, see `Camera::can_see`, so that
+    /// primary rays can skip those entirely outside its view. Shadow,
+    /// reflection and refraction rays still test every object. Changing the
+    /// objects in the world clears this so it must be called again afterwards,
+    /// it has no effect when a custom `Intersector` is set.
+    pub fn prepare_for_camera(&mut self, camera: &Camera) {
+        self.visible = Some(
+            self.objects
+                .iter()
+                .map(|object| camera.can_see(&object.bounding_box()))
+                .collect(),
+        );
+    }
+
+    /// The lights added with `add_light`, lights in groups are not included.
+    pub fn lights_mut(&mut self) -> &mut [Light] {
+        &mut self.lights
+    }
+
+    /// Remove every light but the one at `index` in the order given by
+    /// `lights`, or every light when `index` is `None`.
+    pub fn isolate_light(&mut self, index: Option<usize>) {
+        let light = index.and_then(|index| self.lights().nth(index));
+
+        self.lights = light.into_iter().collect();
+
+        for object in &mut self.objects {
+            object.remove_lights();
+        }
+    }
+
+    /// Iterate over every light in the world, those added with `add_light`
+    /// followed by those in groups.
+    pub fn lights(&self) -> impl Iterator<Item = Light> + '_ {
+        self.lights
+            .iter()
+            .chain(self.objects.iter().flat_map(Object::lights))
+            .copied()
+    }
+
+    #[must_use]
+    pub fn colour_at<R: Rng>(
+        &self,
+        ray: &Ray,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        self.colour_at_within(ray, 0.0, f64::INFINITY, depth, rng)
+    }
+
+    /// Find the colour for the given ray only considering hits with a t value
+    /// greater than `minimum` and no greater than `maximum`. Secondary rays are
+    /// not bounded.
+    #[must_use]
+    pub fn colour_at_within<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        self.colour_at_with_background(ray, minimum, maximum, depth, None, rng)
+    }
+
+    /// Find the colour for the given ray as `colour_at_within` does, rays that
+    /// miss everything, including secondary rays, see `background` when it is
+    /// set rather than the environment or background of the world.
+    #[must_use]
+    pub fn colour_at_with_background<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        depth: u32,
+        background: Option<Colour>,
+        rng: &mut R,
+    ) -> Colour {
+        self.colour_of_ray(ray, minimum, maximum, depth, background, None, rng)
+    }
+
+    /// Find the colour for the given ray, rays with no `origin` are primary
+    /// rays and skip the objects culled by `prepare_for_camera`. Rays spawned
+    /// from a hit skip nearby hits on the object they left, see
+    /// `set_self_intersection`.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    fn colour_of_ray<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        depth: u32,
+        background: Option<Colour>,
+        origin: Option<&Object>,
+        rng: &mut R,
+    ) -> Colour {
+        self.shade_ray(ray, minimum, maximum, depth, background, origin, rng)
+            .map_or_else(
+                || self.miss_colour(ray, background),
+                |(_, direct, indirect)| direct + indirect,
+            )
+    }
+
+    /// Find the hit for the given primary ray and its colour as
+    /// `colour_at_with_background` does, split into the light reaching the hit
+    /// directly and the light arriving there by reflection and refraction, see
+    /// `shade_hit_split`. Rays that miss everything give `None` and see
+    /// `miss_colour`.
+    #[must_use]
+    pub fn split_colour_at_within<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        depth: u32,
+        background: Option<Colour>,
+        rng: &mut R,
+    ) -> Option<(Intersection<'_>, Colour, Colour)> {
+        self.shade_ray(ray, minimum, maximum, depth, background, None, rng)
+    }
+
+    /// The colour seen by a ray that misses everything, `background` when it
+    /// is set and otherwise the environment or background of the world.
+    #[must_use]
+    pub fn miss_colour(&self, ray: &Ray, background: Option<Colour>) -> Colour {
+        if let Some(background) = background {
+            return background;
+        }
+
+        self.environment.as_ref().map_or(self.background, |environment| {
+            environment.colour_at(&ray.direction)
+        })
+    }
+
+    /// Find the hit for the given ray and shade it, see `colour_of_ray`, with
+    /// the colour split as `shade_hit_split` does.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    fn shade_ray<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        depth: u32,
+        background: Option<Colour>,
+        origin: Option<&Object>,
+        rng: &mut R,
+    ) -> Option<(Intersection<'_>, Colour, Colour)> {
+        let intersections = self.intersect(ray, origin.is_none())?;
+
+        // Nearby hits on the object the ray left are only skipped when choosing
+        // the hit, they are still needed to track which media the ray is in.
+        let hit = if let (Some(origin), Some(distance)) =
+            (origin, self.self_intersection)
+        {
+            List::from(
+                intersections
+                    .iter()
+                    .filter(|intersection| {
+                        !ptr::eq(intersection.object, origin)
+                            || intersection.t.abs() >= distance
+                    })
+                    .copied()
+                    .collect::<Vec<_>>(),
+            )
+            .hit_within(minimum, maximum)?
+        } else {
+            intersections.hit_within(minimum, maximum)?
+        };
+
+        let material = hit.object.material();
+
+        if self.spectral
+            && ray.wavelength.is_none()
+            && material.transparency > 0.0
+            && material.dispersion != 0.0
+        {
+            let [red, green, blue] = WAVELENGTHS.map(|wavelength| {
+                self.shade_ray(
+                    &ray.with_wavelength(Some(wavelength)),
+                    minimum,
+                    maximum,
+                    depth,
+                    background,
+                    origin,
+                    rng,
+                )
+                .map_or(
+                    (Colour::black(), Colour::black()),
+                    |(_, direct, indirect)| (direct, indirect),
+                )
+            });
+
+            return Some((
+                hit,
+                Colour::new(red.0.red, green.0.green, blue.0.blue),
+                Colour::new(red.1.red, green.1.green, blue.1.blue),
+            ));
+        }
+
+        let mut computations = hit.prepare_computations(ray, &intersections);
+
+        computations.background = background;
+
+        let (direct, indirect) =
+            self.shade_hit_split(&computations, depth, rng);
+
+        debug_assert!(
+            !self.check_colours || (direct + indirect).is_finite(),
+            "Non finite colour {:?} from ray {ray:?}",
+            direct + indirect
+        );
+
+        Some((hit, direct, indirect))
+    }
+
+    /// Find the colour for a secondary ray spawned from a hit, keeping any
+    /// background override used for the ray that made the hit and skipping
+    /// nearby hits on the object it was spawned from.
+    #[must_use]
+    fn colour_from<R: Rng>(
+        &self,
+        computations: &Computations,
+        ray: &Ray,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        self.colour_of_ray(
+            ray,
+            0.0,
+            f64::INFINITY,
+            depth,
+            computations.background,
+            Some(computations.object),
+            rng,
+        )
+    }
+
+    /// Find how close the hit for the given ray is to the nearest edge of a
+    /// triangle, only considering hits between `minimum` and `maximum`, see
+    /// `Object::edge_distance`. Rays that miss or hit other shapes give `None`.
+    #[must_use]
+    pub fn edge_distance_within(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+    ) -> Option<f64> {
+        let intersections = self.intersect(ray, true)?;
+        let hit = intersections.hit_within(minimum, maximum)?;
+
+        hit.object.edge_distance(&hit)
+    }
+
+    /// Find the u and v values of the hit for the given ray, only considering
+    /// hits between `minimum` and `maximum`, as a colour with u in the red
+    /// channel and v in the green. Hits on shapes without u and v values are
+    /// black.
+    #[must_use]
+    pub fn u_v_at_within(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+    ) -> Colour {
+        self.intersect(ray, true)
+            .and_then(|intersections| {
+                intersections.hit_within(minimum, maximum)
+            })
+            .and_then(|hit| hit.object.u_v_at(&ray.position(hit.t), &hit))
+            .map_or_else(Colour::black, |(u, v)| Colour::new(u, v, 0.0))
+    }
+
+    /// Find the normal of the hit for the given ray, only considering hits
+    /// between `minimum` and `maximum`, as a colour with each channel mapped
+    /// from -1.0..1.0 to 0.0..1.0. This is the world space normal used for
+    /// shading, including the transformations of the object and any
+    /// smoothing, but pointing out of the object rather than towards the ray.
+    /// Rays that miss are black.
+    #[must_use]
+    pub fn normal_at_within(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+    ) -> Colour {
+        self.intersect(ray, true)
+            .and_then(|intersections| {
+                intersections
+                    .hit_within(minimum, maximum)
+                    .map(|hit| hit.object.normal_at(&ray.position(hit.t), &hit))
+            })
+            .map_or_else(Colour::black, |normal| Colour::from_normal(&normal))
+    }
+
+    /// Find the alpha of the hit for the given ray, only considering hits
+    /// between `minimum` and `maximum`, as a grey colour. Hits on objects are
+    /// opaque while hits on shadow catchers are as opaque as the shadow on
+    /// them is dark, rays that miss are transparent.
+    #[must_use]
+    pub fn matte_at_within<R: Rng>(
+        &self,
+        ray: &Ray,
+        minimum: f64,
+        maximum: f64,
+        rng: &mut R,
+    ) -> Colour {
+        let Some(intersections) = self.intersect_all(ray, true) else {
+            return Colour::black();
+        };
+
+        let Some(hit) = intersections.hit_within(minimum, maximum) else {
+            return Colour::black();
+        };
+
+        if !hit.object.material().shadow_catcher {
+            return Colour::white();
+        }
+
+        let computations = hit.prepare_computations(ray, &intersections);
+
+        let shadow_point =
+            computations.point + computations.normal * self.shadow_bias;
+
+        let mut lights = 0.0;
+        let mut intensity = 0.0;
+
+        for light in self.lights() {
+            lights += 1.0;
+            intensity += light.intensity_at(&shadow_point, self, rng);
+        }
+
+        if lights == 0.0 {
+            return Colour::black();
+        }
+
+        let alpha = 1.0 - intensity / lights;
+
+        Colour::new(alpha, alpha, alpha)
+    }
+
+    #[must_use]
+    pub fn shade_hit<R: Rng>(
+        &self,
+        computations: &Computations,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        let (direct, indirect) = self.shade_hit_split(computations, depth, rng);
+
+        direct + indirect
+    }
+
+    /// Shade the hit as `shade_hit` does but split into the light reaching the
+    /// surface directly from the lights, including ambient light, and the
+    /// light arriving by reflection and refraction.
+    #[must_use]
+    pub fn shade_hit_split<R: Rng>(
+        &self,
+        computations: &Computations,
+        depth: u32,
+        rng: &mut R,
+    ) -> (Colour, Colour) {
+        let mut ambient = Colour::black();
+        let mut direct = Colour::black();
+
+        let shadow_point =
+            computations.point + computations.normal * self.shadow_bias;
+
+        for (light, weight) in self.sample_lights(&computations.point, rng) {
+            // Share the sampled positions between the shadow and shading
+            // calculations rather than sampling the light twice.
+            let positions = light.positions_at(&shadow_point, rng);
+
+            // The normal always faces the eye, see `prepare_computations`, so
+            // two sided materials turn it back to face lights behind them.
+            let back_lit = computations.object.material().two_sided
+                && positions.first().is_some_and(|position| {
+                    (*position - computations.point).dot(&computations.normal)
+                        < 0.0
+                });
+
+            let (normal, point, light_point) = if back_lit {
+                (
+                    -computations.normal,
+                    computations.under_point,
+                    computations.point - computations.normal * self.shadow_bias,
+                )
+            } else {
+                (computations.normal, computations.over_point, shadow_point)
+            };
+
+            let (light_ambient, light_direct) =
+                computations.object.material().lighting_terms(
+                    computations.object,
+                    &light,
+                    &positions,
+                    &point,
+                    &computations.eye,
+                    &normal,
+                    light.intensity_at_positions(
+                        &positions,
+                        &light_point,
+                        self,
+                    ),
+                    computations.vertex_colour,
+                );
+
+            ambient += light_ambient * weight;
+            direct += light_direct * weight;
+        }
+
+        let surface = ambient
+            * self.ambient_visibility(&shadow_point, computations, rng)
+            + direct;
+
+        let depth = computations
+            .object
+            .material()
+            .max_depth
+            .map_or(depth, |max_depth| depth.min(max_depth));
+
+        let reflected = self.reflected_colour(computations, depth, rng);
+
+        let refracted = self.refracted_colour(computations, depth, rng);
+
+        if computations.object.material().reflective > 0.0
+            && computations.object.material().transparency > 0.0
+        {
+            let reflectance = computations.schlick();
+
+            return (
+                surface,
+                reflected * reflectance + refracted * (1.0 - reflectance),
+            );
+        }
+
+        (surface, reflected + refracted)
+    }
+
+    /// The fraction of the hemisphere around the normal that is open when
+    /// ambient occlusion is on, rays are cosine weighted so blockers close to
+    /// the normal count for more.
+    #[must_use]
+    fn ambient_visibility<R: Rng>(
+        &self,
+        point: &Point,
+        computations: &Computations,
+        rng: &mut R,
+    ) -> f64 {
+        let Some((samples, distance)) = self.ambient_occlusion else {
+            return 1.0;
+        };
+
+        if samples == 0 {
+            return 1.0;
+        }
+
+        let (tangent, bitangent) = basis(&computations.normal);
+
+        let open = (0..samples)
+            .filter(|_| {
+                let radius = rng.gen_range(0.0..1.0_f64).sqrt();
+                let phi = rng.gen_range(0.0..TAU);
+
+                let direction = tangent * (radius * phi.cos())
+                    + bitangent * (radius * phi.sin())
+                    + computations.normal
+                        * (1.0 - radius * radius).max(0.0).sqrt();
+
+                !self.intersect_any(&Ray::new(*point, direction), distance)
+            })
+            .count();
+
+        #[allow(clippy::cast_precision_loss)]
+        let visibility = open as f64 / f64::from(samples);
+
+        visibility
+    }
+
+    /// When set, reflections and refractions are not cut off once the depth
+    /// runs out. Instead each further bounce is traced with a chance equal to
+    /// how much light it carries, and those that are traced are scaled up to
+    /// make up for the ones that are not. This avoids darkening bright mirrors
+    /// at the cost of noise so it is off by default.
+    pub fn set_russian_roulette(&mut self, enabled: bool) {
+        self.russian_roulette = enabled;
+    }
+
+    /// Decide whether to keep tracing a secondary ray after the depth has run
+    /// out, returning the weight to give it if so.
+    #[must_use]
+    fn roulette<R: Rng>(&self, coefficient: f64, rng: &mut R) -> Option<f64> {
+        if !self.russian_roulette {
+            return None;
+        }
+
+        // Always leave some chance of stopping so that paths between perfect
+        // mirrors still end.
+        let probability = coefficient.clamp(0.0, MAX_SURVIVAL);
+
+        (rng.gen::<f64>() < probability).then(|| 1.0 / probability)
+    }
+
+    /// Choose the lights to shade `point` with along with the weight to give
+    /// each, see `set_light_samples`.
+    #[must_use]
+    fn sample_lights<R: Rng>(
+        &self,
+        point: &Point,
+        rng: &mut R,
+    ) -> Vec<(Light, f64)> {
+        let lights: Vec<_> = self.lights().collect();
+
+        let Some(samples) =
+            self.light_samples.filter(|samples| *samples < lights.len())
+        else {
+            return lights.into_iter().map(|light| (light, 1.0)).collect();
+        };
+
+        let importances: Vec<_> =
+            lights.iter().map(|light| light.importance(point)).collect();
+        let total: f64 = importances.iter().sum();
+
+        if total <= 0.0 || !total.is_finite() {
+            return lights.into_iter().map(|light| (light, 1.0)).collect();
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let samples_float = samples as f64;
+
+        (0..samples)
+            .map(|_| {
+                let mut target = rng.gen_range(0.0..total);
+
+                let index = importances
+                    .iter()
+                    .position(|importance| {
+                        target -= importance;
+
+                        target < 0.0
+                    })
+                    .or_else(|| {
+                        // Rounding can leave a sliver of the total unclaimed.
+                        importances
+                            .iter()
+                            .rposition(|importance| *importance > 0.0)
+                    })
+                    .unwrap_or_default();
+
+                // Dividing by the chance of picking the light keeps the
+                // expected result the same as sampling every light.
+                let probability = importances[index] / total;
+
+                (lights[index], 1.0 / (probability * samples_float))
+            })
+            .collect()
+    }
+
+    /// Intersect the ray with every object in the world, shadow catchers are
+    /// skipped as they are invisible to rays.
+    #[must_use]
+    fn intersect(&self, ray: &Ray, primary: bool) -> Option<List<'_>> {
+        let mut list = self.intersect_all(ray, primary)?;
+
+        list.retain(|intersection| {
+            !intersection.object.material().shadow_catcher
+        });
+
+        if list.is_empty() {
+            return None;
+        }
+
+        Some(list)
+    }
+
+    /// Intersect the ray with every object in the world including shadow
+    /// catchers. Primary rays skip any objects culled by `prepare_for_camera`.
+    #[must_use]
+    fn intersect_all(&self, ray: &Ray, primary: bool) -> Option<List<'_>> {
+        let mut list = match &self.intersector {
+            Some(intersector) => intersector.intersect(&self.objects, ray)?,
+            None => match self.visible.as_ref().filter(|_| primary) {
+                Some(visible) => intersect_objects(
+                    self.objects.iter().zip(visible).filter_map(
+                        |(object, visible)| visible.then_some(object),
+                    ),
+                    ray,
+                    self.bounding_boxes,
+                )?,
+                None => {
+                    intersect_objects(&self.objects, ray, self.bounding_boxes)?
+                }
+            },
+        };
+
+        if list.is_empty() {
+            return None;
+        }
+
+        // The limit is applied once to the whole list, the lists of groups and
+        // CSGs are left alone so they are only counted once when truncated.
+        if let Some(limit) = &self.limit {
+            limit.apply(&mut list);
+        }
+
+        list.sort();
+
+        Some(list)
+    }
+
+    /// Render a `ShadowMap` for the first directional light in the world so
+    /// shadows from it can be looked up rather than traced. Changing the
+    /// objects in the world clears the map so it must be built again
+    /// afterwards.
+    pub fn build_shadow_map(&mut self, resolution: u32) {
+        let shadow_map = self
+            .lights()
+            .find_map(|light| match light {
+                Light::Directional(directional) => Some(directional),
+                _ => None,
+            })
+            .and_then(|light| ShadowMap::new(self, &light, resolution));
+
+        self.shadow_map = shadow_map;
+    }
+
+    pub fn clear_shadow_map(&mut self) {
+        self.shadow_map = None;
+    }
+
+    #[must_use]
+    pub fn is_shadowed(&self, light_position: &Point, point: &Point) -> bool {
+        if let Some(shadowed) = self
+            .shadow_map
+            .as_ref()
+            .filter(|shadow_map| shadow_map.is_for(light_position))
+            .and_then(|shadow_map| shadow_map.is_shadowed(point))
+        {
+            return shadowed;
+        }
+
+        let vector = *light_position - *point;
+
+        let distance = vector.magnitude();
+        let direction = vector.normalise();
+
+        self.intersect_any(&Ray::new(*point, direction), distance)
+    }
+
+    /// Check if the nearest object along the ray in front of its origin is
+    /// closer than `distance` and casts a shadow, objects that do not cast
+    /// shadows still hide any objects behind them. Unlike a full intersection
+    /// this never sorts or limits the list.
+    #[must_use]
+    pub fn intersect_any(&self, ray: &Ray, distance: f64) -> bool {
+        let nearest = |intersections: List| {
+            intersections
+                .iter()
+                .filter(|intersection| {
+                    intersection.t > 0.0 && intersection.t < distance
+                })
+                .min_by(|a, b| a.t.total_cmp(&b.t))
+                .map(|intersection| {
+                    (intersection.t, intersection.object.casts_shadow())
+                })
+        };
+
+        let hit = if let Some(intersector) = &self.intersector {
+            intersector.intersect(&self.objects, ray).and_then(nearest)
+        } else {
+            self.objects
+                .iter()
+                .filter_map(|object| {
+                    object
+                        .intersect_with(ray, self.bounding_boxes)
+                        .and_then(nearest)
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+        };
+
+        hit.is_some_and(|(_, casts_shadow)| casts_shadow)
+    }
+
+    #[must_use]
+    pub fn reflected_colour<R: Rng>(
+        &self,
+        computations: &Computations,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        let reflective = computations.object.material().reflective;
+
+        if reflective <= 0.0 {
+            return Colour::black();
+        }
+
+        let (depth, weight) = if depth == 0 {
+            let Some(weight) = self.roulette(reflective, rng) else {
+                return Colour::black();
+            };
+
+            (1, weight)
+        } else {
+            (depth, 1.0)
+        };
+
+        self.reflected_colour_with(computations, depth, rng, &Ggx) * weight
+    }
+
+    /// Calculate the reflected colour, rough materials average a number of
+    /// reflection rays whose directions are chosen by `sampler`, each weighted
+    /// by the GGX BRDF and the probability of choosing it. Only the first rough
+    /// surface along a path takes more than one sample, otherwise the number of
+    /// rays would grow exponentially with depth.
+    #[must_use]
+    fn reflected_colour_with<R: Rng, S: ReflectionSampler>(
+        &self,
+        computations: &Computations,
+        depth: u32,
+        rng: &mut R,
+        sampler: &S,
+    ) -> Colour {
+        let material = computations.object.material();
+
+        if material.roughness <= 0.0 {
+            let reflect_ray =
+                Ray::new(computations.over_point, computations.reflect)
+                    .with_wavelength(computations.wavelength)
+                    .with_glossy(computations.glossy);
+
+            let colour =
+                self.colour_from(computations, &reflect_ray, depth - 1, rng);
+
+            return colour * material.reflective;
+        }
+
+        let count = if computations.glossy {
+            1
+        } else {
+            material.reflection_samples.max(1)
+        };
+        let mut colour = Colour::black();
+
+        for _ in 0..count {
+            let (direction, pdf) = sampler.sample(
+                &computations.normal,
+                &computations.eye,
+                material.roughness,
+                rng,
+            );
+
+            // Directions below the surface carry no light and those that could
+            // never have been chosen would divide by zero.
+            let brdf = ggx_brdf_cos(
+                &computations.normal,
+                &computations.eye,
+                &direction,
+                material.roughness,
+            );
+
+            if brdf <= 0.0 || pdf <= 0.0 {
+                continue;
+            }
+
+            let reflect_ray = Ray::new(computations.over_point, direction)
+                .with_wavelength(computations.wavelength)
+                .with_glossy(true);
+
+            colour +=
+                self.colour_from(computations, &reflect_ray, depth - 1, rng)
+                    * (brdf / pdf);
+        }
+
+        colour / f64::from(count) * material.reflective
+    }
+
+    #[must_use]
+    pub fn refracted_colour<R: Rng>(
+        &self,
+        computations: &Computations,
+        depth: u32,
+        rng: &mut R,
+    ) -> Colour {
+        let transparency = computations.object.material().transparency;
+
+        if approx_eq!(transparency, 0.0) {
+            return Colour::black();
+        }
+
+        let (depth, weight) = if depth == 0 {
+            let Some(weight) = self.roulette(transparency, rng) else {
+                return Colour::black();
+            };
+
+            (1, weight)
+        } else {
+            (depth, 1.0)
+        };
+
+        let Some(direction) = computations.refracted_direction() else {
+            return Colour::black();
+        };
+
+        let refracted_ray = Ray::new(computations.under_point, direction)
+            .with_wavelength(computations.wavelength)
+            .with_glossy(computations.glossy);
+
+        self.colour_from(computations, &refracted_ray, depth - 1, rng)
+            * transparency
+            * weight
+    }
+}
+
+/// Build two vectors that, along with `axis`, form an orthonormal basis.
+#[must_use]
+fn basis(axis: &Vector) -> (Vector, Vector) {
+    let other =
+        if axis.x.abs() > 0.9 { Vector::y_axis() } else { Vector::x_axis() };
+
+    let tangent = axis.cross(&other).normalise();
+    let bitangent = axis.cross(&tangent);
+
+    (tangent, bitangent)
+}
+
+/// A `ReflectionSampler` chooses the directions of rays reflected from rough
+/// surfaces.
+trait ReflectionSampler {
+    /// Choose the direction of a ray reflected towards `eye`, returning it
+    /// along with the probability density of choosing it.
+    fn sample<R: Rng>(
+        &self,
+        normal: &Vector,
+        eye: &Vector,
+        roughness: f64,
+        rng: &mut R,
+    ) -> (Vector, f64);
+}
+
+/// Importance samples reflection directions using the GGX microfacet
+/// distribution, a microfacet normal is chosen around `normal` with a spread
+/// controlled by `roughness` and the eye vector is reflected about it. This
+/// concentrates samples where the distribution is largest, close to the mirror
+/// direction.
+struct Ggx;
+
+impl ReflectionSampler for Ggx {
+    fn sample<R: Rng>(
+        &self,
+        normal: &Vector,
+        eye: &Vector,
+        roughness: f64,
+        rng: &mut R,
+    ) -> (Vector, f64) {
+        let alpha = roughness * roughness;
+
+        let u: f64 = rng.gen_range(0.0..1.0);
+        let phi = rng.gen_range(0.0..TAU);
+
+        let theta = (alpha * (u / (1.0 - u)).sqrt()).atan();
+
+        let (tangent, bitangent) = basis(normal);
+
+        let microfacet = tangent * (theta.sin() * phi.cos())
+            + bitangent * (theta.sin() * phi.sin())
+            + *normal * theta.cos();
+
+        let direction = (-*eye).reflect(&microfacet);
+
+        let pdf = ggx_distribution(normal, &microfacet, alpha)
+            * normal.dot(&microfacet)
+            / (4.0 * eye.dot(&microfacet).abs());
+
+        (direction, pdf)
+    }
+}
+
+/// The GGX distribution of microfacet normals around `normal`.
+#[must_use]
+fn ggx_distribution(normal: &Vector, microfacet: &Vector, alpha: f64) -> f64 {
+    let alpha_2 = alpha * alpha;
+    let cos_theta = normal.dot(microfacet);
+    let denominator = cos_theta * cos_theta * (alpha_2 - 1.0) + 1.0;
+
+    alpha_2 / (PI * denominator * denominator)
+}
+
+/// The Smith masking term for GGX, the fraction of microfacets visible from
+/// `direction`.
+#[must_use]
+fn smith_masking(normal: &Vector, direction: &Vector, alpha: f64) -> f64 {
+    let alpha_2 = alpha * alpha;
+    let cos_theta = normal.dot(direction);
+
+    2.0 * cos_theta
+        / (cos_theta
+            + (alpha_2 + (1.0 - alpha_2) * cos_theta * cos_theta).sqrt())
+}
+
+/// The GGX BRDF for light arriving along `light` and leaving along `eye`,
+/// multiplied by the cosine of the angle of `light` to `normal`. There is no
+/// Fresnel term, the material's `reflective` stands in for it.
+#[must_use]
+fn ggx_brdf_cos(
+    normal: &Vector,
+    eye: &Vector,
+    light: &Vector,
+    roughness: f64,
+) -> f64 {
+    let cos_eye = normal.dot(eye);
+    let cos_light = normal.dot(light);
+
+    if cos_eye <= 0.0 || cos_light <= 0.0 {
+        return 0.0;
+    }
+
+    let alpha = roughness * roughness;
+    let microfacet = (*eye + *light).normalise();
+
+    ggx_distribution(normal, &microfacet, alpha)
+        * smith_masking(normal, eye, alpha)
+        * smith_masking(normal, light, alpha)
+        / (4.0 * cos_eye)
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::module_name_repetitions)]
+pub fn test_world() -> World {
+    use crate::{math::Transformation, Material};
+
+    let mut w = World::new();
+
+    w.add_object(
+        Object::sphere_builder()
+            .material(
+                Material::builder()
+                    .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                    .diffuse(0.7)
+                    .specular(0.2)
+                    .build(),
+            )
+            .build(),
+    );
+    w.add_object(
+        Object::sphere_builder()
+            .transformation(Transformation::new().scale(0.5, 0.5, 0.5))
+            .build(),
+    );
+
+    w.add_light(Light::new_point(
+        Point::new(-10.0, 10.0, -10.0),
+        Colour::white(),
+    ));
+
+    w
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        f64::consts::{FRAC_PI_2, SQRT_2},
+    };
+
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{
+        intersection::Intersection,
+        math::{float::*, Angle, Transformation, Vector},
+        object::Updatable,
+        AntiAliasing, Bounded, BoundingBox, Camera, Intersectable, Material,
+        Operation, Output, Pattern, TList, TValues,
+    };
+
+    fn rng() -> impl Rng {
+        Xoshiro256PlusPlus::seed_from_u64(0)
+    }
+
+    #[test]
+    fn creating_a_world() {
+        let w = World::new();
+        assert_eq!(w.objects.len(), 0);
+        assert_eq!(w.lights.len(), 0);
+
+        let w = World::default();
+        assert_eq!(w.objects.len(), 0);
+        assert_eq!(w.lights.len(), 0);
+    }
+
+    #[test]
+    fn adding_elements_to_a_world() {
+        let mut w = World::new();
+
+        let o1 = Object::test_builder().build();
+        let o2 = Object::sphere_builder()
+            .transformation(Transformation::new().translate(1.0, 2.0, 3.0))
+            .build();
+
+        w.add_object(o1.clone());
+        w.add_object(o2.clone());
+
+        assert_eq!(w.objects.len(), 2);
+        assert_approx_eq!(w.objects[0], &o1);
+        assert_approx_eq!(w.objects[1], &o2);
+
+        let l1 = Light::new_point(Point::origin(), Colour::blue());
+        let l2 = Light::new_point(Point::new(1.0, 2.0, 3.0), Colour::green());
+
+        w.add_light(l1);
+        w.add_light(l2);
+
+        assert_eq!(w.lights.len(), 2);
+        assert_approx_eq!(w.lights[0], l1);
+        assert_approx_eq!(w.lights[1], l2);
+    }
+
+    #[test]
+    fn the_colour_when_a_ray_misses() {
+        let w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::y_axis());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::black());
+    }
+
+    #[test]
+    fn the_colour_when_a_ray_hits() {
+        let w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        assert_approx_eq!(
+            w.colour_at(&r, 2, &mut rng()),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn the_colour_with_an_intersection_behind_the_ray() {
+        let mut w = test_world();
+
+        w.objects[0].replace_material(
+            &Material::builder()
+                .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                .ambient(1.0)
+                .diffuse(0.7)
+                .specular(0.2)
+                .build(),
+        );
+        w.objects[1]
+            .replace_material(&Material::builder().ambient(1.0).build());
+
+        let r = Ray::new(Point::new(0.0, 0.0, 0.75), -Vector::z_axis());
+
+        assert_approx_eq!(w.colour_at(&r, 1, &mut rng()), Colour::white());
+    }
+
+    #[test]
+    fn colour_at_with_mutually_reflective_surfaces() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(Point::origin(), Colour::white()));
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, 1.0, 0.0))
+                .material(Material::builder().reflective(1.0).build())
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(Material::builder().reflective(1.0).build())
+                .build(),
+        );
+
+        let r = Ray::new(Point::origin(), Vector::y_axis());
+
+        let _ = w.colour_at(&r, 5, &mut rng());
+    }
+
+    #[test]
+    fn a_material_can_cap_the_recursion_depth() {
+        let world = |max_depth| {
+            let mut w = World::new();
+
+            w.add_light(Light::new_point(Point::origin(), Colour::white()));
+
+            w.add_object(
+                Object::plane_builder()
+                    .transformation(
+                        Transformation::new().translate(0.0, 1.0, 0.0),
+                    )
+                    .material(
+                        Material::builder()
+                            .reflective(0.5)
+                            .max_depth(max_depth)
+                            .build(),
+                    )
+                    .build(),
+            );
+            w.add_object(
+                Object::plane_builder()
+                    .transformation(
+                        Transformation::new().translate(0.0, -1.0, 0.0),
+                    )
+                    .material(Material::builder().reflective(0.5).build())
+                    .build(),
+            );
+
+            w
+        };
+
+        let up = Ray::new(Point::origin(), Vector::y_axis());
+        let down = Ray::new(Point::origin(), -Vector::y_axis());
+
+        let w = world(None);
+
+        assert_approx_ne!(
+            w.colour_at(&up, 1, &mut rng()),
+            w.colour_at(&up, 5, &mut rng())
+        );
+
+        let w = world(Some(1));
+
+        assert_approx_eq!(
+            w.colour_at(&up, 1, &mut rng()),
+            w.colour_at(&up, 5, &mut rng())
+        );
+        assert_approx_eq!(
+            w.colour_at(&up, 1, &mut rng()),
+            w.colour_at(&up, 100, &mut rng())
+        );
+
+        assert_approx_ne!(
+            w.colour_at(&down, 1, &mut rng()),
+            w.colour_at(&down, 2, &mut rng())
+        );
+    }
+
+    #[test]
+    fn shading_an_intersection() {
+        let w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let i = Intersection::new(&w.objects[0], 4.0);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 5, &mut rng()),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn ambient_occlusion_only_darkens_the_ambient_light() {
+        let shade = |light_z: f64, point: Point, occlusion| {
+            let mut w = World::new();
+
+            // A floor meeting a wall along z = 0 forms a crevice.
+            w.add_object(Object::plane_builder().build());
+            w.add_object(
+                Object::plane_builder()
+                    .transformation(
+                        Transformation::new().rotate_x(Angle(FRAC_PI_2)),
+                    )
+                    .build(),
+            );
+            w.add_light(Light::new_point(
+                Point::new(0.0, 10.0, light_z),
+                Colour::white(),
+            ));
+            w.set_ambient_occlusion(occlusion);
+
+            let r = Ray::new(
+                point + Vector::new(0.0, 1.0, -1.0),
+                Vector::new(0.0, -1.0, 1.0).normalise(),
+            );
+            let i = Intersection::new(&w.objects[0], SQRT_2);
+            let c = i.prepare_computations(&r, &List::from(i));
+
+            w.shade_hit(&c, 5, &mut rng())
+        };
+
+        let ambient = Colour::new(0.1, 0.1, 0.1);
+        let crevice = Point::new(0.0, 0.0, -0.05);
+        let open = Point::new(0.0, 0.0, -5.0);
+
+        // With the light behind the wall the crevice only gets ambient light,
+        // which ambient occlusion darkens.
+        let plain = shade(10.0, crevice, None);
+        let occluded = shade(10.0, crevice, Some((64, 1.0)));
+
+        assert_approx_eq!(plain, ambient);
+        assert!(occluded.red < 0.8 * ambient.red);
+
+        // With the light in front the direct light is kept in full and only
+        // the ambient light is darkened.
+        let plain = shade(-10.0, crevice, None);
+        let occluded = shade(-10.0, crevice, Some((64, 1.0)));
+
+        assert!(occluded.red < plain.red);
+        assert!(plain.red - occluded.red <= ambient.red);
+        assert!(occluded.red > 0.5);
+
+        // Out in the open nothing is blocked so nothing changes.
+        assert_approx_eq!(
+            shade(-10.0, open, Some((64, 1.0))),
+            shade(-10.0, open, None)
+        );
+    }
+
+    #[test]
+    fn sampling_a_subset_of_lights_converges_on_all_lights() {
+        let mut w = World::new();
+
+        w.add_object(Object::plane_builder().build());
+
+        for (x, z, intensity) in [
+            (-4.0, 1.0, 0.8),
+            (-2.0, -3.0, 0.2),
+            (0.0, 2.0, 0.5),
+            (1.0, 0.0, 1.0),
+            (3.0, -1.0, 0.1),
+            (5.0, 4.0, 0.6),
+        ] {
+            w.add_light(Light::new_point(
+                Point::new(x, 3.0, z),
+                Colour::new(intensity, intensity * 0.5, 0.2),
+            ));
+        }
+
+        let r = Ray::new(Point::new(0.0, 1.0, -1.0), -Vector::y_axis());
+
+        let i = Intersection::new(&w.objects[0], 1.0);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        let mut rng = rng();
+
+        let expected = w.shade_hit(&c, 5, &mut rng);
+
+        let with_samples = |samples| {
+            let mut w = w.clone();
+
+            w.set_light_samples(Some(samples));
+
+            w
+        };
+
+        let world = with_samples(2);
+
+        let samples = 20_000;
+        let mut colour = Colour::black();
+
+        for _ in 0..samples {
+            colour += world.shade_hit(&c, 5, &mut rng);
+        }
+
+        assert_approx_eq!(
+            colour / f64::from(samples),
+            expected,
+            epsilon = 0.01
+        );
+
+        // A single sample is not the same as sampling every light.
+        assert_approx_ne!(with_samples(1).shade_hit(&c, 5, &mut rng), expected);
+
+        // Asking for as many samples as there are lights samples each light.
+        assert_approx_eq!(with_samples(6).shade_hit(&c, 5, &mut rng), expected);
+    }
+
+    #[test]
+    fn russian_roulette_matches_a_deep_render() {
+        let mut w = World::new();
+
+        let mirror = Material::builder()
+            .pattern(Colour::new(0.8, 0.4, 0.2).into())
+            .ambient(0.2)
+            .reflective(0.8)
+            .build();
+
+        w.add_object(Object::plane_builder().material(mirror.clone()).build());
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, 1.0, 0.0))
+                .material(mirror)
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.5, 0.0),
+            Colour::white(),
+        ));
+
+        let r = Ray::new(
+            Point::new(0.0, 0.5, -5.0),
+            Vector::new(0.0, 1.0, 1.0).normalise(),
+        );
+
+        let mut rng = rng();
+
+        let expected = w.colour_at(&r, 100, &mut rng);
+        let shallow = w.colour_at(&r, 2, &mut rng);
+
+        assert_approx_ne!(shallow, expected, epsilon = 0.5);
+
+        w.set_russian_roulette(true);
+
+        let samples = 5_000;
+        let mut colour = Colour::black();
+
+        for _ in 0..samples {
+            colour += w.colour_at(&r, 2, &mut rng);
+        }
+
+        assert_approx_eq!(
+            colour / f64::from(samples),
+            expected,
+            epsilon = 0.05
+        );
+    }
+
+    #[test]
+    fn shading_an_intersection_from_the_inside() {
+        let mut w = test_world();
+
+        w.lights.clear();
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.25, 0.0),
+            Colour::white(),
+        ));
+
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        let i = Intersection::new(&w.objects[1], 0.5);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 5, &mut rng()),
+            Colour::new(0.904_98, 0.904_98, 0.904_98),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn colour_when_intersection_is_in_shadow() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.0, -10.0),
+            Colour::white(),
+        ));
+
+        w.add_object(Object::sphere_builder().build());
+
+        let o = Object::sphere_builder()
+            .transformation(Transformation::new().translate(0.0, 0.0, 10.0))
+            .build();
+        w.add_object(o.clone());
+
+        let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::z_axis());
+
+        let i = Intersection::new(&o, 4.0);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 3, &mut rng()),
+            Colour::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn shade_hit_with_a_reflective_material() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -sqrt_2_div_2, sqrt_2_div_2),
+        );
+
+        let i = Intersection::new(&w.objects[2], SQRT_2);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 5, &mut rng()),
+            Colour::new(0.876_76, 0.924_34, 0.829_17),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn shade_hit_with_a_transparent_material() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(
+                    Material::builder()
+                        .transparency(0.5)
+                        .refractive_index(1.5)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().translate(0.0, -3.5, -0.5),
+                )
+                .material(
+                    Material::builder()
+                        .pattern(Colour::red().into())
+                        .ambient(0.5)
+                        .build(),
+                )
+                .build(),
+        );
+
+        let o = &w.objects[2];
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -sqrt_2_div_2, sqrt_2_div_2),
+        );
+
+        let l = List::from(Intersection::new(o, SQRT_2));
+
+        let c = l[0].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 5, &mut rng()),
+            Colour::new(0.936_43, 0.686_43, 0.686_43),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn shade_hit_with_a_reflective_transparent_material() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(
+                    Material::builder()
+                        .reflective(0.5)
+                        .transparency(0.5)
+                        .refractive_index(1.5)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().translate(0.0, -3.5, -0.5),
+                )
+                .material(
+                    Material::builder()
+                        .pattern(Colour::red().into())
+                        .ambient(0.5)
+                        .build(),
+                )
+                .build(),
+        );
+
+        let o = &w.objects[2];
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -sqrt_2_div_2, sqrt_2_div_2),
+        );
+
+        let l = List::from(Intersection::new(o, SQRT_2));
+
+        let c = l[0].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.shade_hit(&c, 5, &mut rng()),
+            Colour::new(0.933_92, 0.696_43, 0.692_43),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn intersect_a_world_with_a_ray() {
+        let w = test_world();
+
+        let i = w
+            .intersect(
+                &Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(i.len(), 4);
+        assert_approx_eq!(i[0].t, 4.0);
+        assert_approx_eq!(i[1].t, 4.5);
+        assert_approx_eq!(i[2].t, 5.5);
+        assert_approx_eq!(i[3].t, 6.0);
+    }
+
+    #[test]
+    fn rendering_a_world_with_a_camera() {
+        let w = test_world();
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let i = c
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        assert_approx_eq!(
+            i.get_pixel(5, 5),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_a_world_multi_threaded() {
+        let w = test_world();
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let i = c
+            .render(&w, 5, false, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        assert_approx_eq!(
+            i.get_pixel(5, 5),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rendering_a_world_with_a_callback() {
+        let w = test_world();
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+        let i = c
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        let mut pixels = Vec::new();
+        c.render_with_callback(
+            &w,
+            5,
+            false,
+            AntiAliasing::None,
+            &mut o,
+            &mut rng(),
+            |x, y, colour| pixels.push((x, y, colour)),
+        )
+        .unwrap();
+
+        assert_eq!(pixels.len(), 11 * 11);
+
+        for (x, y, colour) in pixels {
+            assert_approx_eq!(colour, i.get_pixel(x as usize, y as usize));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn rendering_a_world_with_edge_anti_aliasing() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        /// Counts the rays leaving the camera, the only rays starting there.
+        #[derive(Debug, Default)]
+        struct CameraRays(AtomicU64);
+
+        impl Intersector for CameraRays {
+            fn intersect<'a>(
+                &self,
+                objects: &'a [Object],
+                ray: &Ray,
+            ) -> Option<List<'a>> {
+                if approx_eq!(ray.origin, Point::new(0.0, 0.0, -5.0)) {
+                    self.0.fetch_add(1, Ordering::Relaxed);
+                }
+
+                AllObjects.intersect(objects, ray)
+            }
+        }
+
+        let mut w = test_world();
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+
+        let intersector = Arc::new(CameraRays::default());
+        w.set_intersector(Some(intersector.clone()));
+
+        let (i, s) = c
+            .render_with_stats(
+                &w,
+                5,
+                true,
+                AntiAliasing::None,
+                &mut o,
+                &mut rng(),
+            )
+            .unwrap();
+
+        assert_eq!(intersector.0.swap(0, Ordering::Relaxed), 121);
+        assert_eq!(s.rays(), 121);
+
+        let (a, s) = c
+            .render_with_stats(
+                &w,
+                5,
+                false,
+                AntiAliasing::Edges,
+                &mut o,
+                &mut rng(),
+            )
+            .unwrap();
+
+        let rays = intersector.0.load(Ordering::Relaxed);
+
+        assert_eq!(s.rays(), rays);
+
+        let edges = c.find_edges(&i.pixels().copied().collect::<Vec<_>>());
+        let edge_count = edges.iter().filter(|edge| **edge).count() as u64;
+
+        assert!(edge_count > 0 && edge_count < 121);
+
+        // Every edge pixel is traced again with 4 x 4 samples.
+        assert_eq!(rays, 121 + edge_count * 16);
+
+        let mut changed = false;
+        for y in 0..11 {
+            for x in 0..11 {
+                let single = i.get_pixel(x, y);
+                let multi = a.get_pixel(x, y);
+
+                if edges[y * 11 + x] {
+                    changed |= approx_ne!(single, multi);
+                } else {
+                    // Flat regions must match the single sample pass exactly.
+                    assert_eq!(
+                        [single.red, single.green, single.blue]
+                            .map(f64::to_bits),
+                        [multi.red, multi.green, multi.blue].map(f64::to_bits)
+                    );
+                }
+            }
+        }
+
+        assert!(changed);
+    }
+
+    #[test]
+    fn summarising_a_world() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::group_builder()
+                .add_object(Object::cube_builder().build())
+                .add_object(Object::new_csg(
+                    Operation::Union,
+                    Object::sphere_builder().build(),
+                    Object::plane_builder().build(),
+                ))
+                .build(),
+        );
+        w.add_light(Light::new_area(
+            Point::origin(),
+            Vector::x_axis(),
+            2,
+            Vector::y_axis(),
+            2,
+            Colour::white(),
+        ));
+
+        let s = w.summary();
+
+        assert!(s.resolution().is_none());
+        assert_eq!(s.shapes(None), 5);
+        assert_eq!(s.shapes(Some("sphere")), 3);
+        assert_eq!(s.shapes(Some("cube")), 1);
+        assert_eq!(s.shapes(Some("plane")), 1);
+        assert_eq!(s.groups(), 1);
+        assert_eq!(s.csgs(), 1);
+        assert_eq!(s.lights(None), 2);
+        assert_eq!(s.lights(Some("area")), 1);
+        assert_eq!(s.lights(Some("point")), 1);
+    }
+
+    #[test]
+    fn limiting_the_number_of_intersections() {
+        let mut w = World::new();
+
+        for i in 0..50 {
+            w.add_object(
+                Object::sphere_builder()
+                    .transformation(Transformation::new().translate(
+                        0.0,
+                        0.0,
+                        f64::from(i) * 0.01,
+                    ))
+                    .build(),
+            );
+        }
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        assert_eq!(w.max_intersections(), None);
+        assert_eq!(w.intersect(&r, false).unwrap().len(), 100);
+        assert_eq!(w.truncated_intersections(), 0);
+
+        w.set_max_intersections(Some(10));
+        assert_eq!(w.max_intersections(), Some(10));
+
+        let l = w.intersect(&r, false).unwrap();
+
+        assert_eq!(l.len(), 10);
+        assert_eq!(w.truncated_intersections(), 1);
+
+        assert_approx_eq!(l.hit().unwrap().t, 4.0);
+        for (i, intersection) in (0..10).zip(l.iter()) {
+            assert_approx_eq!(intersection.t, 4.0 + f64::from(i) * 0.01);
+        }
+
+        // Hits behind the origin are kept for refraction.
+        let r = Ray::new(Point::new(0.0, 0.0, -0.5), Vector::z_axis());
+
+        let l = w.intersect(&r, false).unwrap();
+
+        assert_eq!(l.len(), 60);
+        assert_approx_eq!(l.hit().unwrap().t, 1.5);
+        assert_eq!(w.truncated_intersections(), 2);
+
+        w.reset_truncated_intersections();
+        assert_eq!(w.truncated_intersections(), 0);
+
+        w.set_max_intersections(None);
+        assert_eq!(w.intersect(&r, false).unwrap().len(), 100);
+
+        // Lists are only counted once however deeply the objects are nested.
+        let mut g = World::new();
+        g.add_object(
+            Object::group_builder().set_objects(take(&mut w.objects)).build(),
+        );
+        g.set_max_intersections(Some(10));
+
+        assert_eq!(g.intersect(&r, false).unwrap().len(), 60);
+        assert_eq!(g.truncated_intersections(), 1);
+    }
+
+    #[test]
+    fn is_shadow_tests_for_occlusion_between_two_point() {
+        let w = test_world();
+
+        let l = Point::new(-10.0, -10.0, -10.0);
+
+        assert!(!w.is_shadowed(&l, &Point::new(-10.0, -10.0, 10.0)));
+        assert!(w.is_shadowed(&l, &Point::new(10.0, 10.0, 10.0)));
+        assert!(!w.is_shadowed(&l, &Point::new(-20.0, -20.0, -20.0)));
+        assert!(!w.is_shadowed(&l, &Point::new(-5.0, -5.0, 5.0)));
+    }
+
+    #[test]
+    fn lighting_a_two_sided_surface_from_behind() {
+        let mut w = World::new();
+        w.add_light(Light::new_point(
+            Point::new(0.0, 10.0, 0.0),
+            Colour::white(),
+        ));
+        w.add_object(Object::plane_builder().build());
+
+        let r = Ray::new(Point::new(0.0, -5.0, 0.0), Vector::y_axis());
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(0.1, 0.1, 0.1)
+        );
+
+        w.objects[0]
+            .replace_material(&Material::builder().two_sided(true).build());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::white());
+
+        // Lights on the same side as the eye are unchanged.
+        let r = Ray::new(Point::new(0.0, 5.0, 0.0), -Vector::y_axis());
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(1.9, 1.9, 1.9)
+        );
+    }
+
+    #[test]
+    fn no_shadow_when_an_object_does_not_cast_shadow() {
+        let mut w = test_world();
+
+        w.objects[0] = Object::sphere_builder()
+            .material(
+                Material::builder()
+                    .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                    .diffuse(0.7)
+                    .specular(0.2)
+                    .build(),
+            )
+            .casts_shadow(false)
+            .build();
+
+        assert!(!w.is_shadowed(
+            &w.lights[0].positions(&mut rng())[0],
+            &Point::new(10.0, -10.0, 10.0)
+        ));
+    }
+
+    #[test]
+    fn a_light_in_a_group_moves_with_the_group() {
+        let l =
+            Light::new_point(Point::new(-10.0, 10.0, -10.0), Colour::white());
+
+        let position =
+            |w: &World| w.lights().next().unwrap().positions(&mut rng())[0];
+        let point = Point::new(10.0, -10.0, 10.0);
+
+        let mut w = test_world();
+
+        w.lights.clear();
+        w.add_object(Object::group_builder().add_light(l).build());
+
+        assert_eq!(w.lights().count(), 1);
+        assert_approx_eq!(position(&w), Point::new(-10.0, 10.0, -10.0));
+        assert!(w.is_shadowed(&position(&w), &point));
+
+        let mut w = test_world();
+
+        w.lights.clear();
+        w.add_object(
+            Object::group_builder()
+                .transformation(Transformation::new().translate(0.0, 0.0, 30.0))
+                .add_object(Object::group_builder().add_light(l).build())
+                .build(),
+        );
+
+        assert_eq!(w.lights().count(), 1);
+        assert_approx_eq!(position(&w), Point::new(-10.0, 10.0, 20.0));
+        assert!(!w.is_shadowed(&position(&w), &point));
+    }
+
+    #[test]
+    fn shadow_bias_removes_acne_at_large_scales() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().scale(1.0e6, 1.0e6, 1.0e6),
+                )
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(1.0e5, 1.0e5, 1.0e5)
+                        .translate(0.0, 0.0, -1.2e7),
+                )
+                .material(Material::builder().ambient(1.0).build())
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(0.0, 3.0e6, -1.0e7),
+            Colour::white(),
+        ));
+
+        let rays = (0..100)
+            .map(|index| {
+                let x = f64::from(index % 10) * 1_000.0;
+                let y = f64::from(index / 10) * 1_000.0;
+
+                Ray::new(Point::new(x, y, -1.0e7), Vector::z_axis())
+            })
+            .collect::<Vec<_>>();
+
+        let shadowed = |w: &World| {
+            rays.iter()
+                .filter(|ray| {
+                    let i = w.intersect(ray, false).unwrap();
+                    let h = i.hit().unwrap();
+                    let c = h.prepare_computations(ray, &i);
+
+                    let shadow_point = c.point + c.normal * w.shadow_bias();
+
+                    w.is_shadowed(
+                        &Point::new(0.0, 3.0e6, -1.0e7),
+                        &shadow_point,
+                    )
+                })
+                .count()
+        };
+
+        let reflected = |w: &World| {
+            let i = w.intersect(&rays[0], false).unwrap();
+            let h = i.hit().unwrap();
+            let c = h.prepare_computations(&rays[0], &i);
+
+            w.reflected_colour(&c, 5, &mut rng())
+        };
+
+        let colours = |w: &World| {
+            rays.iter()
+                .map(|ray| w.colour_at(ray, 0, &mut rng()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_approx_eq!(w.shadow_bias(), 100_000.0 * f64::EPSILON);
+
+        let before = reflected(&w);
+        let speckled = colours(&w);
+
+        assert!(shadowed(&w) > 0);
+
+        w.set_shadow_bias(1.0);
+
+        assert_eq!(shadowed(&w), 0);
+
+        // Pixels that were wrongly in shadow are now lit.
+        assert!(colours(&w)
+            .iter()
+            .zip(&speckled)
+            .any(|(lit, speckle)| lit.red > speckle.red + 0.1));
+
+        assert_approx_eq!(reflected(&w), before);
+        assert_approx_ne!(before, Colour::black());
+    }
+
+    #[test]
+    fn shadows_from_a_shadow_map() {
+        let mut w = World::new();
+
+        w.add_object(Object::plane_builder().build());
+        w.add_object(
+            Object::cube_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(1.0, 0.2, 1.0)
+                        .translate(0.0, 2.0, 0.0),
+                )
+                .build(),
+        );
+
+        w.build_shadow_map(64);
+
+        assert!(w.shadow_map.is_none());
+
+        let l = Light::new_directional(
+            Vector::new(1.0, -2.0, 0.5),
+            Colour::white(),
+        );
+        w.add_light(l);
+
+        w.build_shadow_map(64);
+
+        assert!(w.shadow_map.is_some());
+
+        let position = l.positions(&mut rng())[0];
+        let p = Point::new(1.0, 0.0, 0.25);
+
+        assert!(w.is_shadowed(&position, &p));
+
+        w.clear_shadow_map();
+
+        assert!(w.shadow_map.is_none());
+        assert!(w.is_shadowed(&position, &p));
+
+        w.build_shadow_map(64);
+
+        // The map is cleared when objects change.
+        let _ = w.remove_object(1);
+
+        assert!(w.shadow_map.is_none());
+        assert!(!w.is_shadowed(&position, &p));
+    }
+
+    #[test]
+    fn moving_an_occluder_clears_the_shadow_map() {
+        let mut w = World::new();
+
+        w.add_light(Light::new_directional(-Vector::y_axis(), Colour::white()));
+        w.add_object(Object::plane_builder().build());
+        w.add_object(
+            Object::cube_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(1.0, 0.2, 1.0)
+                        .translate(0.0, 2.0, 0.0),
+                )
+                .build(),
+        );
+
+        let r = Ray::new(
+            Point::new(0.0, 5.0, -5.0),
+            Vector::new(0.0, -1.0, 1.0).normalise(),
+        );
+
+        w.build_shadow_map(64);
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(0.1, 0.1, 0.1)
+        );
+
+        w.objects_mut()[1].update_transformation(
+            &Transformation::new().translate(10.0, 0.0, 0.0),
+        );
+
+        assert!(w.shadow_map.is_none());
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::white(),
+            epsilon = 0.000_01
+        );
+
+        let mut check = |change: &dyn Fn(&mut World)| {
+            w.build_shadow_map(64);
+            assert!(w.shadow_map.is_some());
+
+            change(&mut w);
+            assert!(w.shadow_map.is_none());
+        };
+
+        check(&|w| w.add_object(Object::sphere_builder().build()));
+        check(&|w| {
+            let _ = w.replace_object(2, Object::sphere_builder().build());
+        });
+        check(&|w| {
+            let _ = w.remove_object(2);
+        });
+        check(&|w| w.divide(1));
+        check(&|w| w.set_time(0.0));
+    }
+
+    #[test]
+    fn intersecting_without_bounding_boxes() {
+        /// A unit square in the xy plane whose bounding box wrongly only
+        /// covers a small area around its centre.
+        #[derive(Debug)]
+        struct Square;
+
+        impl Intersectable for Square {
+            fn intersect(&self, ray: &Ray) -> Option<TList> {
+                let t = -ray.origin.z / ray.direction.z;
+                let point = ray.position(t);
+
+                if point.x.abs() > 1.0 || point.y.abs() > 1.0 {
+                    return None;
+                }
+
+                Some(TList::from(TValues::new(t)))
+            }
+
+            fn normal_at(
+                &self,
+                _point: &Point,
+                _intersection: &Intersection,
+            ) -> Vector {
+                -Vector::z_axis()
+            }
+        }
+
+        impl Bounded for Square {
+            fn bounding_box(&self) -> BoundingBox {
+                BoundingBox::new(
+                    Point::new(-0.1, -0.1, 0.0),
+                    Point::new(0.1, 0.1, 0.0),
+                )
+            }
+        }
+
+        let mut w = World::new();
+        w.add_object(
+            Object::group_builder()
+                .add_object(Object::custom_builder(Square).build())
+                .build(),
+        );
+
+        let r = Ray::new(Point::new(0.5, 0.5, -5.0), Vector::z_axis());
+
+        assert!(w.intersect(&r, false).is_none());
+        assert!(!w.intersect_any(&r, 10.0));
+
+        w.set_bounding_boxes(false);
+
+        let l = w.intersect(&r, false).unwrap();
+
+        assert_eq!(l.len(), 1);
+        assert_approx_eq!(l[0].t, 5.0);
+        assert!(w.intersect_any(&r, 10.0));
+    }
+
+    #[test]
+    fn rays_that_miss_see_the_background() {
+        let mut w = World::new();
+
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        assert_approx_eq!(w.background(), Colour::black());
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::black());
+
+        w.set_background(Colour::red());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::red());
+        assert_approx_eq!(
+            w.colour_at_with_background(
+                &r,
+                0.0,
+                f64::INFINITY,
+                5,
+                Some(Colour::blue()),
+                &mut rng()
+            ),
+            Colour::blue()
+        );
+
+        // Reflections of the background also see the override.
+        w.add_object(
+            Object::plane_builder()
+                .material(
+                    Material::builder()
+                        .pattern(Colour::black().into())
+                        .specular(0.0)
+                        .reflective(1.0)
+                        .build(),
+                )
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(0.0, 10.0, 0.0),
+            Colour::white(),
+        ));
+
+        let r =
+            Ray::new(Point::origin(), Vector::new(0.0, -1.0, 1.0).normalise());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::red());
+        assert_approx_eq!(
+            w.colour_at_with_background(
+                &r,
+                0.0,
+                f64::INFINITY,
+                5,
+                Some(Colour::blue()),
+                &mut rng()
+            ),
+            Colour::blue()
+        );
+    }
+
+    #[test]
+    fn rays_that_miss_see_the_environment() {
+        let mut w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::x_axis());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::black());
+
+        w.set_environment(Some(CubeMap::from_colours([
+            Colour::red(),
+            Colour::green(),
+            Colour::blue(),
+            Colour::yellow(),
+            Colour::cyan(),
+            Colour::purple(),
+        ])));
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::red());
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), -Vector::z_axis());
+
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), Colour::purple());
+
+        // Hits are unaffected.
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(0.380_66, 0.475_83, 0.285_5),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn removing_and_replacing_objects() {
+        let mut w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let c = Colour::new(0.380_66, 0.475_83, 0.285_5);
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            c,
+            epsilon = 0.000_01
+        );
+
+        let outer = w.remove_object(0);
+
+        assert_eq!(w.objects.len(), 1);
+
+        // Only the inner sphere with a default material is left.
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(0.601_86, 0.601_86, 0.601_86),
+            epsilon = 0.000_01
+        );
+
+        let inner = w.replace_object(0, outer);
+
+        assert_eq!(w.objects.len(), 1);
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            c,
+            epsilon = 0.000_01
+        );
+
+        w.objects_mut()[0] = inner;
+        w.lights_mut()[0] = Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::new(0.5, 0.5, 0.5),
+        );
+
+        assert_approx_eq!(
+            w.colour_at(&r, 5, &mut rng()),
+            Colour::new(0.300_93, 0.300_93, 0.300_93),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn colours_are_not_checked_by_default() {
+        let mut w = test_world();
+        w.lights[0] = Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::new(f64::NAN, 1.0, 1.0),
+        );
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        assert!(!w.colour_at(&r, 5, &mut rng()).is_finite());
+    }
+
+    #[test]
+    #[should_panic(expected = "Non finite colour")]
+    fn checking_for_non_finite_colours() {
+        let mut w = test_world();
+        w.lights[0] = Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::new(f64::NAN, 1.0, 1.0),
+        );
+        w.set_check_colours(true);
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let _ = w.colour_at(&r, 5, &mut rng());
+    }
+
+    #[test]
+    fn intersecting_any_object_along_a_ray() {
+        let w = test_world();
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        assert!(w.intersect_any(&r, 10.0));
+        assert!(w.intersect_any(&r, 4.5));
+        assert!(!w.intersect_any(&r, 3.9));
+
+        let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::z_axis());
+
+        assert!(!w.intersect_any(&r, 10.0));
+
+        let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::z_axis());
+
+        assert!(!w.intersect_any(&r, 10.0));
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn the_reflected_colour_for_a_non_reflective_material() {
+        let mut w = test_world();
+
+        w.objects[0].replace_material(
+            &Material::builder()
+                .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                .diffuse(0.7)
+                .specular(0.2)
+                .build(),
+        );
+        w.objects[1]
+            .replace_material(&Material::builder().ambient(1.0).build());
+
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        let o = &w.objects[1];
+
+        let i = Intersection::new(o, 1.0);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.reflected_colour(&c, 3, &mut rng()),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    fn the_reflected_colour_for_a_reflective_material() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -sqrt_2_div_2, sqrt_2_div_2),
+        );
+
+        let i = Intersection::new(&w.objects[2], SQRT_2);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.reflected_colour(&c, 4, &mut rng()),
+            Colour::new(0.190_33, 0.237_91, 0.142_74),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn importance_sampled_reflections_have_lower_variance() {
+        struct UniformCone;
+
+        impl ReflectionSampler for UniformCone {
+            fn sample<R: Rng>(
+                &self,
+                normal: &Vector,
+                eye: &Vector,
+                roughness: f64,
+                rng: &mut R,
+            ) -> (Vector, f64) {
+                let reflect = (-*eye).reflect(normal);
+
+                let cos_max = (roughness * FRAC_PI_2).cos();
+                let cos_theta: f64 = rng.gen_range(cos_max..=1.0);
+                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+                let phi = rng.gen_range(0.0..TAU);
+
+                let (tangent, bitangent) = basis(&reflect);
+
+                let direction = tangent * (sin_theta * phi.cos())
+                    + bitangent * (sin_theta * phi.sin())
+                    + reflect * cos_theta;
+
+                (direction, 1.0 / (TAU * (1.0 - cos_max)))
+            }
+        }
+
+        fn variance<S: ReflectionSampler>(
+            w: &World,
+            c: &Computations,
+            rng: &mut impl Rng,
+            sampler: &S,
+        ) -> f64 {
+            let values = (0..500)
+                .map(|_| w.reflected_colour_with(c, 4, rng, sampler).red)
+                .collect::<Vec<_>>();
+
+            let mean = values.iter().sum::<f64>() / 500.0;
+
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / 500.0
+        }
+
+        let mut w = World::new();
+
+        w.add_light(Light::new_point(
+            Point::new(0.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(
+            Object::plane_builder()
+                .material(
+                    Material::builder()
+                        .pattern(Colour::black().into())
+                        .ambient(0.0)
+                        .diffuse(0.0)
+                        .specular(0.0)
+                        .reflective(1.0)
+                        .roughness(0.2)
+                        .reflection_samples(4)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(
+                    Transformation::new()
+                        .rotate_x(Angle(FRAC_PI_2))
+                        .translate(0.0, 0.0, 5.0),
+                )
+                .material(
+                    Material::builder()
+                        .pattern(
+                            Pattern::stripe_builder(
+                                Colour::white().into(),
+                                Colour::black().into(),
+                            )
+                            .transformation(
+                                Transformation::new()
+                                    .scale(6.0, 1.0, 1.0)
+                                    .translate(-3.0, 0.0, 0.0),
+                            )
+                            .build(),
+                        )
+                        .ambient(1.0)
+                        .diffuse(0.0)
+                        .specular(0.0)
+                        .build(),
+                )
+                .build(),
+        );
+
+        let r = Ray::new(
+            Point::new(0.0, 1.0, -5.0),
+            Vector::new(0.0, -1.0, 1.0).normalise(),
+        );
+
+        let i = Intersection::new(&w.objects[0], SQRT_2);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        let mut rng = rng();
+
+        let importance = variance(&w, &c, &mut rng, &Ggx);
+        let uniform = variance(&w, &c, &mut rng, &UniformCone);
+
+        assert!(importance < uniform, "{importance} >= {uniform}");
+    }
+
+    #[test]
+    fn glossy_rays_take_a_single_reflection_sample() {
+        struct Counting(Cell<u32>);
+
+        impl ReflectionSampler for Counting {
+            fn sample<R: Rng>(
+                &self,
+                normal: &Vector,
+                eye: &Vector,
+                roughness: f64,
+                rng: &mut R,
+            ) -> (Vector, f64) {
+                self.0.set(self.0.get() + 1);
+
+                Ggx.sample(normal, eye, roughness, rng)
+            }
+        }
+
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(
+                    Material::builder()
+                        .reflective(0.5)
+                        .roughness(0.2)
+                        .reflection_samples(8)
+                        .build(),
+                )
+                .build(),
+        );
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -1.0, 1.0).normalise(),
+        );
+
+        let i = Intersection::new(&w.objects[2], SQRT_2);
+
+        let mut rng = rng();
+
+        let counting = Counting(Cell::new(0));
+        let c = i.prepare_computations(&r, &List::from(i));
+        let _ = w.reflected_colour_with(&c, 4, &mut rng, &counting);
+
+        assert_eq!(counting.0.get(), 8);
+
+        let counting = Counting(Cell::new(0));
+        let c = i.prepare_computations(&r.with_glossy(true), &List::from(i));
+        let _ = w.reflected_colour_with(&c, 4, &mut rng, &counting);
+
+        assert_eq!(counting.0.get(), 1);
+    }
+
+    #[test]
+    fn the_reflected_colour_at_the_maximum_recursion_depth() {
+        let mut w = test_world();
+
+        w.add_object(
+            Object::plane_builder()
+                .transformation(Transformation::new().translate(0.0, -1.0, 0.0))
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(
+            Point::new(0.0, 0.0, -3.0),
+            Vector::new(0.0, -sqrt_2_div_2, sqrt_2_div_2),
+        );
+
+        let i = Intersection::new(&w.objects[2], SQRT_2);
+
+        let c = i.prepare_computations(&r, &List::from(i));
+
+        assert_approx_eq!(
+            w.reflected_colour(&c, 0, &mut rng()),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn the_refracted_colour_with_an_opaque_surface() {
+        let w = test_world();
+
+        let o = &w.objects[0];
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let l = List::from(vec![
+            Intersection::new(o, 4.0),
+            Intersection::new(o, 6.0),
+        ]);
+
+        let c = l[0].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.refracted_colour(&c, 5, &mut rng()),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn the_refracted_colour_at_the_maximum_recursion_depth() {
+        let mut w = test_world();
+
+        w.objects[0].replace_material(
+            &Material::builder()
+                .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                .diffuse(0.7)
+                .specular(0.2)
+                .transparency(1.0)
+                .refractive_index(1.5)
+                .build(),
+        );
+
+        let o = &w.objects[0];
+
+        let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::z_axis());
+
+        let l = List::from(vec![
+            Intersection::new(o, 4.0),
+            Intersection::new(o, 6.0),
+        ]);
+
+        let c = l[0].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.refracted_colour(&c, 0, &mut rng()),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn the_refracted_colour_under_total_internal_reflection() {
+        let mut w = test_world();
+
+        w.objects[0].replace_material(
+            &Material::builder()
+                .pattern(Colour::new(0.8, 1.0, 0.6).into())
+                .diffuse(0.7)
+                .specular(0.2)
+                .transparency(1.0)
+                .refractive_index(1.5)
+                .build(),
+        );
+
+        let o = &w.objects[0];
+
+        let sqrt_2_div_2 = SQRT_2 / 2.0;
+
+        let r = Ray::new(Point::new(0.0, 0.0, sqrt_2_div_2), Vector::y_axis());
+
+        let l = List::from(vec![
+            Intersection::new(o, -sqrt_2_div_2),
+            Intersection::new(o, sqrt_2_div_2),
+        ]);
+
+        let c = l[1].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.refracted_colour(&c, 5, &mut rng()),
+            Colour::black()
+        );
+    }
+
+    #[test]
+    fn the_refracted_colour_with_a_reflected_ray() {
+        let mut w = test_world();
+
+        w.objects[0].replace_material(
+            &Material::builder()
+                .pattern(Pattern::test_builder().build())
+                .ambient(1.0)
+                .build(),
+        );
+        w.objects[1].replace_material(
+            &Material::builder()
+                .transparency(1.0)
+                .refractive_index(1.5)
+                .build(),
+        );
+
+        let o1 = &w.objects[0];
+        let o2 = &w.objects[1];
+
+        let r = Ray::new(Point::new(0.0, 0.0, 0.1), Vector::y_axis());
+
+        let l = List::from(vec![
+            Intersection::new(o1, -0.989_9),
+            Intersection::new(o2, -0.489_9),
+            Intersection::new(o2, 0.489_9),
+            Intersection::new(o1, 0.989_9),
+        ]);
+
+        let c = l[2].prepare_computations(&r, &l);
+
+        assert_approx_eq!(
+            w.refracted_colour(&c, 5, &mut rng()),
+            Colour::new(0.0, 0.998_88, 0.047_22),
+            epsilon = 0.000_01
+        );
+    }
+
+    #[test]
+    fn rays_pass_through_transparent_texels_of_an_alpha_map() {
+        let mut w = World::new();
+
+        // The top right quarter of each repeat of the image is transparent.
+        w.add_object(
+            Object::plane_builder()
+                .material(
+                    Material::builder()
+                        .alpha_map(Some(
+                            Pattern::load_alpha_builder(
+                                "src/scene/tests/assets/cutout.png",
+                            )
+                            .unwrap()
+                            .build(),
+                        ))
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().translate(0.75, -3.0, 0.75),
+                )
+                .build(),
+        );
+
+        let l = w
+            .intersect(
+                &Ray::new(Point::new(0.25, 1.0, 0.75), -Vector::y_axis()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(l.len(), 3);
+        assert_approx_eq!(l[0].t, 1.0);
+
+        let l = w
+            .intersect(
+                &Ray::new(Point::new(0.75, 1.0, 0.75), -Vector::y_axis()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(l.len(), 2);
+        assert_approx_eq!(l[0].t, 3.0);
+        assert_approx_eq!(l[1].t, 5.0);
+
+        assert!(w.objects[0]
+            .intersect(&Ray::new(
+                Point::new(1.75, 1.0, -0.25),
+                -Vector::y_axis()
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn white_light_through_a_dispersive_prism() {
+        let mut w = World::new();
+
+        w.add_object(
+            Object::sphere_builder()
+                .material(
+                    Material::builder()
+                        .ambient(0.0)
+                        .diffuse(0.0)
+                        .specular(0.0)
+                        .transparency(1.0)
+                        .refractive_index(1.5)
+                        .dispersion(0.05)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(
+                    Transformation::new()
+                        .rotate_x(Angle(FRAC_PI_2))
+                        .translate(0.0, 0.0, 3.0),
+                )
+                .material(
+                    Material::builder()
+                        .pattern(
+                            Pattern::stripe_builder(
+                                Colour::white().into(),
+                                Colour::black().into(),
+                            )
+                            .transformation(
+                                Transformation::new().scale(0.1, 0.1, 0.1),
+                            )
+                            .build(),
+                        )
+                        .ambient(1.0)
+                        .diffuse(0.0)
+                        .specular(0.0)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_light(Light::new_point(
+            Point::new(0.0, 0.0, -10.0),
+            Colour::white(),
+        ));
+
+        // Follow a ray through the sphere at each wavelength.
+        let r = Ray::new(Point::new(0.7, 0.0, -5.0), Vector::z_axis());
+
+        let exit = |wavelength| {
+            let r = r.with_wavelength(Some(wavelength));
+
+            let l = w.objects[0].intersect(&r).unwrap();
+            let c = l[0].prepare_computations(&r, &l);
+
+            let r = Ray::new(c.under_point, c.refracted_direction().unwrap())
+                .with_wavelength(Some(wavelength));
+
+            let l = w.objects[0].intersect(&r).unwrap();
+            let hit = l.hit().unwrap();
+
+            hit.prepare_computations(&r, &l).refracted_direction().unwrap()
+        };
+
+        let [red, green, blue] = WAVELENGTHS.map(exit);
+
+        assert_approx_ne!(red, green);
+        assert_approx_ne!(green, blue);
+        assert!(blue.x < green.x && green.x < red.x);
+
+        let colours = |w: &World| {
+            (-9..=9)
+                .map(|x| {
+                    w.colour_at(
+                        &Ray::new(
+                            Point::new(f64::from(x) / 10.0, 0.0, -5.0),
+                            Vector::z_axis(),
+                        ),
+                        5,
+                        &mut rng(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let is_grey = |colour: &Colour| {
+            approx_eq!(colour.red, colour.green)
+                && approx_eq!(colour.green, colour.blue)
+        };
+
+        let c = colours(&w);
+
+        assert!(c.iter().all(is_grey));
+        assert!(c.iter().any(|colour| colour.red > 0.5));
+
+        w.set_spectral(true);
+
+        assert!(!colours(&w).iter().all(is_grey));
+    }
+
+    #[test]
+    fn listing_intersections_with_ids() {
+        let mut w = World::new();
+
+        let refractive_index = |index| {
+            let mut m = Material::glass();
+            m.refractive_index = index;
+
+            m
+        };
+
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(Transformation::new().scale(2.0, 2.0, 2.0))
+                .material(refractive_index(1.5))
+                .build(),
+        );
+        w.add_object(
+            Object::group_builder()
+                .set_objects(vec![
+                    Object::sphere_builder()
+                        .transformation(
+                            Transformation::new().translate(0.0, 0.0, -0.25),
+                        )
+                        .material(refractive_index(2.0))
+                        .build(),
+                    Object::sphere_builder()
+                        .transformation(
+                            Transformation::new().translate(0.0, 0.0, 0.25),
+                        )
+                        .material(refractive_index(2.5))
+                        .build(),
+                ])
+                .build(),
+        );
+
+        let r = Ray::new(Point::new(0.0, 0.0, -4.0), Vector::z_axis());
+
+        let l = w.intersections_with_ids(&r);
+
+        let expected = [
+            (0, 2.0, true),
+            (1, 2.75, true),
+            (2, 3.25, true),
+            (1, 4.75, false),
+            (2, 5.25, false),
+            (0, 6.0, false),
+        ];
+
+        assert_eq!(l.len(), expected.len());
+
+        for ((id, t, entering), (expected_id, expected_t, expected_entering)) in
+            l.into_iter().zip(expected)
+        {
+            assert_eq!(id, expected_id);
+            assert_approx_eq!(t, expected_t);
+            assert_eq!(entering, expected_entering);
+        }
+
+        assert!(w
+            .intersections_with_ids(&Ray::new(
+                Point::new(0.0, 5.0, -4.0),
+                Vector::z_axis()
+            ))
+            .is_empty());
+    }
+
+    #[test]
+    fn rendering_through_a_custom_intersector() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Only ever tests the first object, counting how often it is used.
+        #[derive(Debug, Default)]
+        struct FirstObject(AtomicUsize);
+
+        impl Intersector for FirstObject {
+            fn intersect<'a>(
+                &self,
+                objects: &'a [Object],
+                ray: &Ray,
+            ) -> Option<List<'a>> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+
+                AllObjects.intersect(&objects[..1], ray)
+            }
+        }
+
+        let c = Camera::new(
+            11,
+            11,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                Point::new(0.0, 0.0, -5.0),
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let mut o = Output::<Vec<_>>::new_sink();
+
+        let mut w = test_world();
+
+        let intersector = Arc::new(FirstObject::default());
+        w.set_intersector(Some(intersector.clone()));
+
+        let image = c
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        assert!(intersector.0.load(Ordering::Relaxed) >= 121);
+
+        // Only the outer sphere is tested so rendering it alone must match.
+        let mut outer = test_world();
+        outer.remove_object(1);
+
+        let expected = c
+            .render(&outer, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_approx_eq!(
+                    image.get_pixel(x, y),
+                    expected.get_pixel(x, y)
+                );
+            }
+        }
+
+        w.set_intersector(Some(Arc::new(AllObjects)));
+
+        let image = c
+            .render(&w, 5, true, AntiAliasing::None, &mut o, &mut rng())
+            .unwrap();
+        let expected = c
+            .render(
+                &test_world(),
+                5,
+                true,
+                AntiAliasing::None,
+                &mut o,
+                &mut rng(),
+            )
+            .unwrap();
+
+        assert_approx_eq!(image.get_pixel(5, 5), expected.get_pixel(5, 5));
+    }
+
+    #[test]
+    fn culling_objects_the_camera_can_not_see() {
+        let mut w = World::new();
+        w.add_light(Light::new_point(
+            Point::new(0.0, 10.0, -20.0),
+            Colour::white(),
+        ));
+        w.add_object(Object::plane_builder().build());
+        w.add_object(
+            Object::sphere_builder()
+                .transformation(
+                    Transformation::new().translate(0.0, 5.0, -10.0),
+                )
+                .build(),
+        );
+
+        let from = Point::new(0.0, 1.0, -5.0);
+
+        let c = Camera::new(
+            100,
+            50,
+            Angle(FRAC_PI_2),
+            Transformation::view_transformation(
+                from,
+                Point::origin(),
+                Vector::y_axis(),
+            ),
+        );
+
+        let behind =
+            Ray::new(from, (Point::new(0.0, 5.0, -10.0) - from).normalise());
+        let floor = Ray::new(from, (Point::origin() - from).normalise());
+
+        assert_approx_eq!(
+            w.matte_at_within(&behind, 0.0, f64::INFINITY, &mut rng()),
+            Colour::white()
+        );
+        let shadowed = w.colour_at(&floor, 5, &mut rng());
+
+        w.prepare_for_camera(&c);
+
+        // The sphere is behind the camera so primary rays never hit it, but
+        // it still shadows the floor in front of the camera.
+        assert_approx_eq!(
+            w.matte_at_within(&behind, 0.0, f64::INFINITY, &mut rng()),
+            Colour::black()
+        );
+        assert!(w.is_shadowed(&Point::new(0.0, 10.0, -20.0), &Point::origin()));
+        assert_approx_eq!(w.colour_at(&floor, 5, &mut rng()), shadowed);
+        assert_approx_ne!(
+            shadowed,
+            World::new().colour_at(&floor, 5, &mut rng())
+        );
+
+        w.add_object(Object::test_builder().build());
+
+        assert_approx_eq!(
+            w.matte_at_within(&behind, 0.0, f64::INFINITY, &mut rng()),
+            Colour::white()
+        );
+    }
+
+    /// A plane at y = 0 where, as if from rounding errors, rays leaving the
+    /// surface upwards hit it again just above where they started.
+    #[derive(Debug)]
+    struct RoughPlane;
+
+    impl Intersectable for RoughPlane {
+        fn intersect(&self, ray: &Ray) -> Option<TList> {
+            if approx_eq!(ray.direction.y, 0.0) {
+                return None;
+            }
+
+            let mut list =
+                TList::from(TValues::new(-ray.origin.y / ray.direction.y));
+
+            if ray.direction.y > 0.0 {
+                list.push(TValues::new(
+                    (0.000_000_1 - ray.origin.y) / ray.direction.y,
+                ));
+            }
+
+            Some(list)
+        }
+
+        fn normal_at(
+            &self,
+            _point: &Point,
+            _intersection: &Intersection,
+        ) -> Vector {
+            Vector::y_axis()
+        }
+    }
+
+    impl Bounded for RoughPlane {
+        fn bounding_box(&self) -> BoundingBox {
+            BoundingBox::new(
+                Point::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+                Point::new(f64::INFINITY, 0.000_000_1, f64::INFINITY),
+            )
+        }
+    }
+
+    #[test]
+    fn ignoring_secondary_hits_on_the_originating_object() {
+        let world = |mut plane: Object| {
+            plane
+                .replace_material(&Material::builder().reflective(0.5).build());
+
+            let mut w = World::new();
+            w.add_light(Light::new_point(
+                Point::new(0.0, 10.0, 0.0),
+                Colour::white(),
+            ));
+            w.add_object(plane);
+
+            w
+        };
+
+        let r = Ray::new(
+            Point::new(0.0, 1.0, -1.0),
+            Vector::new(0.0, -1.0, 1.0).normalise(),
+        );
+
+        let expected =
+            world(Object::plane_builder().casts_shadow(false).build())
+                .colour_at(&r, 5, &mut rng());
+
+        // The reflected ray hits the plane it left again straight away, the
+        // plane casts no shadows so shadow rays do not hit it in the same way.
+        let mut w = world(
+            Object::custom_builder(RoughPlane).casts_shadow(false).build(),
+        );
+        assert_approx_ne!(w.colour_at(&r, 5, &mut rng()), expected);
+
+        w.set_self_intersection(Some(0.000_1));
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), expected);
+
+        // Hits on the same object further away are still seen, here reflecting
+        // around the inside of a sphere.
+        let mut w = World::new();
+        w.add_light(Light::new_point(Point::origin(), Colour::white()));
+        w.add_object(
+            Object::sphere_builder()
+                .material(Material::builder().reflective(0.5).build())
+                .build(),
+        );
+
+        let r = Ray::new(Point::origin(), Vector::z_axis());
+
+        let inside = w.colour_at(&r, 5, &mut rng());
+
+        w.set_self_intersection(Some(0.000_1));
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), inside);
+
+        w.objects[0].replace_material(&Material::default());
+        assert_approx_ne!(w.colour_at(&r, 5, &mut rng()), inside);
+    }
+
+    #[test]
+    fn ignoring_nearby_hits_keeps_the_refractive_indices_of_a_hit() {
+        let mut w = World::new();
+        w.add_light(Light::new_point(
+            Point::new(-10.0, 10.0, -10.0),
+            Colour::white(),
+        ));
+        w.add_object(
+            Object::sphere_builder()
+                .material(
+                    Material::builder()
+                        .transparency(1.0)
+                        .refractive_index(1.5)
+                        .build(),
+                )
+                .build(),
+        );
+        w.add_object(
+            Object::plane_builder()
+                .transformation(
+                    Transformation::new()
+                        .rotate_x(Angle(FRAC_PI_2))
+                        .translate(0.0, 0.0, 5.0),
+                )
+                .material(
+                    Material::builder()
+                        .pattern(
+                            Pattern::gradient_builder(
+                                Colour::white().into(),
+                                Colour::blue().into(),
+                            )
+                            .build(),
+                        )
+                        .build(),
+                )
+                .build(),
+        );
+
+        // The refracted ray starts just inside the sphere, skipping the hit
+        // where it entered must not lose track of being inside the glass.
+        let r = Ray::new(
+            Point::new(0.0, 0.3, -5.0),
+            Vector::new(0.1, 0.0, 1.0).normalise(),
+        );
+
+        let expected = w.colour_at(&r, 5, &mut rng());
+
+        w.set_self_intersection(Some(0.000_1));
+        assert_approx_eq!(w.colour_at(&r, 5, &mut rng()), expected);
+    }
+}
@@ -0,0 +1,265 @@
+use super::{basis, World};
+use crate::{
+    light::Directional,
+    math::{float::approx_eq, Point, Ray, Vector},
+    Bounded, BoundingBox,
+};
+
+/// The number of texels left around the edge of the map, points that land in
+/// them fall back to tracing a shadow ray.
+const BORDER: u32 = 2;
+
+/// A `ShadowMap` holds the depth of the first shadow casting surface seen
+/// along a grid of parallel rays from a directional light, covering the
+/// bounds of all the finite objects in a `World`. Checking a point against
+/// the map avoids tracing a shadow ray, but near the edges of shadows the
+/// answer is unreliable so no answer is given and a ray must be traced.
+#[derive(Clone, Debug)]
+pub struct ShadowMap {
+    position: Point,
+    direction: Vector,
+    u: Vector,
+    v: Vector,
+    u_minimum: f64,
+    v_minimum: f64,
+    texel_size: f64,
+    resolution: u32,
+    depths: Vec<f64>,
+}
+
+impl ShadowMap {
+    /// Render the depths seen by the light over the world, returns `None` if
+    /// the world has no objects with finite bounds.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the resolution is too small to leave a border around the
+    /// edge of the map.
+    #[must_use]
+    pub fn new(
+        world: &World,
+        light: &Directional,
+        resolution: u32,
+    ) -> Option<Self> {
+        assert!(
+            resolution > 2 * BORDER,
+            "Shadow map resolution must be greater than {}",
+            2 * BORDER
+        );
+
+        let direction = light.direction();
+        let (u, v) = basis(&direction);
+
+        let corners: Vec<_> = world
+            .objects
+            .iter()
+            .map(Bounded::bounding_box)
+            .filter(BoundingBox::is_finite)
+            .flat_map(|bounding_box| bounding_box.corners())
+            .map(|corner| corner - Point::origin())
+            .collect();
+
+        if corners.is_empty() {
+            return None;
+        }
+
+        let range = |axis: &Vector| {
+            corners.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |acc, c| {
+                let value = c.dot(axis);
+
+                (acc.0.min(value), acc.1.max(value))
+            })
+        };
+
+        let (u_minimum, u_maximum) = range(&u);
+        let (v_minimum, v_maximum) = range(&v);
+
+        let texel_size = ((u_maximum - u_minimum).max(v_maximum - v_minimum)
+            / f64::from(resolution - 2 * BORDER))
+        .max(f64::EPSILON);
+
+        let u_minimum = u_minimum - texel_size * f64::from(BORDER);
+        let v_minimum = v_minimum - texel_size * f64::from(BORDER);
+
+        let position = light.position();
+        let start = (position - Point::origin()).dot(&direction);
+
+        let mut depths = Vec::new();
+
+        for y in 0..resolution {
+            for x in 0..resolution {
+                let origin = Point::origin()
+                    + u * (u_minimum + (f64::from(x) + 0.5) * texel_size)
+                    + v * (v_minimum + (f64::from(y) + 0.5) * texel_size)
+                    + direction * start;
+
+                let depth = world
+                    .intersect(&Ray::new(origin, direction), false)
+                    .and_then(|list| {
+                        list.iter()
+                            .find(|intersection| {
+                                intersection.object.casts_shadow()
+                                    && intersection.t > 0.0
+                            })
+                            .map(|intersection| start + intersection.t)
+                    })
+                    .unwrap_or(f64::INFINITY);
+
+                depths.push(depth);
+            }
+        }
+
+        Some(Self {
+            position,
+            direction,
+            u,
+            v,
+            u_minimum,
+            v_minimum,
+            texel_size,
+            resolution,
+            depths,
+        })
+    }
+
+    /// Check if the map was rendered for a light at the given position.
+    #[must_use]
+    pub fn is_for(&self, light_position: &Point) -> bool {
+        approx_eq!(self.position, *light_position)
+    }
+
+    /// Check if the point is in shadow. `None` is returned when the point lies
+    /// outside the map or the texels around it disagree, as happens at the
+    /// edges of shadows and on surfaces nearly parallel to the light.
+    #[must_use]
+    pub fn is_shadowed(&self, point: &Point) -> Option<bool> {
+        let vector = *point - Point::origin();
+
+        let x =
+            ((vector.dot(&self.u) - self.u_minimum) / self.texel_size).floor();
+        let y =
+            ((vector.dot(&self.v) - self.v_minimum) / self.texel_size).floor();
+
+        let limit = f64::from(self.resolution - 1);
+
+        if !(1.0..limit).contains(&x) || !(1.0..limit).contains(&y) {
+            return None;
+        }
+
+        // Allow for the surface itself being seen by neighbouring texels at a
+        // slightly different depth.
+        let depth = vector.dot(&self.direction) - 2.0 * self.texel_size;
+
+        // Both x and y are within the map so the conversions are valid.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let (x, y) = (x as usize, y as usize);
+        let resolution = self.resolution as usize;
+
+        let mut occluded = (y - 1..=y + 1).flat_map(|y| {
+            (x - 1..=x + 1)
+                .map(move |x| self.depths[y * resolution + x] < depth)
+        });
+
+        let first = occluded.next()?;
+
+        if occluded.all(|other| other == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{
+        light::Lightable, math::Transformation, Colour, Light, Object,
+    };
+
+    fn occluder_over_plane() -> (World, Directional) {
+        let mut w = World::new();
+
+        w.add_object(Object::plane_builder().build());
+        w.add_object(
+            Object::cube_builder()
+                .transformation(
+                    Transformation::new()
+                        .scale(1.0, 0.2, 1.0)
+                        .translate(0.0, 2.0, 0.0),
+                )
+                .build(),
+        );
+
+        let d = Directional::new(Vector::new(1.0, -2.0, 0.5), Colour::white());
+
+        w.add_light(Light::Directional(d));
+
+        (w, d)
+    }
+
+    #[test]
+    fn creating_a_shadow_map() {
+        let (w, d) = occluder_over_plane();
+
+        let m = ShadowMap::new(&w, &d, 32).unwrap();
+
+        assert_eq!(m.depths.len(), 32 * 32);
+        assert!(m.is_for(&d.position()));
+        assert!(!m.is_for(&Point::origin()));
+
+        assert!(m.depths.iter().any(|depth| depth.is_finite()));
+
+        assert!(ShadowMap::new(&World::new(), &d, 32).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Shadow map resolution must be greater than 4")]
+    fn a_shadow_map_needs_a_border() {
+        let (w, d) = occluder_over_plane();
+
+        let _ = ShadowMap::new(&w, &d, 4);
+    }
+
+    #[test]
+    fn shadow_maps_agree_with_traced_shadows() {
+        let (w, d) = occluder_over_plane();
+
+        let m = ShadowMap::new(&w, &d, 128).unwrap();
+
+        let mut r = Xoshiro256PlusPlus::seed_from_u64(0);
+
+        let (mut answered, mut disagree) = (0, 0);
+
+        for _ in 0..2000 {
+            let point =
+                Point::new(r.gen_range(-0.5..2.5), 0.0, r.gen_range(-1.0..2.0));
+
+            let traced = d
+                .positions(&mut r)
+                .iter()
+                .any(|position| w.is_shadowed(position, &point));
+
+            if let Some(shadowed) = m.is_shadowed(&point) {
+                answered += 1;
+
+                if shadowed != traced {
+                    disagree += 1;
+                }
+            }
+        }
+
+        assert!(answered > 1000);
+        assert!(disagree * 100 < answered);
+
+        // Points under the middle of the occluder, beside it and outside the
+        // map.
+        assert_eq!(m.is_shadowed(&Point::new(1.0, 0.0, 0.25)), Some(true));
+        assert_eq!(m.is_shadowed(&Point::new(-0.2, 0.0, 0.25)), Some(false));
+        assert!(m.is_shadowed(&Point::new(-3.0, 0.0, -3.0)).is_none());
+    }
+}